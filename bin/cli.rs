@@ -1,16 +1,15 @@
 //! CLI tool for deploying and interacting with Casper Predict contracts.
 
 use casper_predict::{
-    factory::{FactoryInitArgs, MarketFactory},
+    factory::{FactoryInitArgs, MarketFactory, MarketFactoryInitArgs},
+    market::Market,
+    token::OutcomeToken,
     vault::{Vault, VaultInitArgs},
 };
 use odra::casper_types::U256;
-use odra::host::{HostEnv, HostRef};
-use odra_cli::{
-    deploy::DeployScript,
-    scenario::{Args, Error, Scenario, ScenarioMetadata},
-    CommandArg, DeployedContractsContainer, OdraCli,
-};
+use odra::host::HostEnv;
+use odra::prelude::Addressable;
+use odra_cli::{deploy::DeployScript, DeployedContractsContainer, DeployerExt, OdraCli};
 
 /// Deploys the Vault and MarketFactory contracts and sets up permissions.
 pub struct DeploySystemScript;
@@ -34,22 +33,24 @@ impl DeployScript for DeploySystemScript {
         println!("Vault deployed at: {:?}", vault.address());
 
         println!("Deploying MarketFactory...");
-        let factory_args = FactoryInitArgs {
-            admin,
-            vault_contract: vault.address(),
-            platform_fee_bps: 200,                // 2%
-            min_market_duration: 3600,            // 1 hour
-            max_market_duration: 2592000,         // 30 days
-            min_initial_liquidity: U256::from(1_000_000_000u64), // 1 CSPR
+        let factory_args = MarketFactoryInitArgs {
+            args: FactoryInitArgs {
+                admin,
+                vault_contract: vault.address(),
+                platform_fee_bps: 200,                // 2%
+                min_market_duration: 3600,            // 1 hour
+                max_market_duration: 2592000,         // 30 days
+                min_initial_liquidity: U256::from(1_000_000_000u64), // 1 CSPR
+            },
         };
         // Gas limit: 200 CSPR
         let factory = MarketFactory::load_or_deploy(env, factory_args, container, 200_000_000_000)?;
         println!("MarketFactory deployed at: {:?}", factory.address());
 
         println!("Title: Authorizing Factory in Vault...");
-        // Set gas for the transaction
+        // Lets the factory seed markets and authorize the ones it registers
         env.set_gas(5_000_000_000); // 5 CSPR
-        vault.authorize_market(*factory.address());
+        vault.set_factory(factory.address());
         println!("Factory authorized.");
 
         println!("System deployment complete!");
@@ -63,6 +64,8 @@ pub fn main() {
         .deploy(DeploySystemScript)
         .contract::<Vault>()
         .contract::<MarketFactory>()
+        .contract::<Market>()
+        .contract::<OutcomeToken>()
         .build()
         .run();
 }
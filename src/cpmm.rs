@@ -0,0 +1,215 @@
+//! Constant-product market maker over complete sets of outcome shares.
+//!
+//! A complete set holds one share of every outcome and costs one mote:
+//! exactly one of its shares pays out one mote at resolution. Anyone can
+//! mint sets for CSPR and redeem them back at any time before resolution.
+//!
+//! The pool holds a balance of every outcome and trades them keeping the
+//! product of its balances constant, as in the Gnosis fixed product market
+//! maker (FPMM):
+//! - buying outcome `i` for `x` motes mints `x` sets into the pool and takes
+//!   out as many `i` shares as keep the product unchanged;
+//! - selling `i` shares for `r` motes puts them into the pool and burns `r`
+//!   sets out of it.
+//!
+//! The price of outcome `i` is `(1 / b_i) / sum_j (1 / b_j)` for pool
+//! balances `b`, so prices are probabilities summing to one. Rounding always
+//! favours the pool.
+
+use crate::curves::{u256_to_u512, u512_to_u256};
+use crate::types::implied_probabilities_bps;
+use odra::casper_types::{U256, U512};
+use odra::prelude::*;
+
+/// Fixed-point one (1e18)
+const WAD: u64 = 1_000_000_000_000_000_000;
+
+/// Motes in one CSPR, the payout prices are quoted for
+const ONE_CSPR: u64 = 1_000_000_000;
+
+/// `numerator / denominator`, rounded up.
+fn ceil_div(numerator: U512, denominator: U512) -> U512 {
+    (numerator + denominator - U512::one()) / denominator
+}
+
+/// A complete-set pool with one balance per outcome, indexed by outcome ID.
+pub struct CompleteSetPool {
+    pub balances: Vec<U256>,
+}
+
+impl CompleteSetPool {
+    /// A pool funded with `sets` complete sets.
+    pub fn seeded(outcome_count: u64, sets: U256) -> Self {
+        Self { balances: vec![sets; outcome_count as usize] }
+    }
+
+    /// Probability of an outcome in fixed point.
+    pub fn probability(&self, outcome_id: usize) -> U256 {
+        let wad = U256::from(WAD);
+        let inverse = |balance: &U256| wad * wad / (*balance).max(U256::one());
+        let total = self.balances.iter().fold(U256::zero(), |sum, balance| sum + inverse(balance));
+        inverse(&self.balances[outcome_id]) * wad / total
+    }
+
+    /// Price of one CSPR's worth of an outcome's shares, in motes.
+    pub fn price(&self, outcome_id: usize) -> U256 {
+        self.probability(outcome_id) * U256::from(ONE_CSPR) / U256::from(WAD)
+    }
+
+    /// Probabilities of all outcomes in basis points, summing to 10_000.
+    pub fn probabilities_bps(&self) -> Vec<u64> {
+        let probabilities: Vec<U256> =
+            (0..self.balances.len()).map(|outcome_id| self.probability(outcome_id)).collect();
+        implied_probabilities_bps(&probabilities)
+    }
+
+    /// Shares of an outcome bought by investing `amount` motes.
+    pub fn shares_for_cost(&self, outcome_id: usize, amount: U256) -> U256 {
+        if amount.is_zero() {
+            return U256::zero();
+        }
+        let invested = u256_to_u512(amount);
+        let balance = u256_to_u512(self.balances[outcome_id]);
+
+        // Pool balance of the outcome that keeps the product constant
+        let mut ending = balance * U512::from(WAD);
+        for (other, other_balance) in self.balances.iter().enumerate() {
+            if other != outcome_id {
+                let other_balance = u256_to_u512(*other_balance);
+                ending = ceil_div(ending * other_balance, other_balance + invested);
+            }
+        }
+        u512_to_u256((balance + invested).saturating_sub(ceil_div(ending, U512::from(WAD))))
+    }
+
+    /// Smallest investment that buys at least `shares` of an outcome.
+    ///
+    /// Every mote invested returns at least one share, so the answer is
+    /// found by bisection between zero and `shares`.
+    pub fn cost_to_buy(&self, outcome_id: usize, shares: U256) -> U256 {
+        let (mut low, mut high) = (U256::zero(), shares);
+        while low < high {
+            let mid = low + (high - low) / U256::from(2u64);
+            if self.shares_for_cost(outcome_id, mid) >= shares {
+                high = mid;
+            } else {
+                low = mid + U256::one();
+            }
+        }
+        high
+    }
+
+    /// Shares of an outcome the pool needs to pay out `revenue` motes, or
+    /// `None` if the pool cannot burn that many sets.
+    fn shares_to_sell(&self, outcome_id: usize, revenue: U256) -> Option<U256> {
+        let returned = u256_to_u512(revenue);
+        let balance = u256_to_u512(self.balances[outcome_id]);
+
+        let mut ending = balance * U512::from(WAD);
+        for (other, other_balance) in self.balances.iter().enumerate() {
+            if other != outcome_id {
+                let other_balance = u256_to_u512(*other_balance);
+                if other_balance <= returned {
+                    return None;
+                }
+                ending = ceil_div(ending * other_balance, other_balance - returned);
+            }
+        }
+        Some(u512_to_u256((returned + ceil_div(ending, U512::from(WAD))).saturating_sub(balance)))
+    }
+
+    /// Largest revenue in motes from selling `shares` of an outcome.
+    ///
+    /// Selling never returns more than one mote per share, so the answer
+    /// is found by bisection between zero and `shares`.
+    pub fn revenue_from_sell(&self, outcome_id: usize, shares: U256) -> U256 {
+        let (mut low, mut high) = (U256::zero(), shares);
+        while low < high {
+            let mid = high - (high - low) / U256::from(2u64);
+            if self.shares_to_sell(outcome_id, mid).is_some_and(|needed| needed <= shares) {
+                low = mid;
+            } else {
+                high = mid - U256::one();
+            }
+        }
+        low
+    }
+
+    /// Records a purchase of `shares` of an outcome for `amount` motes.
+    pub fn buy(&mut self, outcome_id: usize, amount: U256, shares: U256) {
+        for balance in self.balances.iter_mut() {
+            *balance = balance.saturating_add(amount);
+        }
+        self.balances[outcome_id] = self.balances[outcome_id].saturating_sub(shares);
+    }
+
+    /// Records a sale of `shares` of an outcome for `revenue` motes.
+    pub fn sell(&mut self, outcome_id: usize, shares: U256, revenue: U256) {
+        self.balances[outcome_id] = self.balances[outcome_id].saturating_add(shares);
+        for balance in self.balances.iter_mut() {
+            *balance = balance.saturating_sub(revenue);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Product of all pool balances.
+    fn product(pool: &CompleteSetPool) -> U512 {
+        pool.balances.iter().fold(U512::one(), |product, balance| product * u256_to_u512(*balance))
+    }
+
+    #[test]
+    fn test_prices_are_probabilities() {
+        let pool = CompleteSetPool::seeded(4, U256::from(ONE_CSPR));
+        assert_eq!(pool.probabilities_bps(), vec![2_500; 4]);
+        assert_eq!(pool.price(0), U256::from(ONE_CSPR / 4));
+
+        // An outcome scarce in the pool is the likely one
+        let pool = CompleteSetPool { balances: vec![U256::from(100u64), U256::from(300u64)] };
+        assert_eq!(pool.probabilities_bps(), vec![7_500, 2_500]);
+    }
+
+    #[test]
+    fn test_trades_keep_the_product_constant() {
+        let mut pool = CompleteSetPool::seeded(3, U256::from(10 * ONE_CSPR));
+        let invariant = product(&pool);
+
+        let amount = U256::from(3 * ONE_CSPR);
+        let shares = pool.shares_for_cost(1, amount);
+        assert!(shares > amount);
+        assert_eq!(pool.cost_to_buy(1, shares), amount);
+        pool.buy(1, amount, shares);
+        assert!(product(&pool) >= invariant);
+        assert!(pool.probability(1) > pool.probability(0));
+
+        // Selling the shares back returns slightly less than was paid
+        let revenue = pool.revenue_from_sell(1, shares);
+        assert!(revenue < amount && revenue + 2 >= amount);
+        pool.sell(1, shares, revenue);
+        assert!(product(&pool) >= invariant);
+    }
+
+    #[test]
+    fn test_quotes_are_tight() {
+        let pool = CompleteSetPool {
+            balances: vec![U256::from(5_000_000u64), U256::from(80_000_000u64), U256::from(1_234_567u64)],
+        };
+        for outcome_id in 0..3 {
+            for amount in [1u64, 999, 1_000_000, 50_000_000] {
+                let amount = U256::from(amount);
+                let shares = pool.shares_for_cost(outcome_id, amount);
+                let cost = pool.cost_to_buy(outcome_id, shares);
+                assert!(cost <= amount);
+                assert!(pool.shares_for_cost(outcome_id, cost) >= shares);
+                assert!(cost.is_zero() || pool.shares_for_cost(outcome_id, cost - 1) < shares);
+
+                let revenue = pool.revenue_from_sell(outcome_id, amount);
+                assert!(pool.shares_to_sell(outcome_id, revenue).unwrap() <= amount);
+                assert!(pool.shares_to_sell(outcome_id, revenue + 1).is_none_or(|needed| needed > amount));
+            }
+        }
+    }
+}
@@ -0,0 +1,428 @@
+//! Bonding curve families for market pricing.
+//!
+//! Every curve is defined by its cumulative cost `F(x)`, the total price of
+//! the first `x` shares. Buying `n` shares at supply `s` costs
+//! `F(s + n) - F(s)` and selling them back returns the same amount, so
+//! prices depend only on supply and never on the path that led to it.
+//!
+//! [`BondingCurveParams`](crate::types::BondingCurveParams) selects one of
+//! these curves through its [`CurveKind`](crate::types::CurveKind).
+
+use odra::casper_types::{U256, U512};
+
+/// Fixed-point scale for fractional shares in square roots (1e9)
+const SHARE_SCALE: u64 = 1_000_000_000;
+
+/// Scale of an exponential growth rate (1e9 = 100% per share)
+pub const GROWTH_RATE_SCALE: u64 = 1_000_000_000;
+
+/// Fixed-point one for exponential powers (1e18)
+const FIXED_ONE: u64 = 1_000_000_000_000_000_000;
+
+/// Largest number of shares a budget search will consider (2^128)
+fn max_search_shares() -> U256 {
+    U256::one() << 128
+}
+
+/// Convert U512 to U256, saturating at `U256::MAX`
+pub(crate) fn u512_to_u256(value: U512) -> U256 {
+    if value > u256_to_u512(U256::MAX) {
+        return U256::MAX;
+    }
+    let mut bytes = [0u8; 32];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = value.byte(i);
+    }
+    U256::from_little_endian(&bytes)
+}
+
+/// Convert U256 to U512
+pub(crate) fn u256_to_u512(value: U256) -> U512 {
+    let mut bytes = [0u8; 32];
+    value.to_little_endian(&mut bytes);
+    U512::from_little_endian(&bytes)
+}
+
+/// `sqrt(value) * SHARE_SCALE`, rounded down. `None` on overflow.
+fn scaled_sqrt(value: U512) -> Option<U512> {
+    let scale = U512::from(SHARE_SCALE);
+    Some(value.checked_mul(scale)?.checked_mul(scale)?.integer_sqrt())
+}
+
+/// Common pricing interface of all bonding curves.
+///
+/// Implementors provide the marginal price and the cumulative cost; trading
+/// quantities are derived from the cumulative cost. A cumulative cost of
+/// `U256::MAX` marks supply the curve can no longer price, and buying into
+/// it costs `U256::MAX`.
+pub trait BondingCurve {
+    /// Marginal price of a share at `supply`.
+    fn price_at_supply(&self, supply: U256) -> U256;
+
+    /// Total cost of the first `supply` shares.
+    fn cumulative_cost(&self, supply: U256) -> U256;
+
+    /// Cost to buy `shares` starting from `current_supply`.
+    fn cost_to_buy(&self, current_supply: U256, shares: U256) -> U256 {
+        if shares.is_zero() {
+            return U256::zero();
+        }
+        let end = self.cumulative_cost(current_supply.saturating_add(shares));
+        if end == U256::MAX {
+            return U256::MAX;
+        }
+        end.saturating_sub(self.cumulative_cost(current_supply))
+    }
+
+    /// Revenue from selling `shares` starting from `current_supply`.
+    fn revenue_from_sell(&self, current_supply: U256, shares: U256) -> U256 {
+        if shares.is_zero() || current_supply < shares {
+            return U256::zero();
+        }
+        self.cost_to_buy(current_supply.saturating_sub(shares), shares)
+    }
+
+    /// Largest number of shares that cost at most `max_cost`.
+    ///
+    /// Doubles an upper bound until it is unaffordable, then bisects.
+    fn shares_for_cost(&self, current_supply: U256, max_cost: U256) -> U256 {
+        if max_cost.is_zero() {
+            return U256::zero();
+        }
+
+        let limit = max_search_shares();
+        let mut high = U256::one();
+        while self.cost_to_buy(current_supply, high) <= max_cost {
+            if high >= limit {
+                return limit;
+            }
+            high = high.saturating_mul(U256::from(2u64));
+        }
+
+        // cost(low) fits the budget, cost(high) does not
+        let mut low = high / U256::from(2u64);
+        while high - low > U256::one() {
+            let mid = low + (high - low) / U256::from(2u64);
+            if self.cost_to_buy(current_supply, mid) <= max_cost {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+}
+
+/// `price(x) = initial_price + slope * x`, charged per whole share.
+pub struct LinearCurve {
+    pub initial_price: U256,
+    pub slope: U256,
+}
+
+impl BondingCurve for LinearCurve {
+    fn price_at_supply(&self, supply: U256) -> U256 {
+        self.initial_price.saturating_add(self.slope.saturating_mul(supply))
+    }
+
+    /// F(x) = x * initial_price + slope * x * (x + 1) / 2
+    fn cumulative_cost(&self, supply: U256) -> U256 {
+        self.cost_to_buy(U256::zero(), supply)
+    }
+
+    /// cost = n * initial_price + k * [n*(2*current_supply + n + 1)/2]
+    fn cost_to_buy(&self, current_supply: U256, shares: U256) -> U256 {
+        if shares.is_zero() {
+            return U256::zero();
+        }
+
+        // Base cost: n * initial_price
+        let base_cost = shares.saturating_mul(self.initial_price);
+
+        // Premium cost from bonding curve:
+        // Sum of (k * i) for i from current_supply+1 to current_supply+n
+        // = k * sum(i) = k * [n * (2*current_supply + n + 1) / 2]
+        let two = U256::from(2u64);
+        let sum_factor = shares.saturating_mul(
+            two.saturating_mul(current_supply)
+                .saturating_add(shares)
+                .saturating_add(U256::one())
+        ) / two;
+        let premium = self.slope.saturating_mul(sum_factor);
+
+        base_cost.saturating_add(premium)
+    }
+
+    /// Solves `k*n^2 + (2*initial_price + k*(2*current_supply + 1))*n <= 2*max_cost`
+    /// with an integer square root, then corrects the rounding by one share.
+    fn shares_for_cost(&self, current_supply: U256, max_cost: U256) -> U256 {
+        if max_cost.is_zero() {
+            return U256::zero();
+        }
+
+        let mut shares = if self.slope.is_zero() {
+            // Flat price; free shares cannot be priced
+            if self.initial_price.is_zero() {
+                return U256::zero();
+            }
+            max_cost / self.initial_price
+        } else {
+            // Work in U512 so the discriminant cannot overflow
+            let two = U512::from(2u64);
+            let k = u256_to_u512(self.slope);
+            let b = two
+                .saturating_mul(u256_to_u512(self.initial_price))
+                .saturating_add(k.saturating_mul(
+                    two.saturating_mul(u256_to_u512(current_supply)).saturating_add(U512::one()),
+                ));
+            let discriminant = b
+                .saturating_mul(b)
+                .saturating_add(U512::from(8u64).saturating_mul(k).saturating_mul(u256_to_u512(max_cost)));
+            let root = discriminant.integer_sqrt();
+            u512_to_u256(root.saturating_sub(b) / two.saturating_mul(k))
+        };
+
+        // The floored square root can be one share off in either direction
+        if self.cost_to_buy(current_supply, shares.saturating_add(U256::one())) <= max_cost {
+            shares = shares.saturating_add(U256::one());
+        }
+        if !shares.is_zero() && self.cost_to_buy(current_supply, shares) > max_cost {
+            shares = shares.saturating_sub(U256::one());
+        }
+        shares
+    }
+}
+
+/// `price(x) = initial_price * (1 + growth_rate / GROWTH_RATE_SCALE)^x`,
+/// charged per whole share.
+pub struct ExponentialCurve {
+    pub initial_price: U256,
+    pub growth_rate: U256,
+}
+
+impl ExponentialCurve {
+    /// Growth factor per share as a fixed-point number.
+    fn growth_factor(&self) -> U512 {
+        let per_unit = U512::from(FIXED_ONE / GROWTH_RATE_SCALE);
+        U512::from(FIXED_ONE).saturating_add(u256_to_u512(self.growth_rate).saturating_mul(per_unit))
+    }
+
+    /// `growth_factor^exponent` in fixed point, or `None` once it exceeds
+    /// 2^250 (far beyond any price representable in U256).
+    fn growth_pow(&self, exponent: U256) -> Option<U512> {
+        let one = U512::from(FIXED_ONE);
+        let ceiling = U512::one() << 250;
+        let mut base = self.growth_factor();
+        let mut result = one;
+        let mut exponent = exponent;
+        while !exponent.is_zero() {
+            if exponent.bit(0) {
+                result = result * base / one;
+                if result > ceiling {
+                    return None;
+                }
+            }
+            exponent >>= 1;
+            if !exponent.is_zero() {
+                base = base * base / one;
+                if base > ceiling {
+                    return None;
+                }
+            }
+        }
+        Some(result)
+    }
+}
+
+impl BondingCurve for ExponentialCurve {
+    fn price_at_supply(&self, supply: U256) -> U256 {
+        self.growth_pow(supply)
+            .and_then(|power| u256_to_u512(self.initial_price).checked_mul(power))
+            .map_or(U256::MAX, |price| u512_to_u256(price / U512::from(FIXED_ONE)))
+    }
+
+    /// F(x) = initial_price * g * (g^x - 1) / (g - 1)
+    fn cumulative_cost(&self, supply: U256) -> U256 {
+        if self.growth_rate.is_zero() {
+            return supply.saturating_mul(self.initial_price);
+        }
+        let one = U512::from(FIXED_ONE);
+        let factor = self.growth_factor();
+        let Some(power) = self.growth_pow(supply) else {
+            return U256::MAX;
+        };
+        // Geometric series in fixed point
+        let series = power.saturating_sub(one) * factor / (factor - one);
+        u256_to_u512(self.initial_price)
+            .checked_mul(series)
+            .map_or(U256::MAX, |cost| u512_to_u256(cost / one))
+    }
+}
+
+/// `price(x) = initial_price + k * sqrt(x)`, priced continuously.
+pub struct SqrtCurve {
+    pub initial_price: U256,
+    pub k: U256,
+}
+
+impl BondingCurve for SqrtCurve {
+    fn price_at_supply(&self, supply: U256) -> U256 {
+        let root = scaled_sqrt(u256_to_u512(supply)).unwrap_or(U512::MAX);
+        let premium = u256_to_u512(self.k).saturating_mul(root) / U512::from(SHARE_SCALE);
+        self.initial_price.saturating_add(u512_to_u256(premium))
+    }
+
+    /// F(x) = initial_price * x + (2/3) * k * x^(3/2)
+    fn cumulative_cost(&self, supply: U256) -> U256 {
+        let x = u256_to_u512(supply);
+        let Some(root) = x.checked_mul(x).and_then(|square| square.checked_mul(x)).and_then(scaled_sqrt) else {
+            return U256::MAX;
+        };
+        let premium = u256_to_u512(self.k)
+            .checked_mul(root)
+            .map(|scaled| scaled * U512::from(2u64) / U512::from(3 * SHARE_SCALE));
+        let base = u256_to_u512(self.initial_price).saturating_mul(x);
+        premium.map_or(U256::MAX, |premium| u512_to_u256(base.saturating_add(premium)))
+    }
+}
+
+/// Price rising from about `initial_price` to at most `max_price`, centred
+/// on `midpoint` with a transition `width` in shares, priced continuously.
+///
+/// Uses the algebraic sigmoid `1/2 * (1 + d / sqrt(d^2 + width^2))` with
+/// `d = x - midpoint`, whose integral needs only a square root.
+pub struct SigmoidCurve {
+    pub initial_price: U256,
+    pub max_price: U256,
+    pub midpoint: U256,
+    pub width: U256,
+}
+
+impl SigmoidCurve {
+    /// `(d + sqrt(d^2 + width^2)) * SHARE_SCALE` and the scaled root, with
+    /// `d = supply - midpoint`. Never negative.
+    fn offset_terms(&self, supply: U256) -> Option<(U512, U512)> {
+        let x = u256_to_u512(supply);
+        let midpoint = u256_to_u512(self.midpoint);
+        let width = u256_to_u512(self.width);
+        let distance = if x >= midpoint { x - midpoint } else { midpoint - x };
+        let radius = scaled_sqrt(distance.checked_mul(distance)?.checked_add(width.checked_mul(width)?)?)?;
+        let scaled_distance = distance.checked_mul(U512::from(SHARE_SCALE))?;
+        let term = if x >= midpoint {
+            radius.checked_add(scaled_distance)?
+        } else {
+            radius.saturating_sub(scaled_distance)
+        };
+        Some((term, radius))
+    }
+
+    fn price_range(&self) -> U512 {
+        u256_to_u512(self.max_price.saturating_sub(self.initial_price))
+    }
+}
+
+impl BondingCurve for SigmoidCurve {
+    fn price_at_supply(&self, supply: U256) -> U256 {
+        let Some((term, radius)) = self.offset_terms(supply) else {
+            return self.max_price;
+        };
+        let premium = if radius.is_zero() {
+            self.price_range() / U512::from(2u64)
+        } else {
+            self.price_range().saturating_mul(term) / (radius * U512::from(2u64))
+        };
+        self.initial_price.saturating_add(u512_to_u256(premium)).min(self.max_price)
+    }
+
+    /// F(x) = initial_price * x + range / 2 * (T(x) - T(0)),
+    /// T(x) = d + sqrt(d^2 + width^2)
+    fn cumulative_cost(&self, supply: U256) -> U256 {
+        let (Some((term, _)), Some((start, _))) = (self.offset_terms(supply), self.offset_terms(U256::zero())) else {
+            return U256::MAX;
+        };
+        let premium = self
+            .price_range()
+            .checked_mul(term.saturating_sub(start))
+            .map(|scaled| scaled / U512::from(2 * SHARE_SCALE));
+        let base = u256_to_u512(self.initial_price).saturating_mul(u256_to_u512(supply));
+        premium.map_or(U256::MAX, |premium| u512_to_u256(base.saturating_add(premium)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curves() -> Vec<Box<dyn BondingCurve>> {
+        vec![
+            Box::new(LinearCurve { initial_price: U256::from(10_000_000u64), slope: U256::from(1_000_000u64) }),
+            Box::new(ExponentialCurve {
+                initial_price: U256::from(10_000_000u64),
+                growth_rate: U256::from(10_000_000u64),
+            }),
+            Box::new(SqrtCurve { initial_price: U256::from(10_000_000u64), k: U256::from(1_000_000u64) }),
+            Box::new(SigmoidCurve {
+                initial_price: U256::from(10_000_000u64),
+                max_price: U256::from(1_000_000_000u64),
+                midpoint: U256::from(500u64),
+                width: U256::from(100u64),
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_curves_are_monotonic_and_path_independent() {
+        for curve in curves() {
+            let mut previous = curve.price_at_supply(U256::zero());
+            for supply in (1..2_000u64).step_by(37) {
+                let price = curve.price_at_supply(U256::from(supply));
+                assert!(price >= previous);
+                previous = price;
+            }
+
+            // Buying in two steps costs the same as buying at once
+            let supply = U256::from(123u64);
+            let whole = curve.cost_to_buy(supply, U256::from(300u64));
+            let split = curve.cost_to_buy(supply, U256::from(100u64))
+                + curve.cost_to_buy(supply + 100, U256::from(200u64));
+            assert_eq!(whole, split);
+            assert_eq!(curve.revenue_from_sell(supply + 300, U256::from(300u64)), whole);
+        }
+    }
+
+    #[test]
+    fn test_shares_for_cost_is_the_largest_affordable() {
+        for curve in curves() {
+            for (supply, budget) in [(0u64, 1_000_000_000u64), (250, 7_777_777_777), (900, 50_000_000_000)] {
+                let (supply, budget) = (U256::from(supply), U256::from(budget));
+                let shares = curve.shares_for_cost(supply, budget);
+                assert!(curve.cost_to_buy(supply, shares) <= budget);
+                assert!(curve.cost_to_buy(supply, shares + 1) > budget);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sigmoid_is_capped() {
+        let curve = SigmoidCurve {
+            initial_price: U256::from(100u64),
+            max_price: U256::from(1_000u64),
+            midpoint: U256::from(50u64),
+            width: U256::from(10u64),
+        };
+        assert_eq!(curve.price_at_supply(U256::from(50u64)), U256::from(550u64));
+        assert!(curve.price_at_supply(U256::zero()) < U256::from(110u64));
+        assert!(curve.price_at_supply(U256::from(1_000_000u64)) <= U256::from(1_000u64));
+        assert!(curve.price_at_supply(U256::from(1_000_000u64)) >= U256::from(999u64));
+    }
+
+    #[test]
+    fn test_exponential_overflow_is_unaffordable() {
+        let curve = ExponentialCurve {
+            initial_price: U256::from(10_000_000u64),
+            growth_rate: U256::from(GROWTH_RATE_SCALE),
+        };
+        // Doubling every share: 2^300 is beyond any budget
+        assert_eq!(curve.cost_to_buy(U256::zero(), U256::from(300u64)), U256::MAX);
+        assert!(curve.shares_for_cost(U256::zero(), U256::MAX - 1) < U256::from(300u64));
+    }
+}
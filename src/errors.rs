@@ -21,6 +21,14 @@ pub enum Error {
     NotAuthorizedMarket = 4,
     /// Caller is not the factory contract
     NotFactory = 5,
+    /// Caller is not the market creator
+    NotCreator = 6,
+    /// Caller is not the owner of the order
+    NotOrderOwner = 7,
+    /// Caller is not the token contract registered for the outcome
+    NotOutcomeToken = 8,
+    /// Caller is not a member of the market's resolver committee
+    NotCommitteeMember = 9,
 
     // =========================================================================
     // Market State Errors (10-19)
@@ -41,6 +49,10 @@ pub enum Error {
     MarketAlreadyEnded = 16,
     /// Market is not in the Cancelled state
     MarketNotCancelled = 17,
+    /// Operation requires a compound market
+    NotCompoundMarket = 18,
+    /// Operation requires a complete-set AMM market
+    NotCompleteSetMarket = 19,
 
     // =========================================================================
     // Trading Errors (20-29)
@@ -59,6 +71,10 @@ pub enum Error {
     InvalidShareAmount = 25,
     /// Minimum receive amount not met
     MinimumNotMet = 26,
+    /// Provider does not hold enough LP shares
+    InsufficientLiquidityShares = 27,
+    /// Spender is not allowed to transfer that many shares
+    InsufficientAllowance = 28,
 
     // =========================================================================
     // Vault Errors (30-39)
@@ -93,6 +109,12 @@ pub enum Error {
     VaultNotSet = 45,
     /// Fee percentage is too high
     FeeTooHigh = 46,
+    /// No market exists with the given ID
+    MarketNotFound = 47,
+    /// A contract has already been registered for this market
+    MarketAlreadyRegistered = 48,
+    /// Market contract does not match the registry entry
+    MarketMismatch = 49,
 
     // =========================================================================
     // Claim Errors (50-59)
@@ -125,4 +147,76 @@ pub enum Error {
     NotInitialized = 71,
     /// Invalid initialization parameters
     InvalidInitParams = 72,
+
+    // =========================================================================
+    // Oracle Errors (80-89)
+    // =========================================================================
+    /// TWAP window is empty or reaches past the recorded price history
+    InvalidTwapWindow = 80,
+    /// Market is not bound to an oracle
+    OracleNotSet = 81,
+    /// Oracle has not answered the market's query
+    OracleAnswerMissing = 82,
+    /// Oracle answer predates the market's end or is older than allowed
+    StaleOracleAnswer = 83,
+    /// Oracle is not on the factory's allow-list
+    OracleNotApproved = 84,
+
+    // =========================================================================
+    // Order Errors (90-99)
+    // =========================================================================
+    /// No order exists with the given ID
+    OrderNotFound = 90,
+    /// Order has already been filled or cancelled
+    OrderNotOpen = 91,
+    /// Order expiry is not in the future
+    InvalidOrderExpiry = 92,
+
+    // =========================================================================
+    // Resolution Errors (100-109)
+    // =========================================================================
+    /// An outcome has been proposed and awaits finality
+    ResolutionPending = 100,
+    /// Attached bond is below the required bond
+    InsufficientBond = 101,
+    /// No undisputed proposal is pending
+    NoProposal = 102,
+    /// The proposal can no longer be disputed
+    DisputeWindowClosed = 103,
+    /// The proposal can still be disputed
+    DisputeWindowOpen = 104,
+    /// No disputed proposal awaits arbitration
+    NotDisputed = 105,
+    /// Market has no resolver committee
+    NoResolverCommittee = 106,
+    /// Committee member has already voted
+    AlreadyVoted = 107,
+    /// Committee threshold is not a reachable majority or a member is listed twice
+    InvalidCommittee = 108,
+    /// Market does not accept proposed outcomes
+    OptimisticResolutionDisabled = 109,
+
+    // =========================================================================
+    // Scalar Market Errors (110-119)
+    // =========================================================================
+    /// Operation requires a scalar market
+    NotScalarMarket = 110,
+    /// Scalar markets resolve to a value, not an outcome
+    ScalarValueRequired = 111,
+    /// Scalar range is empty
+    InvalidScalarRange = 112,
+
+    // =========================================================================
+    // Outcome Token Errors (120-129)
+    // =========================================================================
+    /// A token contract has already been registered for the outcome
+    OutcomeTokenAlreadySet = 120,
+    /// Token contract does not represent this market's outcome
+    OutcomeTokenMismatch = 121,
+
+    // =========================================================================
+    // Market Registry Errors (130-139)
+    // =========================================================================
+    /// Market was rejected before registration
+    MarketRejected = 130,
 }
@@ -6,6 +6,8 @@
 use odra::prelude::*;
 use odra::casper_types::U256;
 
+use crate::types::OrderSide;
+
 // =============================================================================
 // MarketFactory Events
 // =============================================================================
@@ -15,7 +17,7 @@ use odra::casper_types::U256;
 pub struct MarketCreated {
     /// Unique identifier for the market
     pub market_id: u64,
-    /// Type of market (0=Binary, 1=MultipleChoice, 2=Compound)
+    /// Type of market (0=Binary, 1=MultipleChoice, 2=Compound, 3=Scalar)
     pub market_type: u8,
     /// Address of the market creator
     pub creator: Address,
@@ -27,6 +29,26 @@ pub struct MarketCreated {
     pub category: String,
 }
 
+/// Emitted when a deployed Market contract is registered for a market.
+#[odra::event]
+pub struct MarketRegistered {
+    /// Unique identifier for the market
+    pub market_id: u64,
+    /// Address of the deployed Market contract
+    pub market: Address,
+}
+
+/// Emitted when a market is rejected before registration and its seed refunded.
+#[odra::event]
+pub struct MarketRejected {
+    /// Unique identifier for the market
+    pub market_id: u64,
+    /// Creator the seed was refunded to
+    pub creator: Address,
+    /// Seed refunded, in motes
+    pub refund: U256,
+}
+
 /// Emitted when the platform fee is updated.
 #[odra::event]
 pub struct PlatformFeeUpdated {
@@ -36,6 +58,21 @@ pub struct PlatformFeeUpdated {
     pub new_fee: u64,
 }
 
+/// Emitted when the default fee schedule for new markets is updated.
+#[odra::event]
+pub struct FeeScheduleUpdated {
+    /// New buy fee (basis points)
+    pub buy_fee_bps: u64,
+    /// New sell fee (basis points)
+    pub sell_fee_bps: u64,
+    /// New settlement fee (basis points)
+    pub settlement_fee_bps: u64,
+    /// New creator share of every fee (basis points of the fee)
+    pub creator_share_bps: u64,
+    /// New liquidity provider share of buy and sell fees (basis points of the fee)
+    pub lp_share_bps: u64,
+}
+
 /// Emitted when the factory is paused or unpaused.
 #[odra::event]
 pub struct FactoryPauseStatusChanged {
@@ -58,7 +95,7 @@ pub struct SharesPurchased {
     pub outcome_id: u64,
     /// Number of shares purchased
     pub shares: U256,
-    /// Total CSPR cost
+    /// Total CSPR cost, excluding the buy fee
     pub cost: U256,
     /// New price after purchase
     pub new_price: U256,
@@ -77,7 +114,7 @@ pub struct SharesSold {
     pub outcome_id: u64,
     /// Number of shares sold
     pub shares: U256,
-    /// CSPR revenue received
+    /// CSPR revenue received, net of the sell fee
     pub revenue: U256,
     /// New price after sale
     pub new_price: U256,
@@ -85,6 +122,160 @@ pub struct SharesSold {
     pub timestamp: u64,
 }
 
+/// Emitted when shares move between users without trading.
+#[odra::event]
+pub struct SharesTransferred {
+    /// Address the shares were taken from
+    pub from: Address,
+    /// Address the shares were given to
+    pub to: Address,
+    /// Market identifier
+    pub market_id: u64,
+    /// Outcome transferred
+    pub outcome_id: u64,
+    /// Number of shares transferred
+    pub shares: U256,
+    /// Cost basis moved with the shares
+    pub cost_basis: U256,
+    /// Block timestamp
+    pub timestamp: u64,
+}
+
+/// Emitted when a user allows another to transfer their shares.
+#[odra::event]
+pub struct SharesApproved {
+    /// Address of the share owner
+    pub owner: Address,
+    /// Address allowed to transfer the shares
+    pub spender: Address,
+    /// Market identifier
+    pub market_id: u64,
+    /// Outcome approved
+    pub outcome_id: u64,
+    /// Number of shares the spender may transfer
+    pub amount: U256,
+}
+
+/// Emitted when a user mints complete sets of outcome shares.
+#[odra::event]
+pub struct CompleteSetsMinted {
+    /// Address of the minter
+    pub user: Address,
+    /// Market identifier
+    pub market_id: u64,
+    /// Number of complete sets minted, equal to the CSPR paid in motes
+    pub sets: U256,
+    /// Block timestamp
+    pub timestamp: u64,
+}
+
+/// Emitted when a user redeems complete sets of outcome shares for CSPR.
+#[odra::event]
+pub struct CompleteSetsRedeemed {
+    /// Address of the redeemer
+    pub user: Address,
+    /// Market identifier
+    pub market_id: u64,
+    /// Number of complete sets redeemed, equal to the CSPR paid out in motes
+    pub sets: U256,
+    /// Block timestamp
+    pub timestamp: u64,
+}
+
+/// Emitted when a liquidity provider funds a complete-set AMM pool.
+#[odra::event]
+pub struct LiquidityAdded {
+    /// Address of the liquidity provider
+    pub provider: Address,
+    /// Market identifier
+    pub market_id: u64,
+    /// CSPR added in motes
+    pub amount: U256,
+    /// LP shares minted
+    pub lp_shares: U256,
+    /// Block timestamp
+    pub timestamp: u64,
+}
+
+/// Emitted when a liquidity provider withdraws from a complete-set AMM pool.
+#[odra::event]
+pub struct LiquidityRemoved {
+    /// Address of the liquidity provider
+    pub provider: Address,
+    /// Market identifier
+    pub market_id: u64,
+    /// LP shares burned
+    pub lp_shares: U256,
+    /// CSPR paid out in motes, LP fees included
+    pub payout: U256,
+    /// Block timestamp
+    pub timestamp: u64,
+}
+
+/// Emitted when a liquidity provider claims accrued LP fees.
+#[odra::event]
+pub struct LiquidityFeesClaimed {
+    /// Address of the liquidity provider
+    pub provider: Address,
+    /// Market identifier
+    pub market_id: u64,
+    /// Fees paid out in motes
+    pub amount: U256,
+    /// Block timestamp
+    pub timestamp: u64,
+}
+
+/// Emitted when a user places a limit order.
+#[odra::event]
+pub struct OrderPlaced {
+    /// Order identifier within the market
+    pub order_id: u64,
+    /// Address of the order owner
+    pub owner: Address,
+    /// Market identifier
+    pub market_id: u64,
+    /// Outcome traded
+    pub outcome_id: u64,
+    /// Buy or sell
+    pub side: OrderSide,
+    /// Limit on the spot price in motes
+    pub limit_price: U256,
+    /// Escrowed budget for a buy, reserved shares for a sell
+    pub amount: U256,
+}
+
+/// Emitted when a keeper fills a limit order, fully or in part.
+#[odra::event]
+pub struct OrderFilled {
+    /// Order identifier within the market
+    pub order_id: u64,
+    /// Address of the order owner
+    pub owner: Address,
+    /// Address of the keeper that filled the order
+    pub keeper: Address,
+    /// Market identifier
+    pub market_id: u64,
+    /// Part of the keeper reward paid for this fill
+    pub keeper_reward: U256,
+    /// Budget or shares the order still rests with; zero once filled
+    pub remaining: U256,
+    /// Block timestamp
+    pub timestamp: u64,
+}
+
+/// Emitted when a limit order is cancelled or closed after expiry.
+#[odra::event]
+pub struct OrderCancelled {
+    /// Order identifier within the market
+    pub order_id: u64,
+    /// Address of the order owner
+    pub owner: Address,
+    /// Market identifier
+    pub market_id: u64,
+    /// Block timestamp
+    pub timestamp: u64,
+}
+
 /// Emitted when a market is resolved.
 #[odra::event]
 pub struct MarketResolved {
@@ -100,6 +291,45 @@ pub struct MarketResolved {
     pub proof: String,
 }
 
+/// Emitted when a scalar market is resolved to a value.
+#[odra::event]
+pub struct ScalarMarketResolved {
+    /// Market identifier
+    pub market_id: u64,
+    /// Resolved value
+    pub value: i64,
+    /// Share of the payout owed to LONG shares, in basis points
+    pub long_payout_bps: u64,
+    /// Address of the resolver
+    pub resolver: Address,
+    /// Block timestamp
+    pub timestamp: u64,
+    /// Proof or reference to resolution data
+    pub proof: String,
+}
+
+/// Emitted when a market is resolved Invalid.
+#[odra::event]
+pub struct MarketResolvedInvalid {
+    /// Market identifier
+    pub market_id: u64,
+    /// Address of the resolver
+    pub resolver: Address,
+    /// Block timestamp
+    pub timestamp: u64,
+    /// Proof or reference to resolution data
+    pub proof: String,
+}
+
+/// Emitted when a market resolves owing winners more than it holds.
+#[odra::event]
+pub struct SettlementShortfall {
+    /// Market identifier
+    pub market_id: u64,
+    /// Amount owed to winners beyond the market's funds
+    pub shortfall: U256,
+}
+
 /// Emitted when a market is closed for trading.
 #[odra::event]
 pub struct MarketClosed {
@@ -133,6 +363,19 @@ pub struct WinningsClaimed {
     pub timestamp: u64,
 }
 
+/// Emitted when the creator withdraws the unused seed liquidity of a market.
+#[odra::event]
+pub struct CreatorLiquidityWithdrawn {
+    /// Address of the market creator
+    pub creator: Address,
+    /// Market identifier
+    pub market_id: u64,
+    /// Amount withdrawn in CSPR
+    pub amount: U256,
+    /// Block timestamp
+    pub timestamp: u64,
+}
+
 /// Emitted when a user claims a refund from a cancelled market.
 #[odra::event]
 pub struct RefundClaimed {
@@ -146,6 +389,137 @@ pub struct RefundClaimed {
     pub timestamp: u64,
 }
 
+/// Emitted when an outcome is proposed under optimistic resolution.
+#[odra::event]
+pub struct ResolutionProposed {
+    /// Market identifier
+    pub market_id: u64,
+    /// Address that proposed the outcome
+    pub proposer: Address,
+    /// Proposed winning outcome
+    pub outcome_id: u64,
+    /// Bond posted in motes
+    pub bond: U256,
+    /// Block time in milliseconds until which the proposal can be disputed
+    pub dispute_deadline: u64,
+}
+
+/// Emitted when a proposed outcome is disputed.
+#[odra::event]
+pub struct ResolutionDisputed {
+    /// Market identifier
+    pub market_id: u64,
+    /// Address that disputed the proposal
+    pub disputer: Address,
+    /// Disputed outcome
+    pub outcome_id: u64,
+    /// Counter-bond posted in motes
+    pub bond: U256,
+    /// Block timestamp
+    pub timestamp: u64,
+}
+
+/// Emitted when a resolver committee member votes for an outcome.
+#[odra::event]
+pub struct ResolutionVoted {
+    /// Market identifier
+    pub market_id: u64,
+    /// Voting committee member
+    pub member: Address,
+    /// Outcome voted for, or `INVALID_VOTE`
+    pub outcome_id: u64,
+    /// Votes for this outcome so far, this one included
+    pub votes: u64,
+    /// Matching votes needed to resolve
+    pub threshold: u64,
+    /// Block timestamp
+    pub timestamp: u64,
+}
+
+/// Emitted when a market's resolver committee is rotated.
+#[odra::event]
+pub struct ResolverCommitteeUpdated {
+    /// Market identifier
+    pub market_id: u64,
+    /// New committee members, empty if the committee was removed
+    pub members: Vec<Address>,
+    /// Matching votes needed to resolve
+    pub threshold: u64,
+}
+
+/// Emitted when a disputed proposal is arbitrated or overridden.
+#[odra::event]
+pub struct DisputeResolved {
+    /// Market identifier
+    pub market_id: u64,
+    /// Winning outcome the market resolved to, or `None` if it resolved Invalid
+    pub outcome_id: Option<u64>,
+    /// Proposer or disputer, whichever was right
+    pub winner: Address,
+    /// Bond forfeited by the other side and paid to the winner, in motes
+    pub slashed_bond: U256,
+    /// Block timestamp
+    pub timestamp: u64,
+}
+
+// =============================================================================
+// Outcome Token Events (CEP-18)
+// =============================================================================
+
+/// Emitted by an outcome token when shares are transferred by their owner.
+#[odra::event]
+pub struct Transfer {
+    /// Address the shares were taken from
+    pub sender: Address,
+    /// Address the shares were given to
+    pub recipient: Address,
+    /// Number of shares transferred
+    pub amount: U256,
+}
+
+/// Emitted by an outcome token when shares are transferred by a spender.
+#[odra::event]
+pub struct TransferFrom {
+    /// Address that spent its allowance
+    pub spender: Address,
+    /// Address the shares were taken from
+    pub owner: Address,
+    /// Address the shares were given to
+    pub recipient: Address,
+    /// Number of shares transferred
+    pub amount: U256,
+}
+
+/// Emitted by an outcome token when the market issues shares.
+#[odra::event]
+pub struct Mint {
+    /// Address the shares were issued to
+    pub recipient: Address,
+    /// Number of shares issued
+    pub amount: U256,
+}
+
+/// Emitted by an outcome token when shares are sold back to the market or
+/// claimed.
+#[odra::event]
+pub struct Burn {
+    /// Address the shares were taken from
+    pub owner: Address,
+    /// Number of shares burned
+    pub amount: U256,
+}
+
+/// Emitted by an outcome token when an owner sets an allowance.
+#[odra::event]
+pub struct SetAllowance {
+    /// Address of the share owner
+    pub owner: Address,
+    /// Address allowed to transfer the shares
+    pub spender: Address,
+    /// Number of shares the spender may transfer
+    pub allowance: U256,
+}
+
 // =============================================================================
 // Vault Events
 // =============================================================================
@@ -181,6 +555,16 @@ pub struct FeesCollected {
     pub amount: U256,
 }
 
+/// Emitted when an unclaimable remainder of a market's balance is swept to
+/// the fee recipient.
+#[odra::event]
+pub struct FundsSwept {
+    /// Market identifier
+    pub market_id: u64,
+    /// Amount swept in motes
+    pub amount: U256,
+}
+
 /// Emitted when platform fees are claimed by the recipient.
 #[odra::event]
 pub struct FeesClaimed {
@@ -190,6 +574,26 @@ pub struct FeesClaimed {
     pub amount: U256,
 }
 
+/// Emitted when a market creator's share of fees is collected.
+#[odra::event]
+pub struct CreatorFeesCollected {
+    /// Market identifier
+    pub market_id: u64,
+    /// Market creator the fees accrue to
+    pub creator: Address,
+    /// Fee amount collected
+    pub amount: U256,
+}
+
+/// Emitted when a market creator claims their share of market fees.
+#[odra::event]
+pub struct CreatorFeesClaimed {
+    /// Market creator address
+    pub creator: Address,
+    /// Amount claimed
+    pub amount: U256,
+}
+
 /// Emitted when a market contract is authorized.
 #[odra::event]
 pub struct MarketAuthorized {
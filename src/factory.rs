@@ -1,36 +1,40 @@
 //! MarketFactory Contract for Casper Predict.
 //!
 //! The factory is responsible for:
-//! - Creating new prediction markets (Binary, Multiple Choice, Compound)
-//! - Maintaining a registry of all markets
+//! - Creating new prediction markets (Binary, Multiple Choice, Compound, Scalar)
+//! - Maintaining a registry of all markets and their deployed contracts
 //! - Setting global parameters (fees, duration limits, etc.)
 //! - Emergency pause functionality
 
 use odra::prelude::*;
-use odra::casper_types::{U256, U512};
+use odra::casper_types::U256;
+use odra::ContractRef;
 
+use crate::curves::{u256_to_u512, u512_to_u256};
 use crate::errors::Error;
-use crate::types::{BondingCurveParams, MarketType};
+use crate::market::{MarketContractRef, MarketInitParams};
+use crate::types::{
+    compound_outcome_count, compound_outcome_names, BondingCurveParams, CompoundLeg,
+    CompoundMarketParams, CurveKind, FeeSchedule, MarketType, OptimisticParams, OracleBinding,
+    PricingMode, ResolverCommittee, ScalarMarketParams, MAX_FEE_BPS,
+};
+use crate::lmsr::liquidity_for_subsidy;
+use crate::vault::VaultContractRef;
 use crate::events::{
-    MarketCreated, PlatformFeeUpdated, FactoryPauseStatusChanged, AdminTransferred,
+    MarketCreated, MarketRegistered, MarketRejected, PlatformFeeUpdated, FeeScheduleUpdated,
+    FactoryPauseStatusChanged, AdminTransferred,
 };
 
-/// Maximum fee percentage (10% = 1000 basis points)
-const MAX_FEE_BPS: u64 = 1000;
-
-/// Convert U512 to U256 (assumes value fits)
-fn u512_to_u256(value: U512) -> U256 {
-    let mut bytes = [0u8; 32];
-    for i in 0..32 {
-        bytes[i] = value.byte(i);
-    }
-    U256::from_little_endian(&bytes)
-}
+/// Maximum number of leg combinations in a compound market
+const MAX_COMPOUND_OUTCOMES: u64 = 64;
 
 /// MarketFactory - Central contract for creating prediction markets.
 #[odra::module(
     errors = Error,
-    events = [MarketCreated, PlatformFeeUpdated, FactoryPauseStatusChanged, AdminTransferred]
+    events = [
+        MarketCreated, MarketRegistered, MarketRejected, PlatformFeeUpdated, FeeScheduleUpdated,
+        FactoryPauseStatusChanged, AdminTransferred
+    ]
 )]
 pub struct MarketFactory {
     // =========================================================================
@@ -50,20 +54,26 @@ pub struct MarketFactory {
     market_count: Var<u64>,
     /// Mapping of market_id to market data
     markets: Mapping<u64, MarketData>,
+    /// Init parameters the Market contract must be deployed with (market_id -> params)
+    market_init_params: Mapping<u64, MarketInitParams>,
 
     // =========================================================================
     // Global Parameters
     // =========================================================================
-    /// Platform fee in basis points (e.g., 200 = 2%)
-    platform_fee_bps: Var<u64>,
+    /// Fee schedule given to new markets
+    default_fee_schedule: Var<FeeSchedule>,
     /// Minimum market duration in seconds
     min_market_duration: Var<u64>,
     /// Maximum market duration in seconds
     max_market_duration: Var<u64>,
     /// Minimum initial liquidity in motes
     min_initial_liquidity: Var<U256>,
-    /// Default bonding curve parameters
-    default_bonding_params: Var<BondingCurveParams>,
+    /// Bonding curve parameters per curve family (falls back to `BondingCurveParams::preset`)
+    default_bonding_params: Mapping<CurveKind, BondingCurveParams>,
+    /// Resolver committee given to new markets, if any
+    default_committee: Var<Option<ResolverCommittee>>,
+    /// Oracles market creators may bind their markets to
+    approved_oracles: Mapping<Address, bool>,
 
     // =========================================================================
     // Vault Reference
@@ -89,6 +99,12 @@ pub struct MarketData {
     pub category: String,
     /// Creation timestamp
     pub created_at: u64,
+    /// Pricing mode: bonding curve, LMSR or complete-set AMM
+    pub pricing: PricingMode,
+    /// Deployed Market contract (set once registered)
+    pub market_contract: Option<Address>,
+    /// Whether the market was rejected before registration and its seed refunded
+    pub rejected: bool,
 }
 
 /// Initialization arguments for the factory.
@@ -96,6 +112,7 @@ pub struct MarketData {
 pub struct FactoryInitArgs {
     pub admin: Address,
     pub vault_contract: Address,
+    /// Initial sell and settlement fee; buys start free
     pub platform_fee_bps: u64,
     pub min_market_duration: u64,
     pub max_market_duration: u64,
@@ -116,11 +133,10 @@ impl MarketFactory {
 
         self.admin.set(args.admin);
         self.vault_contract.set(args.vault_contract);
-        self.platform_fee_bps.set(args.platform_fee_bps);
+        self.default_fee_schedule.set(FeeSchedule::flat(args.platform_fee_bps));
         self.min_market_duration.set(args.min_market_duration);
         self.max_market_duration.set(args.max_market_duration);
         self.min_initial_liquidity.set(args.min_initial_liquidity);
-        self.default_bonding_params.set(BondingCurveParams::default_params());
         
         self.paused.set(false);
         self.market_count.set(0);
@@ -133,21 +149,28 @@ impl MarketFactory {
 
     /// Create a new binary (YES/NO) prediction market.
     ///
-    /// Returns the market ID.
+    /// The attached CSPR seeds the market's winners' pool in the vault, and
+    /// `curve` picks the bonding curve family its outcomes are priced with.
+    /// With `PricingMode::Lmsr` the outcomes trade against an LMSR market
+    /// maker instead, and the seed is its subsidy. Returns the market ID.
+    /// The Market contract is then deployed with `get_market_init_params`
+    /// and attached through `register_market`.
     #[odra(payable)]
     pub fn create_binary_market(
         &mut self,
         question: String,
         end_time: u64,
-        _resolution_source: String,
+        resolution_source: String,
         category: String,
+        curve: CurveKind,
+        pricing: PricingMode,
     ) -> u64 {
         self.require_not_paused();
         self.validate_market_params(&question, end_time);
 
         let initial_liquidity_u512 = self.env().attached_value();
         let initial_liquidity = u512_to_u256(initial_liquidity_u512);
-        self.validate_initial_liquidity(initial_liquidity);
+        self.validate_initial_liquidity(initial_liquidity, pricing, 2);
 
         let creator = self.env().caller();
         let market_id = self.next_market_id();
@@ -161,8 +184,17 @@ impl MarketFactory {
             end_time,
             category: category.clone(),
             created_at: self.env().get_block_time(),
+            pricing,
+            market_contract: None,
+            rejected: false,
         };
+        let outcome_names = vec![String::from("Yes"), String::from("No")];
+        let init_params = self.build_init_params(
+            &market_data, outcome_names, Vec::new(), resolution_source, initial_liquidity, curve,
+        );
+        self.market_init_params.set(&market_id, init_params);
         self.markets.set(&market_id, market_data);
+        self.seed_market(market_id, initial_liquidity);
 
         // Emit event
         self.env().emit_event(MarketCreated {
@@ -174,24 +206,24 @@ impl MarketFactory {
             category,
         });
 
-        // Note: In a full implementation, we would deploy a separate Market contract
-        // or use SubModules with dynamic creation. For now, we store the market data
-        // and the frontend/users would interact with individual Market contracts.
-
         market_id
     }
 
-    /// Create a new multiple choice prediction market.
+    /// Create a new multiple choice prediction market, priced with `curve`
+    /// or, depending on `pricing`, another market maker.
     ///
     /// Returns the market ID.
     #[odra(payable)]
+    #[allow(clippy::too_many_arguments)]
     pub fn create_multiple_choice_market(
         &mut self,
         question: String,
         outcomes: Vec<String>,
         end_time: u64,
-        _resolution_source: String,
+        resolution_source: String,
         category: String,
+        curve: CurveKind,
+        pricing: PricingMode,
     ) -> u64 {
         self.require_not_paused();
         self.validate_market_params(&question, end_time);
@@ -203,7 +235,8 @@ impl MarketFactory {
 
         let initial_liquidity_u512 = self.env().attached_value();
         let initial_liquidity = u512_to_u256(initial_liquidity_u512);
-        self.validate_initial_liquidity(initial_liquidity);
+        let outcome_count = outcomes.len() as u64;
+        self.validate_initial_liquidity(initial_liquidity, pricing, outcome_count);
 
         let creator = self.env().caller();
         let market_id = self.next_market_id();
@@ -217,8 +250,16 @@ impl MarketFactory {
             end_time,
             category: category.clone(),
             created_at: self.env().get_block_time(),
+            pricing,
+            market_contract: None,
+            rejected: false,
         };
+        let init_params = self.build_init_params(
+            &market_data, outcomes, Vec::new(), resolution_source, initial_liquidity, curve,
+        );
+        self.market_init_params.set(&market_id, init_params);
         self.markets.set(&market_id, market_data);
+        self.seed_market(market_id, initial_liquidity);
 
         // Emit event
         self.env().emit_event(MarketCreated {
@@ -233,11 +274,282 @@ impl MarketFactory {
         market_id
     }
 
+    /// Create a new compound (parlay) prediction market.
+    ///
+    /// Every combination of leg results becomes an outcome, so positions are
+    /// combined predictions such as "A and B". Returns the market ID.
+    #[odra(payable)]
+    pub fn create_compound_market(&mut self, params: CompoundMarketParams) -> u64 {
+        self.require_not_paused();
+        self.validate_market_params(&params.question, params.end_time);
+
+        // Validate legs
+        let legs_valid = params.legs.len() >= 2
+            && params.legs.iter().all(|leg| leg.outcomes.len() >= 2);
+        if !legs_valid || compound_outcome_count(&params.legs) > MAX_COMPOUND_OUTCOMES {
+            self.env().revert(Error::InvalidOutcomeCount);
+        }
+
+        let initial_liquidity_u512 = self.env().attached_value();
+        let initial_liquidity = u512_to_u256(initial_liquidity_u512);
+        let outcome_count = compound_outcome_count(&params.legs);
+        self.validate_initial_liquidity(initial_liquidity, params.pricing, outcome_count);
+
+        let creator = self.env().caller();
+        let market_id = self.next_market_id();
+
+        // Store market data
+        let market_data = MarketData {
+            market_id,
+            market_type: MarketType::Compound,
+            question: params.question.clone(),
+            creator,
+            end_time: params.end_time,
+            category: params.category.clone(),
+            created_at: self.env().get_block_time(),
+            pricing: params.pricing,
+            market_contract: None,
+            rejected: false,
+        };
+        let outcome_names = compound_outcome_names(&params.legs);
+        let init_params = self.build_init_params(
+            &market_data,
+            outcome_names,
+            params.legs,
+            params.resolution_source,
+            initial_liquidity,
+            params.curve,
+        );
+        self.market_init_params.set(&market_id, init_params);
+        self.markets.set(&market_id, market_data);
+        self.seed_market(market_id, initial_liquidity);
+
+        // Emit event
+        self.env().emit_event(MarketCreated {
+            market_id,
+            market_type: 2, // Compound
+            creator,
+            question: params.question,
+            end_time: params.end_time,
+            category: params.category,
+        });
+
+        market_id
+    }
+
+    /// Create a new scalar (range) prediction market.
+    ///
+    /// The market trades LONG and SHORT shares and is resolved to a value;
+    /// LONG is paid the part of the pool matching where the value lands in
+    /// `params.range`, and SHORT the rest. Returns the market ID.
+    #[odra(payable)]
+    pub fn create_scalar_market(&mut self, params: ScalarMarketParams) -> u64 {
+        self.require_not_paused();
+        self.validate_market_params(&params.question, params.end_time);
+
+        if !params.range.is_valid() {
+            self.env().revert(Error::InvalidScalarRange);
+        }
+
+        let initial_liquidity_u512 = self.env().attached_value();
+        let initial_liquidity = u512_to_u256(initial_liquidity_u512);
+        self.validate_initial_liquidity(initial_liquidity, params.pricing, 2);
+
+        let creator = self.env().caller();
+        let market_id = self.next_market_id();
+
+        // Store market data
+        let market_data = MarketData {
+            market_id,
+            market_type: MarketType::Scalar,
+            question: params.question.clone(),
+            creator,
+            end_time: params.end_time,
+            category: params.category.clone(),
+            created_at: self.env().get_block_time(),
+            pricing: params.pricing,
+            market_contract: None,
+            rejected: false,
+        };
+        let outcome_names = vec![String::from("Long"), String::from("Short")];
+        let mut init_params = self.build_init_params(
+            &market_data,
+            outcome_names,
+            Vec::new(),
+            params.resolution_source,
+            initial_liquidity,
+            params.curve,
+        );
+        init_params.scalar = Some(params.range);
+        self.market_init_params.set(&market_id, init_params);
+        self.markets.set(&market_id, market_data);
+        self.seed_market(market_id, initial_liquidity);
+
+        // Emit event
+        self.env().emit_event(MarketCreated {
+            market_id,
+            market_type: 3, // Scalar
+            creator,
+            question: params.question,
+            end_time: params.end_time,
+            category: params.category,
+        });
+
+        market_id
+    }
+
+    /// Bind a market to an oracle it can be resolved from.
+    ///
+    /// Only possible before the Market contract is registered, so the binding
+    /// is part of the parameters it is deployed with. Can be called by the
+    /// admin, or by the market creator for an oracle the admin approved with
+    /// `set_oracle_approved`.
+    pub fn bind_market_oracle(&mut self, market_id: u64, oracle: OracleBinding) {
+        let market_data = self.markets
+            .get(&market_id)
+            .unwrap_or_revert_with(&self.env(), Error::MarketNotFound);
+
+        let caller = self.env().caller();
+        let admin = self.admin.get().unwrap_or_revert(&self.env());
+        if caller != admin {
+            if caller != market_data.creator {
+                self.env().revert(Error::AccessDenied);
+            }
+            if !self.is_oracle_approved(oracle.oracle) {
+                self.env().revert(Error::OracleNotApproved);
+            }
+        }
+
+        if market_data.market_contract.is_some() {
+            self.env().revert(Error::MarketAlreadyRegistered);
+        }
+
+        let mut init_params = self.market_init_params
+            .get(&market_id)
+            .unwrap_or_revert_with(&self.env(), Error::MarketNotFound);
+        init_params.oracle = Some(oracle);
+        self.market_init_params.set(&market_id, init_params);
+    }
+
+    /// Let anyone propose the outcome of a market once it ends, under the
+    /// bond and dispute window of `optimistic`.
+    ///
+    /// Only possible before the Market contract is registered, and for a
+    /// market bound to neither an oracle nor a resolver committee. Can be
+    /// called by the admin or the market creator.
+    pub fn enable_optimistic_resolution(&mut self, market_id: u64, optimistic: OptimisticParams) {
+        let market_data = self.markets
+            .get(&market_id)
+            .unwrap_or_revert_with(&self.env(), Error::MarketNotFound);
+
+        let caller = self.env().caller();
+        let admin = self.admin.get().unwrap_or_revert(&self.env());
+        if caller != admin && caller != market_data.creator {
+            self.env().revert(Error::AccessDenied);
+        }
+
+        if market_data.market_contract.is_some() {
+            self.env().revert(Error::MarketAlreadyRegistered);
+        }
+
+        let mut init_params = self.market_init_params
+            .get(&market_id)
+            .unwrap_or_revert_with(&self.env(), Error::MarketNotFound);
+        if init_params.oracle.is_some() || init_params.committee.is_some() {
+            self.env().revert(Error::OptimisticResolutionDisabled);
+        }
+        init_params.optimistic = Some(optimistic);
+        self.market_init_params.set(&market_id, init_params);
+    }
+
+    /// Register the deployed Market contract for a market.
+    ///
+    /// The contract must have been deployed with the parameters returned by
+    /// `get_market_init_params`. Registration authorizes it in the vault for
+    /// this market only. Can only be called by admin, who vouches that the
+    /// contract runs the Market code.
+    pub fn register_market(&mut self, market_id: u64, market: Address) {
+        self.require_admin();
+        let mut market_data = self.markets
+            .get(&market_id)
+            .unwrap_or_revert_with(&self.env(), Error::MarketNotFound);
+
+        if market_data.market_contract.is_some() {
+            self.env().revert(Error::MarketAlreadyRegistered);
+        }
+        if market_data.rejected {
+            self.env().revert(Error::MarketRejected);
+        }
+
+        // The deployed contract must belong to this factory and this registry entry
+        let market_ref = MarketContractRef::new(self.env(), market);
+        let metadata = market_ref.get_metadata();
+        let config = market_ref.get_config();
+        let seed = market_ref.get_seed_liquidity().amount;
+        let init_params = self.market_init_params.get(&market_id).unwrap_or_revert(&self.env());
+        if !init_params.is_deployed_as(&metadata, &config, seed) {
+            self.env().revert(Error::MarketMismatch);
+        }
+
+        market_data.market_contract = Some(market);
+        self.markets.set(&market_id, market_data);
+
+        let vault = self.vault_contract.get().unwrap_or_revert(&self.env());
+        VaultContractRef::new(self.env(), vault).authorize_market(market, market_id);
+
+        self.env().emit_event(MarketRegistered { market_id, market });
+    }
+
+    /// Reject a market that has no registered contract, refunding the seed
+    /// to its creator.
+    ///
+    /// The admin can reject a market at any time before registration, and
+    /// the creator once its end time has passed unregistered. A rejected
+    /// market can no longer be registered.
+    pub fn reject_market(&mut self, market_id: u64) {
+        let mut market_data = self.markets
+            .get(&market_id)
+            .unwrap_or_revert_with(&self.env(), Error::MarketNotFound);
+        let caller = self.env().caller();
+        let admin = self.admin.get().unwrap_or_revert(&self.env());
+        if caller != admin {
+            if caller != market_data.creator {
+                self.env().revert(Error::AccessDenied);
+            }
+            if self.env().get_block_time() <= market_data.end_time {
+                self.env().revert(Error::MarketNotEnded);
+            }
+        }
+
+        if market_data.market_contract.is_some() {
+            self.env().revert(Error::MarketAlreadyRegistered);
+        }
+        if market_data.rejected {
+            self.env().revert(Error::MarketRejected);
+        }
+        market_data.rejected = true;
+        let creator = market_data.creator;
+        self.markets.set(&market_id, market_data);
+
+        let refund = self.market_init_params
+            .get(&market_id)
+            .unwrap_or_revert(&self.env())
+            .initial_liquidity;
+        if !refund.is_zero() {
+            let vault = self.vault_contract.get().unwrap_or_revert(&self.env());
+            VaultContractRef::new(self.env(), vault).withdraw(market_id, creator, refund);
+        }
+
+        self.env().emit_event(MarketRejected { market_id, creator, refund });
+    }
+
     // =========================================================================
     // Admin Functions
     // =========================================================================
 
     /// Update the platform fee percentage.
+    ///
+    /// Sets both the sell and settlement fee of the default fee schedule.
     pub fn set_platform_fee(&mut self, new_fee_bps: u64) {
         self.require_admin();
 
@@ -245,8 +557,11 @@ impl MarketFactory {
             self.env().revert(Error::FeeTooHigh);
         }
 
-        let old_fee = self.platform_fee_bps.get_or_default();
-        self.platform_fee_bps.set(new_fee_bps);
+        let mut schedule = self.default_fee_schedule.get_or_default();
+        let old_fee = schedule.settlement_fee_bps;
+        schedule.sell_fee_bps = new_fee_bps;
+        schedule.settlement_fee_bps = new_fee_bps;
+        self.default_fee_schedule.set(schedule);
 
         self.env().emit_event(PlatformFeeUpdated {
             old_fee,
@@ -254,6 +569,27 @@ impl MarketFactory {
         });
     }
 
+    /// Update the fee schedule given to new markets.
+    ///
+    /// Existing markets keep the schedule they were created with.
+    pub fn set_default_fee_schedule(&mut self, schedule: FeeSchedule) {
+        self.require_admin();
+
+        if !schedule.is_valid() {
+            self.env().revert(Error::FeeTooHigh);
+        }
+
+        self.default_fee_schedule.set(schedule);
+
+        self.env().emit_event(FeeScheduleUpdated {
+            buy_fee_bps: schedule.buy_fee_bps,
+            sell_fee_bps: schedule.sell_fee_bps,
+            settlement_fee_bps: schedule.settlement_fee_bps,
+            creator_share_bps: schedule.creator_share_bps,
+            lp_share_bps: schedule.lp_share_bps,
+        });
+    }
+
     /// Update market duration limits.
     pub fn set_duration_limits(&mut self, min_duration: u64, max_duration: u64) {
         self.require_admin();
@@ -278,10 +614,35 @@ impl MarketFactory {
         self.vault_contract.set(new_vault);
     }
 
-    /// Update default bonding curve parameters.
+    /// Update the default bonding curve parameters of the curve family `params.kind`.
     pub fn set_default_bonding_params(&mut self, params: BondingCurveParams) {
         self.require_admin();
-        self.default_bonding_params.set(params);
+
+        if !params.is_valid() {
+            self.env().revert(Error::InvalidInitParams);
+        }
+
+        let kind = params.kind;
+        self.default_bonding_params.set(&kind, params);
+    }
+
+    /// Update the resolver committee given to new markets, or stop giving
+    /// them one with `None`. Markets already created keep their committee.
+    pub fn set_default_resolver_committee(&mut self, committee: Option<ResolverCommittee>) {
+        self.require_admin();
+
+        if committee.as_ref().is_some_and(|committee| !committee.is_valid()) {
+            self.env().revert(Error::InvalidCommittee);
+        }
+
+        self.default_committee.set(committee);
+    }
+
+    /// Allow or stop market creators binding their markets to `oracle`.
+    /// Markets already bound keep their oracle.
+    pub fn set_oracle_approved(&mut self, oracle: Address, approved: bool) {
+        self.require_admin();
+        self.approved_oracles.set(&oracle, approved);
     }
 
     /// Pause the factory (stops new market creation).
@@ -323,9 +684,34 @@ impl MarketFactory {
         self.markets.get(&market_id)
     }
 
-    /// Get the platform fee in basis points.
+    /// Get the deployed Market contract address for a market.
+    pub fn get_market_address(&self, market_id: u64) -> Option<Address> {
+        self.markets.get(&market_id).and_then(|data| data.market_contract)
+    }
+
+    /// Get the init parameters a market's contract must be deployed with.
+    pub fn get_market_init_params(&self, market_id: u64) -> Option<MarketInitParams> {
+        self.market_init_params.get(&market_id)
+    }
+
+    /// Get the platform (settlement) fee in basis points.
     pub fn get_platform_fee(&self) -> u64 {
-        self.platform_fee_bps.get_or_default()
+        self.default_fee_schedule.get_or_default().settlement_fee_bps
+    }
+
+    /// Get the fee schedule given to new markets.
+    pub fn get_default_fee_schedule(&self) -> FeeSchedule {
+        self.default_fee_schedule.get_or_default()
+    }
+
+    /// Get the resolver committee given to new markets, if any.
+    pub fn get_default_resolver_committee(&self) -> Option<ResolverCommittee> {
+        self.default_committee.get().flatten()
+    }
+
+    /// Check whether market creators may bind their markets to `oracle`.
+    pub fn is_oracle_approved(&self, oracle: Address) -> bool {
+        self.approved_oracles.get(&oracle).unwrap_or(false)
     }
 
     /// Get minimum market duration.
@@ -348,9 +734,11 @@ impl MarketFactory {
         self.vault_contract.get().unwrap_or_revert(&self.env())
     }
 
-    /// Get default bonding curve parameters.
-    pub fn get_default_bonding_params(&self) -> BondingCurveParams {
-        self.default_bonding_params.get_or_default()
+    /// Get the default bonding curve parameters of a curve family.
+    pub fn get_default_bonding_params(&self, kind: CurveKind) -> BondingCurveParams {
+        self.default_bonding_params
+            .get(&kind)
+            .unwrap_or_else(|| BondingCurveParams::preset(kind))
     }
 
     /// Check if the factory is paused.
@@ -374,6 +762,53 @@ impl MarketFactory {
         current
     }
 
+    /// Build the Market init parameters from a registry entry and the current settings.
+    fn build_init_params(
+        &self,
+        market_data: &MarketData,
+        outcome_names: Vec<String>,
+        legs: Vec<CompoundLeg>,
+        resolution_source: String,
+        initial_liquidity: U256,
+        curve: CurveKind,
+    ) -> MarketInitParams {
+        let admin = self.admin.get().unwrap_or_revert(&self.env());
+        MarketInitParams {
+            market_id: market_data.market_id,
+            market_type: market_data.market_type.clone(),
+            question: market_data.question.clone(),
+            outcome_names,
+            end_time: market_data.end_time,
+            resolution_source,
+            category: market_data.category.clone(),
+            creator: market_data.creator,
+            admin,
+            resolver: admin,
+            fees: self.default_fee_schedule.get_or_default(),
+            vault_contract: self.vault_contract.get().unwrap_or_revert(&self.env()),
+            factory_contract: self.env().self_address(),
+            bonding_params: self.get_default_bonding_params(curve),
+            pricing: market_data.pricing,
+            initial_liquidity,
+            legs,
+            scalar: None,
+            oracle: None,
+            optimistic: None,
+            committee: self.get_default_resolver_committee(),
+        }
+    }
+
+    /// Deposit the creator's initial liquidity into the vault for a new market.
+    fn seed_market(&self, market_id: u64, initial_liquidity: U256) {
+        if initial_liquidity.is_zero() {
+            return;
+        }
+        let vault = self.vault_contract.get().unwrap_or_revert(&self.env());
+        VaultContractRef::new(self.env(), vault)
+            .with_tokens(u256_to_u512(initial_liquidity))
+            .deposit(market_id);
+    }
+
     /// Ensure the caller is the admin.
     fn require_admin(&self) {
         let caller = self.env().caller();
@@ -413,9 +848,18 @@ impl MarketFactory {
     }
 
     /// Validate initial liquidity.
-    fn validate_initial_liquidity(&self, liquidity: U256) {
+    ///
+    /// An LMSR market also needs a seed large enough to fund a non-zero
+    /// liquidity parameter over its `outcome_count` outcomes, and a
+    /// complete-set AMM market a seed to mint the pool's first sets.
+    fn validate_initial_liquidity(&self, liquidity: U256, pricing: PricingMode, outcome_count: u64) {
         let min_liquidity = self.min_initial_liquidity.get_or_default();
-        if liquidity < min_liquidity {
+        let unfunded = match pricing {
+            PricingMode::BondingCurve => false,
+            PricingMode::Lmsr => liquidity_for_subsidy(liquidity, outcome_count).is_zero(),
+            PricingMode::CompleteSetAmm => liquidity.is_zero(),
+        };
+        if liquidity < min_liquidity || unfunded {
             self.env().revert(Error::InsufficientInitialLiquidity);
         }
     }
@@ -424,25 +868,66 @@ impl MarketFactory {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use odra::host::{Deployer, HostRef};
+    use odra::casper_types::U512;
+    use crate::market::{Market, MarketHostRef, MarketInitArgs};
+    use crate::types::ScalarRange;
+    use crate::vault::{Vault, VaultHostRef, VaultInitArgs};
+    use odra::host::{Deployer, HostEnv, HostRef};
+
+    fn factory_args(admin: Address, vault_contract: Address) -> MarketFactoryInitArgs {
+        MarketFactoryInitArgs {
+            args: FactoryInitArgs {
+                admin,
+                vault_contract,
+                platform_fee_bps: 200, // 2%
+                min_market_duration: 3600, // 1 hour
+                max_market_duration: 86400 * 30, // 30 days
+                min_initial_liquidity: U256::from(1_000_000_000u64), // 1 CSPR
+            },
+        }
+    }
 
-    fn setup() -> (MarketFactoryHostRef, odra::host::HostEnv) {
+    fn setup() -> (MarketFactoryHostRef, HostEnv) {
         let env = odra_test::env();
         let admin = env.get_account(0);
-        
-        let init_args = FactoryInitArgs {
-            admin,
-            vault_contract: admin, // Placeholder
-            platform_fee_bps: 200, // 2%
-            min_market_duration: 3600, // 1 hour
-            max_market_duration: 86400 * 30, // 30 days
-            min_initial_liquidity: U256::from(1_000_000_000u64), // 1 CSPR
-        };
 
-        let factory = MarketFactory::deploy(&env, init_args);
+        let factory = MarketFactory::deploy(&env, factory_args(admin, admin)); // Vault placeholder
         (factory, env)
     }
 
+    fn setup_with_vault() -> (MarketFactoryHostRef, VaultHostRef, HostEnv) {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+
+        let mut vault = Vault::deploy(&env, VaultInitArgs { admin, fee_recipient: admin });
+        let factory = MarketFactory::deploy(&env, factory_args(admin, vault.address()));
+        vault.set_factory(factory.address());
+        (factory, vault, env)
+    }
+
+    /// Create a binary market from `creator` and deploy its Market contract.
+    fn create_and_deploy(
+        factory: &mut MarketFactoryHostRef,
+        env: &HostEnv,
+        creator: Address,
+    ) -> (u64, MarketHostRef) {
+        env.set_caller(creator);
+        let market_id = factory
+            .with_tokens(U512::from(1_000_000_000u64))
+            .create_binary_market(
+                "Will CSPR flip ETH?".to_string(),
+                env.block_time() + 86400,
+                "CoinGecko".to_string(),
+                "crypto".to_string(),
+                CurveKind::Linear,
+                PricingMode::BondingCurve,
+            );
+        let params = factory.get_market_init_params(market_id).unwrap();
+        let market = Market::deploy(env, MarketInitArgs { args: params });
+        env.set_caller(env.get_account(0));
+        (market_id, market)
+    }
+
     #[test]
     fn test_factory_init() {
         let (factory, env) = setup();
@@ -475,6 +960,446 @@ mod tests {
         
         factory.set_platform_fee(300);
         assert_eq!(factory.get_platform_fee(), 300);
+        assert_eq!(factory.get_default_fee_schedule(), FeeSchedule::flat(300));
+    }
+
+    #[test]
+    fn test_default_fee_schedule_applies_to_new_markets() {
+        let (mut factory, _vault, env) = setup_with_vault();
+        let schedule = FeeSchedule {
+            buy_fee_bps: 100,
+            sell_fee_bps: 150,
+            settlement_fee_bps: 250,
+            creator_share_bps: 2_000,
+            lp_share_bps: 3_000,
+        };
+        factory.set_default_fee_schedule(schedule);
+        assert_eq!(factory.get_platform_fee(), 250);
+        assert!(env.emitted_event(
+            &factory,
+            FeeScheduleUpdated {
+                buy_fee_bps: 100,
+                sell_fee_bps: 150,
+                settlement_fee_bps: 250,
+                creator_share_bps: 2_000,
+                lp_share_bps: 3_000,
+            }
+        ));
+
+        let (market_id, market) = create_and_deploy(&mut factory, &env, env.get_account(1));
+        assert_eq!(factory.get_market_init_params(market_id).unwrap().fees, schedule);
+        assert_eq!(market.get_config().fees, schedule);
+
+        // Every fee is bounded by MAX_FEE_BPS
+        env.set_caller(env.get_account(0));
+        let too_high = FeeSchedule { buy_fee_bps: MAX_FEE_BPS + 1, ..schedule };
+        assert_eq!(factory.try_set_default_fee_schedule(too_high), Err(Error::FeeTooHigh.into()));
+        env.set_caller(env.get_account(1));
+        assert_eq!(factory.try_set_default_fee_schedule(schedule), Err(Error::NotAdmin.into()));
+    }
+
+    #[test]
+    fn test_markets_pick_their_bonding_curve() {
+        let (mut factory, _vault, env) = setup_with_vault();
+        let exponential = BondingCurveParams {
+            k_constant: U256::from(5_000_000u64),
+            ..BondingCurveParams::preset(CurveKind::Exponential)
+        };
+        factory.set_default_bonding_params(exponential.clone());
+        assert_eq!(factory.get_default_bonding_params(CurveKind::Exponential), exponential);
+        assert_eq!(
+            factory.get_default_bonding_params(CurveKind::Linear),
+            BondingCurveParams::default_params()
+        );
+
+        let mut params = compound_params(&env, &[2, 2]);
+        params.curve = CurveKind::Exponential;
+        let market_id = factory
+            .with_tokens(U512::from(1_000_000_000u64))
+            .create_compound_market(params);
+        let init_params = factory.get_market_init_params(market_id).unwrap();
+        assert_eq!(init_params.bonding_params, exponential);
+
+        let market_id = factory
+            .with_tokens(U512::from(1_000_000_000u64))
+            .create_multiple_choice_market(
+                "Who wins?".to_string(),
+                vec!["A".to_string(), "B".to_string(), "C".to_string()],
+                env.block_time() + 86400,
+                "League results".to_string(),
+                "sports".to_string(),
+                CurveKind::CappedSigmoid,
+                PricingMode::BondingCurve,
+            );
+        let init_params = factory.get_market_init_params(market_id).unwrap();
+        assert_eq!(init_params.bonding_params, BondingCurveParams::preset(CurveKind::CappedSigmoid));
+        let market = Market::deploy(&env, MarketInitArgs { args: init_params });
+        assert!(market.get_current_price(0) < U256::from(1_000_000_000u64));
+
+        // A sigmoid whose cap is below its starting price is rejected
+        let inverted = BondingCurveParams {
+            max_price: U256::one(),
+            ..BondingCurveParams::preset(CurveKind::CappedSigmoid)
+        };
+        assert_eq!(
+            factory.try_set_default_bonding_params(inverted),
+            Err(Error::InvalidInitParams.into())
+        );
+    }
+
+    #[test]
+    fn test_lmsr_market_is_funded_by_the_seed() {
+        let (mut factory, _vault, env) = setup_with_vault();
+        let mut params = compound_params(&env, &[2, 2]);
+        params.pricing = PricingMode::Lmsr;
+        let market_id = factory
+            .with_tokens(U512::from(1_000_000_000u64))
+            .create_compound_market(params.clone());
+        assert!(matches!(factory.get_market_data(market_id).unwrap().pricing, PricingMode::Lmsr));
+
+        let init_params = factory.get_market_init_params(market_id).unwrap();
+        let market = Market::deploy(&env, MarketInitArgs { args: init_params });
+        let config = market.get_config();
+        assert!(matches!(config.pricing, PricingMode::Lmsr));
+        assert_eq!(config.lmsr_liquidity, liquidity_for_subsidy(U256::from(1_000_000_000u64), 4));
+        assert_eq!(market.get_outcome_odds(), vec![(0, 2_500), (1, 2_500), (2, 2_500), (3, 2_500)]);
+
+        // Without a seed there is nothing to fund the market maker
+        factory.set_min_initial_liquidity(U256::zero());
+        assert_eq!(
+            factory.try_create_compound_market(params),
+            Err(Error::InsufficientInitialLiquidity.into())
+        );
+    }
+
+    #[test]
+    fn test_create_market_stores_init_params() {
+        let (mut factory, _vault, env) = setup_with_vault();
+        let creator = env.get_account(1);
+        let (market_id, market) = create_and_deploy(&mut factory, &env, creator);
+
+        let params = factory.get_market_init_params(market_id).unwrap();
+        assert_eq!(params.creator, creator);
+        assert_eq!(params.admin, env.get_account(0));
+        assert_eq!(params.fees, FeeSchedule::flat(200));
+        assert_eq!(params.factory_contract, factory.address());
+        assert_eq!(params.outcome_names.len(), 2);
+        assert_eq!(market.get_outcome_count(), 2);
+
+        // Not registered until the deployed contract is attached
+        assert_eq!(factory.get_market_address(market_id), None);
+    }
+
+    #[test]
+    fn test_initial_liquidity_seeds_market_in_vault() {
+        let (mut factory, vault, env) = setup_with_vault();
+        let creator = env.get_account(1);
+        let (market_id, market) = create_and_deploy(&mut factory, &env, creator);
+
+        let seed = U256::from(1_000_000_000u64);
+        assert_eq!(vault.get_market_balance(market_id), seed);
+        assert_eq!(env.balance_of(&factory), U512::zero());
+        assert_eq!(market.get_seed_liquidity().amount, seed);
+    }
+
+    #[test]
+    fn test_registered_market_is_tradeable() {
+        let (mut factory, vault, env) = setup_with_vault();
+        let creator = env.get_account(1);
+        let (market_id, market) = create_and_deploy(&mut factory, &env, creator);
+
+        factory.register_market(market_id, market.address());
+        assert_eq!(factory.get_market_address(market_id), Some(market.address()));
+        assert!(vault.is_market_authorized(market.address()));
+
+        let trader = env.get_account(2);
+        env.set_caller(trader);
+        market
+            .with_tokens(U512::from(1_000_000_000u64))
+            .buy_shares(0, U256::one());
+        assert!(!market.get_user_position(trader, 0).shares.is_zero());
+    }
+
+    #[test]
+    fn test_register_market_rejects_duplicates_and_mismatches() {
+        let (mut factory, _vault, env) = setup_with_vault();
+        let creator = env.get_account(1);
+        let (first_id, first) = create_and_deploy(&mut factory, &env, creator);
+        let (second_id, _second) = create_and_deploy(&mut factory, &env, creator);
+
+        // A contract deployed for one market cannot be registered for another
+        assert_eq!(
+            factory.try_register_market(second_id, first.address()),
+            Err(Error::MarketMismatch.into())
+        );
+
+        factory.register_market(first_id, first.address());
+        assert_eq!(
+            factory.try_register_market(first_id, first.address()),
+            Err(Error::MarketAlreadyRegistered.into())
+        );
+
+        // Only the admin registers contracts, the market creator included
+        env.set_caller(creator);
+        assert_eq!(
+            factory.try_register_market(second_id, first.address()),
+            Err(Error::NotAdmin.into())
+        );
+    }
+
+    #[test]
+    fn test_unregistered_market_seed_is_refunded_on_rejection() {
+        let (mut factory, vault, env) = setup_with_vault();
+        let creator = env.get_account(1);
+        let (first_id, first) = create_and_deploy(&mut factory, &env, creator);
+        let (second_id, _second) = create_and_deploy(&mut factory, &env, creator);
+        let seed = factory.get_market_init_params(first_id).unwrap().initial_liquidity;
+
+        // The admin can reject an unregistered market, refunding its creator
+        let before = env.balance_of(&creator);
+        factory.reject_market(first_id);
+        assert_eq!(env.balance_of(&creator) - before, u256_to_u512(seed));
+        assert_eq!(vault.get_market_balance(first_id), U256::zero());
+        assert!(env.emitted_event(&factory, MarketRejected { market_id: first_id, creator, refund: seed }));
+        assert!(factory.get_market_data(first_id).unwrap().rejected);
+        assert_eq!(factory.try_reject_market(first_id), Err(Error::MarketRejected.into()));
+        assert_eq!(
+            factory.try_register_market(first_id, first.address()),
+            Err(Error::MarketRejected.into())
+        );
+
+        // The creator only once the market has ended unregistered
+        env.set_caller(env.get_account(2));
+        assert_eq!(factory.try_reject_market(second_id), Err(Error::AccessDenied.into()));
+        env.set_caller(creator);
+        assert_eq!(factory.try_reject_market(second_id), Err(Error::MarketNotEnded.into()));
+        env.advance_block_time(86_401);
+        factory.reject_market(second_id);
+        assert_eq!(vault.get_market_balance(second_id), U256::zero());
+
+        // Registered markets keep their seed
+        let (third_id, third) = create_and_deploy(&mut factory, &env, creator);
+        factory.register_market(third_id, third.address());
+        assert_eq!(factory.try_reject_market(third_id), Err(Error::MarketAlreadyRegistered.into()));
+    }
+
+    #[test]
+    fn test_register_market_compares_every_init_param() {
+        let (mut factory, _vault, env) = setup_with_vault();
+        let creator = env.get_account(1);
+        let (market_id, _market) = create_and_deploy(&mut factory, &env, creator);
+        let params = factory.get_market_init_params(market_id).unwrap();
+
+        // A genuine Market the creator resolves, or without fees, is rejected
+        let tampered: [fn(&mut MarketInitParams); 3] = [
+            |params| params.resolver = params.creator,
+            |params| params.fees = FeeSchedule::flat(0),
+            |params| params.end_time += 1,
+        ];
+        for tamper in tampered {
+            let mut args = params.clone();
+            tamper(&mut args);
+            let market = Market::deploy(&env, MarketInitArgs { args });
+            assert_eq!(
+                factory.try_register_market(market_id, market.address()),
+                Err(Error::MarketMismatch.into())
+            );
+        }
+    }
+
+    #[test]
+    fn test_oracle_binding_is_part_of_the_deployed_params() {
+        let (mut factory, _vault, env) = setup_with_vault();
+        let creator = env.get_account(1);
+        let (market_id, unbound) = create_and_deploy(&mut factory, &env, creator);
+        let binding = OracleBinding { oracle: env.get_account(5), query_id: 7, max_age: 3_600_000 };
+
+        env.set_caller(env.get_account(3));
+        assert_eq!(
+            factory.try_bind_market_oracle(market_id, binding.clone()),
+            Err(Error::AccessDenied.into())
+        );
+
+        // The creator may only pick an oracle the admin approved
+        env.set_caller(creator);
+        assert_eq!(
+            factory.try_bind_market_oracle(market_id, binding.clone()),
+            Err(Error::OracleNotApproved.into())
+        );
+        assert_eq!(
+            factory.try_set_oracle_approved(binding.oracle, true),
+            Err(Error::NotAdmin.into())
+        );
+        env.set_caller(env.get_account(0));
+        factory.set_oracle_approved(binding.oracle, true);
+        assert!(factory.is_oracle_approved(binding.oracle));
+        env.set_caller(creator);
+        factory.bind_market_oracle(market_id, binding.clone());
+        let params = factory.get_market_init_params(market_id).unwrap();
+        assert_eq!(params.oracle, Some(binding.clone()));
+
+        // A contract deployed without the binding does not match the registry
+        env.set_caller(env.get_account(0));
+        assert_eq!(
+            factory.try_register_market(market_id, unbound.address()),
+            Err(Error::MarketMismatch.into())
+        );
+        let market = Market::deploy(&env, MarketInitArgs { args: params });
+        factory.register_market(market_id, market.address());
+        assert_eq!(market.get_config().oracle, Some(binding.clone()));
+        assert_eq!(
+            factory.try_bind_market_oracle(market_id, binding),
+            Err(Error::MarketAlreadyRegistered.into())
+        );
+    }
+
+    #[test]
+    fn test_optimistic_resolution_is_opt_in() {
+        let (mut factory, _vault, env) = setup_with_vault();
+        let creator = env.get_account(1);
+        let (market_id, _unconfigured) = create_and_deploy(&mut factory, &env, creator);
+        let optimistic = OptimisticParams::default_params();
+        assert_eq!(factory.get_market_init_params(market_id).unwrap().optimistic, None);
+
+        env.set_caller(env.get_account(3));
+        assert_eq!(
+            factory.try_enable_optimistic_resolution(market_id, optimistic),
+            Err(Error::AccessDenied.into())
+        );
+        env.set_caller(creator);
+        factory.enable_optimistic_resolution(market_id, optimistic);
+        assert_eq!(factory.get_market_init_params(market_id).unwrap().optimistic, Some(optimistic));
+
+        // Oracle-bound markets resolve from their oracle instead
+        let (market_id, _unconfigured) = create_and_deploy(&mut factory, &env, creator);
+        let binding = OracleBinding { oracle: env.get_account(5), query_id: 7, max_age: 3_600_000 };
+        factory.bind_market_oracle(market_id, binding);
+        env.set_caller(creator);
+        assert_eq!(
+            factory.try_enable_optimistic_resolution(market_id, optimistic),
+            Err(Error::OptimisticResolutionDisabled.into())
+        );
+    }
+
+    #[test]
+    fn test_default_committee_is_given_to_new_markets() {
+        let (mut factory, _vault, env) = setup_with_vault();
+        let committee = ResolverCommittee {
+            members: vec![env.get_account(4), env.get_account(5)],
+            threshold: 2,
+        };
+
+        env.set_caller(env.get_account(0));
+        let unreachable = ResolverCommittee { threshold: 3, ..committee.clone() };
+        assert_eq!(
+            factory.try_set_default_resolver_committee(Some(unreachable)),
+            Err(Error::InvalidCommittee.into())
+        );
+        let minority = ResolverCommittee { threshold: 1, ..committee.clone() };
+        assert_eq!(
+            factory.try_set_default_resolver_committee(Some(minority)),
+            Err(Error::InvalidCommittee.into())
+        );
+        factory.set_default_resolver_committee(Some(committee.clone()));
+        assert_eq!(factory.get_default_resolver_committee(), Some(committee.clone()));
+
+        let (market_id, market) = create_and_deploy(&mut factory, &env, env.get_account(1));
+        factory.register_market(market_id, market.address());
+        assert_eq!(market.get_config().committee, Some(committee));
+
+        env.set_caller(env.get_account(0));
+        factory.set_default_resolver_committee(None);
+        let (_, market) = create_and_deploy(&mut factory, &env, env.get_account(1));
+        assert_eq!(market.get_config().committee, None);
+    }
+
+    fn compound_params(env: &HostEnv, leg_sizes: &[usize]) -> CompoundMarketParams {
+        let legs = leg_sizes
+            .iter()
+            .enumerate()
+            .map(|(i, size)| CompoundLeg {
+                question: format!("Leg {}?", i),
+                outcomes: (0..*size).map(|o| format!("Result {}", o)).collect(),
+            })
+            .collect();
+        CompoundMarketParams {
+            question: "Weekend parlay".to_string(),
+            legs,
+            end_time: env.block_time() + 86400,
+            resolution_source: "League results".to_string(),
+            category: "sports".to_string(),
+            curve: CurveKind::Linear,
+            pricing: PricingMode::BondingCurve,
+        }
+    }
+
+    #[test]
+    fn test_create_compound_market() {
+        let (mut factory, _vault, env) = setup_with_vault();
+        let market_id = factory
+            .with_tokens(U512::from(1_000_000_000u64))
+            .create_compound_market(compound_params(&env, &[2, 3]));
+
+        let params = factory.get_market_init_params(market_id).unwrap();
+        assert!(matches!(params.market_type, MarketType::Compound));
+        assert_eq!(params.legs.len(), 2);
+        assert_eq!(params.outcome_names.len(), 6);
+        assert_eq!(params.outcome_names[0], "Result 0 & Result 0");
+
+        // Deploys and registers like any other market
+        let market = Market::deploy(&env, MarketInitArgs { args: params });
+        factory.register_market(market_id, market.address());
+        assert_eq!(market.get_compound_outcome_id(vec![1, 2]), Some(5));
+    }
+
+    #[test]
+    fn test_create_compound_market_validates_legs() {
+        let (factory, _vault, env) = setup_with_vault();
+        let create = |leg_sizes: &[usize]| {
+            factory
+                .with_tokens(U512::from(1_000_000_000u64))
+                .try_create_compound_market(compound_params(&env, leg_sizes))
+        };
+
+        let invalid = Err(Error::InvalidOutcomeCount.into());
+        assert_eq!(create(&[2]), invalid);
+        assert_eq!(create(&[2, 1]), invalid);
+        assert_eq!(create(&[4, 4, 5]), invalid);
+        assert!(create(&[4, 4, 4]).is_ok());
+    }
+
+    #[test]
+    fn test_create_scalar_market() {
+        let (mut factory, _vault, env) = setup_with_vault();
+        let scalar_params = |lower_bound, upper_bound| ScalarMarketParams {
+            question: "CSPR price on Dec 31, in 1e-4 USD?".to_string(),
+            range: ScalarRange { lower_bound, upper_bound },
+            end_time: env.block_time() + 86400,
+            resolution_source: "CoinGecko".to_string(),
+            category: "crypto".to_string(),
+            curve: CurveKind::Linear,
+            pricing: PricingMode::BondingCurve,
+        };
+        assert_eq!(
+            factory
+                .with_tokens(U512::from(1_000_000_000u64))
+                .try_create_scalar_market(scalar_params(1_000, 100)),
+            Err(Error::InvalidScalarRange.into())
+        );
+
+        let market_id = factory
+            .with_tokens(U512::from(1_000_000_000u64))
+            .create_scalar_market(scalar_params(100, 1_000));
+        let params = factory.get_market_init_params(market_id).unwrap();
+        assert!(matches!(params.market_type, MarketType::Scalar));
+        assert_eq!(params.outcome_names, vec!["Long".to_string(), "Short".to_string()]);
+
+        let market = Market::deploy(&env, MarketInitArgs { args: params });
+        factory.register_market(market_id, market.address());
+        assert_eq!(
+            market.get_metadata().scalar,
+            Some(ScalarRange { lower_bound: 100, upper_bound: 1_000 })
+        );
     }
 
     #[test]
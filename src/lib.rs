@@ -14,7 +14,7 @@
 //! │                 MarketFactory                        │
 //! │  - Creates all market types                         │
 //! │  - Manages market registry                          │
-//! │  - Deploys Market contracts                         │
+//! │  - Registers deployed Market contracts              │
 //! └──────────────┬──────────────────────────────────────┘
 //!                │
 //!                │ Creates
@@ -33,12 +33,17 @@
 //!
 //! ## Modules
 //!
-//! - [`types`] - Core data types and bonding curve math
+//! - [`types`] - Core data types and bonding curve parameters
+//! - [`curves`] - Bonding curve families (linear, exponential, sqrt, capped sigmoid)
+//! - [`lmsr`] - Logarithmic market scoring rule market maker
+//! - [`cpmm`] - Constant-product market maker over complete sets
+//! - [`oracle`] - Oracle interface for resolving markets
 //! - [`events`] - Event definitions for all contracts
 //! - [`errors`] - Custom error types
 //! - [`vault`] - Secure CSPR escrow contract
 //! - [`market`] - Individual prediction market contract
 //! - [`factory`] - Market creation and registry contract
+//! - [`token`] - CEP-18 token interface over a market outcome's shares
 
 #![cfg_attr(not(test), no_std)]
 #![cfg_attr(not(test), no_main)]
@@ -46,13 +51,19 @@
 extern crate alloc;
 
 pub mod types;
+pub mod curves;
+pub mod lmsr;
+pub mod cpmm;
+pub mod oracle;
 pub mod events;
 pub mod errors;
 pub mod vault;
 pub mod market;
 pub mod factory;
+pub mod token;
 
 // Re-export main contracts for convenience
 pub use vault::Vault;
 pub use market::Market;
 pub use factory::MarketFactory;
+pub use token::OutcomeToken;
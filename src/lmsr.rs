@@ -0,0 +1,294 @@
+//! Logarithmic market scoring rule (LMSR) market maker.
+//!
+//! Unlike the bonding curves, which price every outcome on its own, the LMSR
+//! prices all outcomes of a market together through the cost function
+//!
+//! ```text
+//! C(q) = b * ln(sum_i exp(q_i / b))
+//! ```
+//!
+//! where `q_i` is what outcome `i` owes its holders if it wins and `b` is the
+//! liquidity parameter. Buying moves the market from `q` to `q'` for
+//! `C(q') - C(q)`, the price of outcome `i` is `exp(q_i / b) / sum_j exp(q_j / b)`,
+//! so prices are probabilities summing to one, and the market maker never
+//! loses more than `b * ln(outcome_count)`.
+//!
+//! Every share pays out [`LMSR_SHARE_VALUE`] if its outcome wins. All math is
+//! in 18-decimal fixed point; exponents are taken relative to the largest
+//! supply, so they never exceed one and cannot overflow.
+
+use crate::types::implied_probabilities_bps;
+use odra::casper_types::U256;
+use odra::prelude::*;
+
+/// Payout of one winning LMSR share in motes (1 CSPR)
+pub const LMSR_SHARE_VALUE: u64 = 1_000_000_000;
+
+/// Fixed-point one (1e18)
+const WAD: u64 = 1_000_000_000_000_000_000;
+
+/// ln(2) in fixed point
+const LN_2: u64 = 693_147_180_559_945_309;
+
+/// Exponents beyond which `exp(-x)` rounds to zero in fixed point (e^-42 < 1e-18)
+const MAX_NEGATIVE_EXPONENT: u64 = 42;
+
+fn wad() -> U256 {
+    U256::from(WAD)
+}
+
+/// `exp(r)` for `0 <= r < ln(2)`, by its Taylor series.
+fn exp_small(r: U256) -> U256 {
+    let mut sum = wad();
+    let mut term = wad();
+    let mut i = 1u64;
+    loop {
+        term = term * r / wad() / U256::from(i);
+        if term.is_zero() {
+            return sum;
+        }
+        sum += term;
+        i += 1;
+    }
+}
+
+/// `exp(-x)` in fixed point, for `x >= 0`.
+fn exp_neg(x: U256) -> U256 {
+    if x >= U256::from(MAX_NEGATIVE_EXPONENT) * wad() {
+        return U256::zero();
+    }
+    // exp(-x) = exp(-r) / 2^k with x = k * ln(2) + r
+    let ln_2 = U256::from(LN_2);
+    let k = (x / ln_2).as_usize();
+    let r = x % ln_2;
+    (wad() * wad() / exp_small(r)) >> k
+}
+
+/// `ln(x)` in fixed point, for `x >= 1` (i.e. `x >= WAD`).
+fn ln(x: U256) -> U256 {
+    if x <= wad() {
+        return U256::zero();
+    }
+    // ln(x) = k * ln(2) + ln(m) with x = 2^k * m and 1 <= m < 2
+    let mut k = 0usize;
+    while (x >> (k + 1)) >= wad() {
+        k += 1;
+    }
+    let m = x >> k;
+
+    // ln(m) = 2 * atanh(s) = 2 * (s + s^3/3 + s^5/5 + ...), s = (m - 1) / (m + 1) <= 1/3
+    let s = (m - wad()) * wad() / (m + wad());
+    let s_squared = s * s / wad();
+    let mut series = U256::zero();
+    let mut term = s;
+    let mut divisor = 1u64;
+    while !term.is_zero() {
+        series += term / U256::from(divisor);
+        term = term * s_squared / wad();
+        divisor += 2;
+    }
+    U256::from(LN_2) * U256::from(k) + series * U256::from(2u64)
+}
+
+/// `ln(1 / p)` in fixed point, for a probability `0 < p <= 1`.
+fn ln_inverse(p: U256) -> U256 {
+    ln(wad() * wad() / p.max(U256::one()))
+}
+
+/// Liquidity parameter `b` whose worst-case loss `b * ln(outcome_count)`
+/// is covered by `subsidy` motes.
+pub fn liquidity_for_subsidy(subsidy: U256, outcome_count: u64) -> U256 {
+    let ln_outcomes = ln(U256::from(outcome_count) * wad());
+    if ln_outcomes.is_zero() {
+        return U256::zero();
+    }
+    subsidy.saturating_mul(wad()) / ln_outcomes
+}
+
+/// LMSR market maker with liquidity parameter `liquidity` (`b`, in motes).
+///
+/// Supplies are outstanding shares per outcome, indexed by outcome ID.
+pub struct Lmsr {
+    pub liquidity: U256,
+}
+
+impl Lmsr {
+    /// Largest supply and every outcome's `exp((q_i - q_max) / b)`.
+    fn weights(&self, supplies: &[U256]) -> (U256, Vec<U256>) {
+        let max_supply = supplies.iter().copied().max().unwrap_or_default();
+        let weights = supplies
+            .iter()
+            .map(|supply| {
+                let exponent = (max_supply - *supply)
+                    .checked_mul(U256::from(LMSR_SHARE_VALUE))
+                    .and_then(|owed| owed.checked_mul(wad()))
+                    .map(|owed| owed / self.liquidity);
+                exponent.map_or(U256::zero(), exp_neg)
+            })
+            .collect();
+        (max_supply, weights)
+    }
+
+    /// `C(q)` in fixed-point motes.
+    fn cost_function(&self, supplies: &[U256]) -> U256 {
+        let (max_supply, weights) = self.weights(supplies);
+        let sum = weights.iter().fold(U256::zero(), |sum, weight| sum + *weight);
+        max_supply
+            .saturating_mul(U256::from(LMSR_SHARE_VALUE))
+            .saturating_mul(wad())
+            .saturating_add(self.liquidity.saturating_mul(ln(sum)))
+    }
+
+    /// Probability of an outcome in fixed point.
+    pub fn probability(&self, supplies: &[U256], outcome_id: usize) -> U256 {
+        let (_, weights) = self.weights(supplies);
+        let sum = weights.iter().fold(U256::zero(), |sum, weight| sum + *weight);
+        weights[outcome_id] * wad() / sum
+    }
+
+    /// Marginal price of a share of an outcome, in motes.
+    pub fn price(&self, supplies: &[U256], outcome_id: usize) -> U256 {
+        self.probability(supplies, outcome_id) * U256::from(LMSR_SHARE_VALUE) / wad()
+    }
+
+    /// Probabilities of all outcomes in basis points, summing to 10_000.
+    pub fn probabilities_bps(&self, supplies: &[U256]) -> Vec<u64> {
+        let probabilities: Vec<U256> =
+            (0..supplies.len()).map(|outcome_id| self.probability(supplies, outcome_id)).collect();
+        implied_probabilities_bps(&probabilities)
+    }
+
+    /// Cost to buy `shares` of an outcome, rounded up.
+    pub fn cost_to_buy(&self, supplies: &[U256], outcome_id: usize, shares: U256) -> U256 {
+        if shares.is_zero() {
+            return U256::zero();
+        }
+        let mut after = supplies.to_vec();
+        after[outcome_id] = after[outcome_id].saturating_add(shares);
+        let difference = self.cost_function(&after).saturating_sub(self.cost_function(supplies));
+        (difference + wad() - U256::one()) / wad()
+    }
+
+    /// Revenue from selling `shares` of an outcome, rounded down.
+    ///
+    /// Both roundings favour the market maker, so trades can never drain
+    /// more than `C(q) - C(0)` from the pool.
+    pub fn revenue_from_sell(&self, supplies: &[U256], outcome_id: usize, shares: U256) -> U256 {
+        if shares.is_zero() || supplies[outcome_id] < shares {
+            return U256::zero();
+        }
+        let mut after = supplies.to_vec();
+        after[outcome_id] -= shares;
+        self.cost_function(supplies).saturating_sub(self.cost_function(&after)) / wad()
+    }
+
+    /// Largest number of shares of an outcome that cost at most `max_cost`.
+    ///
+    /// Inverts the cost function in closed form,
+    /// `n * V / b = x + ln(1 / p) - ln(1 / (1 - (1 - p) * exp(-x)))` with
+    /// `x = max_cost / b` and `p` the outcome's probability, then corrects
+    /// the fixed-point rounding share by share.
+    pub fn shares_for_cost(&self, supplies: &[U256], outcome_id: usize, max_cost: U256) -> U256 {
+        if max_cost.is_zero() || self.liquidity.is_zero() {
+            return U256::zero();
+        }
+        let p = self.probability(supplies, outcome_id);
+        let x = max_cost.saturating_mul(wad()) / self.liquidity;
+        let remaining = wad() - (wad() - p.min(wad())) * exp_neg(x) / wad();
+        let exponent = x
+            .saturating_add(ln_inverse(p))
+            .saturating_sub(ln_inverse(remaining));
+        let value = U256::from(LMSR_SHARE_VALUE).saturating_mul(wad());
+        let mut shares = exponent.saturating_mul(self.liquidity) / value;
+
+        while self.cost_to_buy(supplies, outcome_id, shares.saturating_add(U256::one())) <= max_cost {
+            shares += U256::one();
+        }
+        while !shares.is_zero() && self.cost_to_buy(supplies, outcome_id, shares) > max_cost {
+            shares -= U256::one();
+        }
+        shares
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_CSPR: u64 = 1_000_000_000;
+
+    fn supplies(shares: &[u64]) -> Vec<U256> {
+        shares.iter().map(|shares| U256::from(*shares)).collect()
+    }
+
+    fn assert_close(actual: U256, expected: U256, tolerance: u64) {
+        let difference = if actual > expected { actual - expected } else { expected - actual };
+        assert!(difference <= U256::from(tolerance), "{} is not within {} of {}", actual, tolerance, expected);
+    }
+
+    #[test]
+    fn test_fixed_point_exp_and_ln() {
+        // exp(-1) and ln(10) to 15 significant digits
+        assert_close(exp_neg(wad()), U256::from(367_879_441_171_442_321u64), 1_000);
+        assert_close(ln(U256::from(10u64) * wad()), U256::from(2_302_585_092_994_045_684u64), 1_000);
+        assert_eq!(exp_neg(U256::zero()), wad());
+        assert_eq!(ln(wad()), U256::zero());
+        assert_eq!(exp_neg(U256::from(50u64) * wad()), U256::zero());
+    }
+
+    #[test]
+    fn test_prices_are_probabilities() {
+        let lmsr = Lmsr { liquidity: U256::from(100 * ONE_CSPR) };
+        let flat = supplies(&[0, 0, 0, 0]);
+        assert_eq!(lmsr.probabilities_bps(&flat), vec![2_500; 4]);
+
+        let skewed = supplies(&[150, 20, 0, 75]);
+        let total = (0..4).fold(U256::zero(), |sum, i| sum + lmsr.probability(&skewed, i));
+        assert_close(total, wad(), 10);
+        assert!(lmsr.price(&skewed, 0) > lmsr.price(&skewed, 3));
+        assert!(lmsr.price(&skewed, 0) < U256::from(ONE_CSPR));
+    }
+
+    #[test]
+    fn test_trades_are_path_independent_up_to_rounding() {
+        let lmsr = Lmsr { liquidity: U256::from(50 * ONE_CSPR) };
+        let start = supplies(&[10, 40, 5]);
+        let whole = lmsr.cost_to_buy(&start, 0, U256::from(30u64));
+        let first = lmsr.cost_to_buy(&start, 0, U256::from(10u64));
+        let second = lmsr.cost_to_buy(&supplies(&[20, 40, 5]), 0, U256::from(20u64));
+        assert!(first + second >= whole && first + second <= whole + 2);
+
+        // Selling back never returns more than buying cost
+        let revenue = lmsr.revenue_from_sell(&supplies(&[40, 40, 5]), 0, U256::from(30u64));
+        assert!(revenue <= whole && revenue + 1 >= whole);
+    }
+
+    #[test]
+    fn test_worst_case_loss_is_covered_by_subsidy() {
+        for outcomes in [2u64, 3, 8, 64] {
+            let subsidy = U256::from(10 * ONE_CSPR);
+            let lmsr = Lmsr { liquidity: liquidity_for_subsidy(subsidy, outcomes) };
+            let start = vec![U256::zero(); outcomes as usize];
+
+            // Buying every share of one outcome up to near certainty
+            let shares = U256::from(1_000u64);
+            let collected = lmsr.cost_to_buy(&start, 0, shares);
+            let owed = shares * U256::from(LMSR_SHARE_VALUE);
+            assert!(collected + subsidy >= owed);
+            assert!(owed - collected <= subsidy);
+        }
+    }
+
+    #[test]
+    fn test_shares_for_cost_is_the_largest_affordable() {
+        let lmsr = Lmsr { liquidity: U256::from(20 * ONE_CSPR) };
+        for state in [supplies(&[0, 0]), supplies(&[300, 0]), supplies(&[0, 300]), supplies(&[7, 3, 11])] {
+            for budget in [1u64, ONE_CSPR / 3, 5 * ONE_CSPR, 1_000 * ONE_CSPR] {
+                let budget = U256::from(budget);
+                let shares = lmsr.shares_for_cost(&state, 1, budget);
+                assert!(lmsr.cost_to_buy(&state, 1, shares) <= budget);
+                assert!(lmsr.cost_to_buy(&state, 1, shares + 1) > budget);
+            }
+        }
+    }
+}
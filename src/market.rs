@@ -1,46 +1,61 @@
 //! Market Contract for Casper Predict.
 //!
 //! Each Market contract represents a single prediction market with:
-//! - Bonding curve-based pricing for automatic price discovery
-//! - Support for Binary and Multiple Choice markets
-//! - Winner-take-all economics where winners receive losers' stakes
+//! - Bonding curve-based pricing for automatic price discovery, an LMSR
+//!   market maker whose prices are outcome probabilities, or a
+//!   constant-product pool of complete sets that can be minted and redeemed,
+//!   funded by liquidity providers who earn a share of trading fees
+//! - Support for Binary, Multiple Choice, Compound and Scalar markets
+//! - Winner-take-all economics where winners receive losers' stakes, or an
+//!   Invalid resolution paying every outcome's holders pro-rata
 //! - Slippage protection on all trades
+//! - Shares transferable between users, optionally through a CEP-18 token
+//!   per outcome
+//!
+//! The market holds no CSPR itself besides the escrow of open limit orders
+//! and the bonds of optimistic resolution:
+//! purchase value is deposited into the Vault under the market's ID and
+//! every payout is withdrawn from it.
 
 use odra::prelude::*;
-use odra::casper_types::{U256, U512};
+use odra::casper_types::U256;
+use odra::ContractRef;
 
+use crate::curves::{u256_to_u512, u512_to_u256};
 use crate::errors::Error;
 use crate::types::{
-    BondingCurveParams, MarketInfo, MarketStatus, MarketType, UserPosition,
-    MarketMetadata, MarketConfig,
+    compound_outcome_count, compound_outcome_id, implied_probabilities_bps, BondingCurveParams,
+    CompoundLeg, MarketInfo, MarketStatus, MarketType, UserPosition, MarketMetadata, MarketConfig,
+    SeedLiquidity, Settlement, FeeSchedule, PricingMode, LiquidityPool, LiquidityPosition,
+    PriceAccumulator, LimitOrder, OrderSide, OrderStatus, TradeLeg, OracleBinding, OptimisticParams,
+    ResolutionProposal, ResolverCommittee, ScalarRange, INVALID_VOTE, PRICE_CHECKPOINTS,
+    PRICE_CHECKPOINT_PERIOD,
 };
+use crate::lmsr::{liquidity_for_subsidy, Lmsr, LMSR_SHARE_VALUE};
+use crate::cpmm::CompleteSetPool;
 use crate::events::{
     SharesPurchased, SharesSold, MarketResolved, MarketClosed, MarketCancelled,
-    WinningsClaimed, RefundClaimed, ResolverUpdated,
+    WinningsClaimed, RefundClaimed, ResolverUpdated, CreatorLiquidityWithdrawn,
+    CompleteSetsMinted, CompleteSetsRedeemed, LiquidityAdded, LiquidityRemoved,
+    LiquidityFeesClaimed, OrderPlaced, OrderFilled, OrderCancelled, SharesTransferred,
+    SharesApproved, ResolutionProposed, ResolutionDisputed, DisputeResolved, ResolutionVoted,
+    ResolverCommitteeUpdated, MarketResolvedInvalid, ScalarMarketResolved, SettlementShortfall,
 };
-
-/// Convert U512 to U256 (assumes value fits)
-fn u512_to_u256(value: U512) -> U256 {
-    let mut bytes = [0u8; 32];
-    for i in 0..32 {
-        bytes[i] = value.byte(i);
-    }
-    U256::from_little_endian(&bytes)
-}
-
-/// Convert U256 to U512
-fn u256_to_u512(value: U256) -> U512 {
-    let mut bytes = [0u8; 32];
-    value.to_little_endian(&mut bytes);
-    U512::from_little_endian(&bytes)
-}
+use crate::oracle::OracleAdapterContractRef;
+use crate::token::OutcomeTokenContractRef;
+use crate::vault::VaultContractRef;
 
 /// A prediction market with bonding curve pricing.
 #[odra::module(
     errors = Error,
     events = [
         SharesPurchased, SharesSold, MarketResolved, MarketClosed,
-        MarketCancelled, WinningsClaimed, RefundClaimed, ResolverUpdated
+        MarketCancelled, WinningsClaimed, RefundClaimed, ResolverUpdated,
+        CreatorLiquidityWithdrawn, CompleteSetsMinted, CompleteSetsRedeemed,
+        LiquidityAdded, LiquidityRemoved, LiquidityFeesClaimed, OrderPlaced, OrderFilled,
+        OrderCancelled, SharesTransferred, SharesApproved, ResolutionProposed,
+        ResolutionDisputed, DisputeResolved, ResolutionVoted, ResolverCommitteeUpdated,
+        MarketResolvedInvalid, ScalarMarketResolved, SettlementShortfall
     ]
 )]
 pub struct Market {
@@ -59,35 +74,244 @@ pub struct Market {
     // =========================================================================
     /// Current market status
     status: Var<MarketStatus>,
-    /// Winning outcome (only set after resolution)
-    winning_outcome: Var<u64>,
+    /// Payout terms and the optimistic resolution under way
+    resolution: SubModule<ResolutionBook>,
     /// Total CSPR in the market (across all outcomes)
     total_liquidity: Var<U256>,
+    /// Creator's initial liquidity, held in the vault as a winners' subsidy
+    seed_liquidity: Var<SeedLiquidity>,
 
     // =========================================================================
     // Bonding Curve State
     // =========================================================================
-    /// Bonding curve parameters
-    bonding_params: Var<BondingCurveParams>,
     /// Total shares issued per outcome (outcome_id -> total_shares)
     outcome_shares: Mapping<u64, U256>,
     /// Total CSPR invested per outcome (outcome_id -> total_cspr)
     outcome_liquidity: Mapping<u64, U256>,
+    /// Cumulative prices and their checkpoints per outcome
+    prices: SubModule<PriceBook>,
+    /// Complete-set AMM pool balances and LP stakes
+    liquidity: SubModule<LiquidityBook>,
+    /// Resting limit orders
+    orders: SubModule<OrderBook>,
 
     // =========================================================================
     // User Positions
     // =========================================================================
-    /// User positions: (user_address, outcome_id) -> UserPosition
-    user_positions: Mapping<(Address, u64), UserPosition>,
+    /// User positions and share allowances
+    positions: SubModule<PositionBook>,
     /// Track if a user has participated (for enumeration purposes)
     user_participated: Mapping<Address, bool>,
     /// Track if user claimed (for resolved/cancelled markets)
     user_claimed: Mapping<Address, bool>,
 }
 
-/// Initialization arguments for a Market.
+/// Liquidity provider book of a complete-set AMM market.
+#[odra::module]
+pub struct LiquidityBook {
+    /// Pool balances and LP share accounting
+    pool: Var<LiquidityPool>,
+    /// LP stakes by provider
+    positions: Mapping<Address, LiquidityPosition>,
+}
+
+#[odra::module]
+impl LiquidityBook {
+    /// Get the pool and its LP share accounting.
+    pub fn pool(&self) -> LiquidityPool {
+        self.pool.get_or_default()
+    }
+
+    /// Store the pool.
+    pub fn set_pool(&mut self, pool: LiquidityPool) {
+        self.pool.set(pool);
+    }
+
+    /// Get a provider's stake.
+    pub fn position(&self, provider: Address) -> LiquidityPosition {
+        self.positions.get(&provider).unwrap_or_default()
+    }
+
+    /// Store a provider's stake.
+    pub fn set_position(&mut self, provider: Address, position: LiquidityPosition) {
+        self.positions.set(&provider, position);
+    }
+}
+
+/// Cumulative price of every outcome, and one checkpoint of it per
+/// `PRICE_CHECKPOINT_PERIOD` over the last `PRICE_CHECKPOINTS` periods.
+#[odra::module]
+pub struct PriceBook {
+    /// Latest accumulator reading by outcome ID
+    accumulators: Mapping<u64, PriceAccumulator>,
+    /// First reading of each period by (outcome_id, period % PRICE_CHECKPOINTS)
+    checkpoints: Mapping<(u64, u64), PriceAccumulator>,
+}
+
+#[odra::module]
+impl PriceBook {
+    /// Get an outcome's latest accumulator reading.
+    pub fn accumulator(&self, outcome_id: u64) -> Option<PriceAccumulator> {
+        self.accumulators.get(&outcome_id)
+    }
+
+    /// Store an outcome's accumulator, checkpointing it if it is the first
+    /// reading of its period.
+    pub fn set_accumulator(&mut self, outcome_id: u64, accumulator: PriceAccumulator) {
+        let slot = (outcome_id, accumulator.period() % PRICE_CHECKPOINTS);
+        let checkpointed = self.checkpoints.get(&slot).map(|checkpoint| checkpoint.period());
+        if checkpointed != Some(accumulator.period()) {
+            self.checkpoints.set(&slot, accumulator);
+        }
+        self.accumulators.set(&outcome_id, accumulator);
+    }
+
+    /// Get the checkpoint of an outcome taken in `period`, if still kept.
+    pub fn checkpoint(&self, outcome_id: u64, period: u64) -> Option<PriceAccumulator> {
+        self.checkpoints
+            .get(&(outcome_id, period % PRICE_CHECKPOINTS))
+            .filter(|checkpoint| checkpoint.period() == period)
+    }
+}
+
+/// Limit orders resting on a market, by order ID.
+#[odra::module]
+pub struct OrderBook {
+    /// Orders by ID
+    orders: Mapping<u64, LimitOrder>,
+    /// Number of orders ever placed, and the next order ID
+    order_count: Var<u64>,
+}
+
+#[odra::module]
+impl OrderBook {
+    /// Get an order.
+    pub fn get(&self, order_id: u64) -> Option<LimitOrder> {
+        self.orders.get(&order_id)
+    }
+
+    /// Store an order.
+    pub fn set(&mut self, order_id: u64, order: LimitOrder) {
+        self.orders.set(&order_id, order);
+    }
+
+    /// Store a new order and return its ID.
+    pub fn insert(&mut self, order: LimitOrder) -> u64 {
+        let order_id = self.order_count.get_or_default();
+        self.orders.set(&order_id, order);
+        self.order_count.set(order_id + 1);
+        order_id
+    }
+
+    /// Number of orders ever placed.
+    pub fn count(&self) -> u64 {
+        self.order_count.get_or_default()
+    }
+}
+
+/// User positions, the share allowances users grant each other and the
+/// token contracts exposing them.
+#[odra::module]
+pub struct PositionBook {
+    /// Positions by (user, outcome_id)
+    positions: Mapping<(Address, u64), UserPosition>,
+    /// Shares a spender may move for an owner, by (owner, spender, outcome_id)
+    allowances: Mapping<(Address, Address, u64), U256>,
+    /// CEP-18 token contracts by outcome ID
+    tokens: Mapping<u64, Address>,
+}
+
+#[odra::module]
+impl PositionBook {
+    /// Get a user's position in an outcome.
+    pub fn get(&self, user: Address, outcome_id: u64) -> UserPosition {
+        self.positions
+            .get(&(user, outcome_id))
+            .unwrap_or_else(|| UserPosition::new(outcome_id))
+    }
+
+    /// Store a user's position in an outcome.
+    pub fn set(&mut self, user: Address, outcome_id: u64, position: UserPosition) {
+        self.positions.set(&(user, outcome_id), position);
+    }
+
+    /// Shares of an outcome `spender` may transfer on behalf of `owner`.
+    pub fn allowance(&self, owner: Address, spender: Address, outcome_id: u64) -> U256 {
+        self.allowances.get(&(owner, spender, outcome_id)).unwrap_or_default()
+    }
+
+    /// Store an allowance.
+    pub fn set_allowance(&mut self, owner: Address, spender: Address, outcome_id: u64, amount: U256) {
+        self.allowances.set(&(owner, spender, outcome_id), amount);
+    }
+
+    /// Get the token contract of an outcome.
+    pub fn token(&self, outcome_id: u64) -> Option<Address> {
+        self.tokens.get(&outcome_id)
+    }
+
+    /// Store the token contract of an outcome.
+    pub fn set_token(&mut self, outcome_id: u64, token: Address) {
+        self.tokens.set(&outcome_id, token);
+    }
+}
+
+/// Settlement of a market and the optimistic resolution leading to it.
+#[odra::module]
+pub struct ResolutionBook {
+    /// Payout terms frozen at resolution, including the winning outcome
+    settlement: Var<Settlement>,
+    /// Outcome proposed under optimistic resolution
+    proposal: Var<ResolutionProposal>,
+    /// Number of times the resolver committee was rotated
+    committee_round: Var<u64>,
+    /// Committee votes by (round, member)
+    votes: Mapping<(u64, Address), u64>,
+}
+
+#[odra::module]
+impl ResolutionBook {
+    /// Get the payout terms.
+    pub fn settlement(&self) -> Settlement {
+        self.settlement.get_or_default()
+    }
+
+    /// Store the payout terms.
+    pub fn set_settlement(&mut self, settlement: Settlement) {
+        self.settlement.set(settlement);
+    }
+
+    /// Get the proposed outcome, if any.
+    pub fn proposal(&self) -> Option<ResolutionProposal> {
+        self.proposal.get()
+    }
+
+    /// Store the proposed outcome.
+    pub fn set_proposal(&mut self, proposal: ResolutionProposal) {
+        self.proposal.set(proposal);
+    }
+
+    /// Get a committee member's vote in the current round.
+    pub fn vote(&self, member: Address) -> Option<u64> {
+        self.votes.get(&(self.committee_round.get_or_default(), member))
+    }
+
+    /// Store a committee member's vote in the current round.
+    pub fn set_vote(&mut self, member: Address, outcome_id: u64) {
+        self.votes.set(&(self.committee_round.get_or_default(), member), outcome_id);
+    }
+
+    /// Start a new round, discarding every vote cast so far.
+    pub fn reset_votes(&mut self) {
+        self.committee_round.set(self.committee_round.get_or_default() + 1);
+    }
+}
+
+/// Initialization parameters for a Market.
+///
+/// Wrapped by the generated `MarketInitArgs` when deploying.
 #[odra::odra_type]
-pub struct MarketInitArgs {
+pub struct MarketInitParams {
     pub market_id: u64,
     pub market_type: MarketType,
     pub question: String,
@@ -98,20 +322,73 @@ pub struct MarketInitArgs {
     pub creator: Address,
     pub admin: Address,
     pub resolver: Address,
-    pub platform_fee_bps: u64,
+    pub fees: FeeSchedule,
     pub vault_contract: Address,
     pub factory_contract: Address,
     pub bonding_params: BondingCurveParams,
+    /// Bonding curves, LMSR or complete-set AMM; LMSR and AMM markets are
+    /// funded by the seed
+    pub pricing: PricingMode,
+    /// Seed already deposited into the vault under `market_id`
+    pub initial_liquidity: U256,
+    /// Sub-questions of a compound market (empty for other types)
+    pub legs: Vec<CompoundLeg>,
+    /// Range of a scalar market (`None` for other types)
+    pub scalar: Option<ScalarRange>,
+    /// Oracle the market can be resolved from, if any
+    pub oracle: Option<OracleBinding>,
+    /// Bond and dispute window of optimistic resolution, if the market opts in
+    pub optimistic: Option<OptimisticParams>,
+    /// Committee resolving the market by vote, if any
+    pub committee: Option<ResolverCommittee>,
+}
+
+impl MarketInitParams {
+    /// Whether a market with `metadata`, `config` and a seed of `seed` was
+    /// deployed with exactly these parameters.
+    pub fn is_deployed_as(&self, metadata: &MarketMetadata, config: &MarketConfig, seed: U256) -> bool {
+        metadata.market_id == self.market_id
+            && metadata.market_type == self.market_type
+            && metadata.question == self.question
+            && metadata.outcome_names == self.outcome_names
+            && metadata.end_time == self.end_time
+            && metadata.resolution_source == self.resolution_source
+            && metadata.category == self.category
+            && metadata.creator == self.creator
+            && metadata.legs == self.legs
+            && metadata.scalar == self.scalar
+            && config.admin == self.admin
+            && config.resolver == self.resolver
+            && config.fees == self.fees
+            && config.vault_contract == self.vault_contract
+            && config.factory_contract == self.factory_contract
+            && config.bonding_params == self.bonding_params
+            && config.pricing == self.pricing
+            && config.oracle == self.oracle
+            && config.optimistic == self.optimistic
+            && config.committee == self.committee
+            && seed == self.initial_liquidity
+    }
 }
 
 #[odra::module]
 impl Market {
     /// Initialize a new prediction market.
-    pub fn init(&mut self, args: MarketInitArgs) {
+    pub fn init(&mut self, args: MarketInitParams) {
         if self.initialized.get_or_default() {
             self.env().revert(Error::AlreadyInitialized);
         }
 
+        if !args.fees.is_valid() {
+            self.env().revert(Error::FeeTooHigh);
+        }
+        if !args.bonding_params.is_valid() {
+            self.env().revert(Error::InvalidInitParams);
+        }
+        if args.committee.as_ref().is_some_and(|committee| !committee.is_valid()) {
+            self.env().revert(Error::InvalidCommittee);
+        }
+
         // Validate outcome count
         let outcome_count = args.outcome_names.len() as u64;
         match args.market_type {
@@ -126,9 +403,26 @@ impl Market {
                 }
             }
             MarketType::Compound => {
-                // Compound markets can have any number of sub-predictions
+                // One outcome per combination of at least two legs
+                let legs_valid = args.legs.len() >= 2
+                    && args.legs.iter().all(|leg| leg.outcomes.len() >= 2);
+                if !legs_valid || outcome_count != compound_outcome_count(&args.legs) {
+                    self.env().revert(Error::InvalidOutcomeCount);
+                }
+            }
+            MarketType::Scalar => {
+                // LONG and SHORT
+                if outcome_count != 2 {
+                    self.env().revert(Error::InvalidOutcomeCount);
+                }
+                if !args.scalar.is_some_and(|range| range.is_valid()) {
+                    self.env().revert(Error::InvalidScalarRange);
+                }
             }
         }
+        if args.scalar.is_some() && !matches!(args.market_type, MarketType::Scalar) {
+            self.env().revert(Error::InvalidInitParams);
+        }
 
         // Initialize Metadata
         let metadata = MarketMetadata {
@@ -142,29 +436,70 @@ impl Market {
             end_time: args.end_time,
             resolution_source: args.resolution_source,
             category: args.category,
+            legs: args.legs,
+            scalar: args.scalar,
         };
         self.metadata.set(metadata);
 
+        // An LMSR market's seed must cover the market maker's worst-case loss,
+        // and an AMM market's seed mints the pool's first complete sets
+        let lmsr_liquidity = match args.pricing {
+            PricingMode::Lmsr => liquidity_for_subsidy(args.initial_liquidity, outcome_count),
+            _ => U256::zero(),
+        };
+        let unfunded = match args.pricing {
+            PricingMode::BondingCurve => false,
+            PricingMode::Lmsr => lmsr_liquidity.is_zero(),
+            PricingMode::CompleteSetAmm => args.initial_liquidity.is_zero(),
+        };
+        if unfunded {
+            self.env().revert(Error::InvalidInitParams);
+        }
+
         // Initialize Config
         let config = MarketConfig {
             admin: args.admin,
             resolver: args.resolver,
-            platform_fee_bps: args.platform_fee_bps,
+            fees: args.fees,
             vault_contract: args.vault_contract,
             factory_contract: args.factory_contract,
+            bonding_params: args.bonding_params,
+            pricing: args.pricing,
+            lmsr_liquidity,
+            oracle: args.oracle,
+            optimistic: args.optimistic,
+            committee: args.committee,
         };
         self.config.set(config);
 
         // Initialize bonding curve state
-        self.bonding_params.set(args.bonding_params);
         for i in 0..outcome_count {
             self.outcome_shares.set(&i, U256::zero());
             self.outcome_liquidity.set(&i, U256::zero());
         }
+        if matches!(args.pricing, PricingMode::CompleteSetAmm) {
+            // The creator's seed is the pool's first LP stake
+            self.set_pool(CompleteSetPool::seeded(outcome_count, args.initial_liquidity));
+            let mut pool = self.liquidity.pool();
+            let mut position = LiquidityPosition::default();
+            pool.mint_shares(&mut position, args.initial_liquidity, args.initial_liquidity);
+            self.liquidity.set_pool(pool);
+            self.liquidity.set_position(args.creator, position);
+        }
+
+        // Start every outcome's cumulative price at creation
+        let created = PriceAccumulator { timestamp: self.env().get_block_time(), cumulative_price: U256::zero() };
+        for outcome_id in 0..outcome_count {
+            self.prices.set_accumulator(outcome_id, created);
+        }
 
         // Set state
         self.status.set(MarketStatus::Active);
         self.total_liquidity.set(U256::zero());
+        self.seed_liquidity.set(SeedLiquidity {
+            amount: args.initial_liquidity,
+            withdrawn: false,
+        });
         self.initialized.set(true);
     }
 
@@ -174,7 +509,8 @@ impl Market {
 
     /// Buy shares in a specific outcome.
     ///
-    /// The caller must attach CSPR to this transaction.
+    /// The caller must attach CSPR to this transaction, covering both the
+    /// bonding curve cost and the buy fee on top of it.
     /// Slippage protection via min_shares parameter.
     #[odra(payable)]
     pub fn buy_shares(&mut self, outcome_id: u64, min_shares: U256) {
@@ -195,12 +531,12 @@ impl Market {
             self.env().revert(Error::InvalidOutcome);
         }
 
-        // Calculate shares from bonding curve
-        let bonding_params = self.bonding_params.get_or_default();
-        let current_supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
+        // Keep room for the buy fee
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        let budget = config.fees.max_cost_before_buy_fee(attached_value);
         
-        // Binary search for the number of shares we can buy with attached_value
-        let shares = self.calculate_shares_for_cost(&bonding_params, current_supply, attached_value);
+        // Solve the pricing for the number of shares we can buy with the budget
+        let shares = self.shares_for_cost(outcome_id, budget);
         
         if shares.is_zero() {
             self.env().revert(Error::InsufficientFunds);
@@ -211,50 +547,41 @@ impl Market {
             self.env().revert(Error::SlippageExceeded);
         }
 
-        // Calculate actual cost
-        let actual_cost = bonding_params.cost_to_buy(current_supply, shares);
-        
-        // Refund excess if any
-        let refund = attached_value.saturating_sub(actual_cost);
-
-        // Update outcome state
-        let new_supply = current_supply.saturating_add(shares);
-        self.outcome_shares.set(&outcome_id, new_supply);
-        
-        let current_liquidity = self.outcome_liquidity.get(&outcome_id).unwrap_or_default();
-        self.outcome_liquidity.set(&outcome_id, current_liquidity.saturating_add(actual_cost));
+        self.execute_buy(caller, &metadata, outcome_id, shares, attached_value);
+    }
 
-        // Update total liquidity
-        let total = self.total_liquidity.get_or_default();
-        self.total_liquidity.set(total.saturating_add(actual_cost));
+    /// Buy exactly `shares` shares in a specific outcome.
+    ///
+    /// The caller must attach at least the fee-inclusive cost; any excess is
+    /// refunded. Slippage protection via the max_cost parameter.
+    #[odra(payable)]
+    pub fn buy_exact_shares(&mut self, outcome_id: u64, shares: U256, max_cost: U256) {
+        self.require_active();
+        self.require_not_ended();
 
-        // Update user position
-        let mut position = self.user_positions
-            .get(&(caller, outcome_id))
-            .unwrap_or_else(|| UserPosition::new(outcome_id));
-        position.add_shares(shares, actual_cost);
-        self.user_positions.set(&(caller, outcome_id), position);
-        self.user_participated.set(&caller, true);
+        let caller = self.env().caller();
+        let attached_value = u512_to_u256(self.env().attached_value());
 
-        // Calculate new price for event
-        let new_price = bonding_params.price_at_supply(new_supply);
+        if shares.is_zero() {
+            self.env().revert(Error::ZeroAmount);
+        }
 
-        // Emit event
-        self.env().emit_event(SharesPurchased {
-            user: caller,
-            market_id: metadata.market_id,
-            outcome_id,
-            shares,
-            cost: actual_cost,
-            new_price,
-            timestamp: self.env().get_block_time(),
-        });
+        // Validate outcome
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        if outcome_id >= metadata.outcome_count {
+            self.env().revert(Error::InvalidOutcome);
+        }
 
-        // Refund excess CSPR if any
-        if !refund.is_zero() {
-            let refund_u512 = u256_to_u512(refund);
-            self.env().transfer_tokens(&caller, &refund_u512);
+        // Check slippage against the fee-inclusive cost
+        let total_cost = self.calculate_buy_cost(outcome_id, shares);
+        if total_cost > max_cost {
+            self.env().revert(Error::SlippageExceeded);
+        }
+        if total_cost > attached_value {
+            self.env().revert(Error::InsufficientFunds);
         }
+
+        self.execute_buy(caller, &metadata, outcome_id, shares, attached_value);
     }
 
     /// Sell shares in a specific outcome.
@@ -277,549 +604,3790 @@ impl Market {
             self.env().revert(Error::InvalidOutcome);
         }
 
-        // Check user has enough shares
-        let mut position = self.user_positions
-            .get(&(caller, outcome_id))
-            .unwrap_or_else(|| UserPosition::new(outcome_id));
-        
-        if position.shares < shares {
-            self.env().revert(Error::InsufficientShares);
-        }
+        self.execute_sell(caller, &metadata, outcome_id, shares, min_receive);
+    }
 
-        // Calculate revenue from bonding curve
-        let bonding_params = self.bonding_params.get_or_default();
-        let current_supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
-        let gross_revenue = bonding_params.revenue_from_sell(current_supply, shares);
+    /// Trade several outcomes in one transaction.
+    ///
+    /// Sell legs execute first, in order, then buy legs. Sale proceeds stay
+    /// in the market to fund the buys, and the attached CSPR covers whatever
+    /// they do not. Instead of a bound per leg, slippage is bounded on the
+    /// total: the CSPR paid back to the caller, attached value included,
+    /// must be at least `min_receive`.
+    #[odra(payable, non_reentrant)]
+    pub fn batch_trade(&mut self, legs: Vec<TradeLeg>, min_receive: U256) {
+        self.require_active();
+        self.require_not_ended();
 
-        // Deduct platform fee
-        let config = self.config.get().unwrap_or_revert(&self.env());
-        let fee = gross_revenue.saturating_mul(U256::from(config.platform_fee_bps)) / U256::from(10_000u64);
-        let net_revenue = gross_revenue.saturating_sub(fee);
+        let caller = self.env().caller();
+        let attached_value = u512_to_u256(self.env().attached_value());
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        if legs.is_empty() {
+            self.env().revert(Error::ZeroAmount);
+        }
+        for leg in &legs {
+            if leg.shares.is_zero() {
+                self.env().revert(Error::ZeroAmount);
+            }
+            if leg.outcome_id >= metadata.outcome_count {
+                self.env().revert(Error::InvalidOutcome);
+            }
+        }
 
-        // Check slippage
-        if net_revenue < min_receive {
+        let (mut proceeds, mut costs, mut fees) = (U256::zero(), U256::zero(), U256::zero());
+        let sells = legs.iter().filter(|leg| matches!(leg.side, OrderSide::Sell));
+        for leg in sells {
+            let (net_revenue, fee) =
+                self.apply_sell(caller, &metadata, leg.outcome_id, leg.shares, U256::zero());
+            proceeds = proceeds.saturating_add(net_revenue);
+            fees = fees.saturating_add(fee);
+        }
+        let buys = legs.iter().filter(|leg| matches!(leg.side, OrderSide::Buy));
+        for leg in buys {
+            let (cost, fee) = self.apply_buy(caller, &metadata, leg.outcome_id, leg.shares);
+            costs = costs.saturating_add(cost).saturating_add(fee);
+            fees = fees.saturating_add(fee);
+        }
+
+        let available = attached_value.saturating_add(proceeds);
+        if available < costs {
+            self.env().revert(Error::InsufficientFunds);
+        }
+        if available - costs < min_receive {
             self.env().revert(Error::SlippageExceeded);
         }
 
-        // Update outcome state
-        let new_supply = current_supply.saturating_sub(shares);
-        self.outcome_shares.set(&outcome_id, new_supply);
+        // Settle the net flow with the vault, then book every leg's fee
+        if costs > proceeds {
+            self.deposit_to_vault(metadata.market_id, costs - proceeds);
+        } else {
+            self.withdraw_from_vault(metadata.market_id, caller, proceeds - costs);
+        }
+        self.collect_trading_fees(metadata.market_id, fees);
+        let refund = attached_value.min(available - costs);
+        if !refund.is_zero() {
+            self.env().transfer_tokens(&caller, &u256_to_u512(refund));
+        }
+    }
 
-        let current_liquidity = self.outcome_liquidity.get(&outcome_id).unwrap_or_default();
-        self.outcome_liquidity.set(&outcome_id, current_liquidity.saturating_sub(gross_revenue));
+    /// Mint complete sets of outcome shares in a complete-set AMM market.
+    ///
+    /// Every attached mote mints one share of each outcome, and the cost
+    /// basis is split evenly across the outcomes.
+    #[odra(payable)]
+    pub fn mint_complete_sets(&mut self) {
+        self.require_active();
+        self.require_not_ended();
+        self.require_complete_set_market();
 
-        // Update total liquidity (fees stay in market for now)
-        let total = self.total_liquidity.get_or_default();
-        self.total_liquidity.set(total.saturating_sub(net_revenue));
+        let caller = self.env().caller();
+        let sets = u512_to_u256(self.env().attached_value());
+        if sets.is_zero() {
+            self.env().revert(Error::ZeroAmount);
+        }
 
-        // Update user position
-        position.remove_shares(shares, net_revenue);
-        self.user_positions.set(&(caller, outcome_id), position);
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        for (outcome_id, cost) in self.split_across_outcomes(sets) {
+            let supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
+            self.outcome_shares.set(&outcome_id, supply.saturating_add(sets));
+            let liquidity = self.outcome_liquidity.get(&outcome_id).unwrap_or_default();
+            self.outcome_liquidity.set(&outcome_id, liquidity.saturating_add(cost));
 
-        // Calculate new price for event
-        let new_price = bonding_params.price_at_supply(new_supply);
+            let mut position = self.positions.get(caller, outcome_id);
+            position.add_shares(sets, cost);
+            self.positions.set(caller, outcome_id, position);
+            self.notify_token(outcome_id, None, Some(caller), sets);
+        }
+        self.user_participated.set(&caller, true);
 
-        // Emit event
-        self.env().emit_event(SharesSold {
+        let total = self.total_liquidity.get_or_default();
+        self.total_liquidity.set(total.saturating_add(sets));
+
+        self.env().emit_event(CompleteSetsMinted {
             user: caller,
             market_id: metadata.market_id,
-            outcome_id,
-            shares,
-            revenue: net_revenue,
-            new_price,
+            sets,
             timestamp: self.env().get_block_time(),
         });
 
-        // Transfer CSPR to seller
-        let net_revenue_u512 = u256_to_u512(net_revenue);
-        self.env().transfer_tokens(&caller, &net_revenue_u512);
+        self.deposit_to_vault(metadata.market_id, sets);
     }
 
-    // =========================================================================
-    // Resolution Functions
-    // =========================================================================
-
-    /// Resolve the market with the winning outcome.
+    /// Redeem complete sets of outcome shares for one mote each.
     ///
-    /// Can only be called by the designated resolver or admin.
-    pub fn resolve_market(&mut self, winning_outcome_id: u64, proof: String) {
-        self.require_closed_or_ended();
-        
-        let caller = self.env().caller();
-        let config = self.config.get().unwrap_or_revert(&self.env());
-        
-        if caller != config.resolver && caller != config.admin {
-            self.env().revert(Error::NotResolver);
+    /// Allowed in a complete-set AMM market until it is resolved or cancelled.
+    #[odra(non_reentrant)]
+    pub fn redeem_complete_sets(&mut self, sets: U256) {
+        let status = self.status.get_or_default();
+        if !matches!(status, MarketStatus::Active | MarketStatus::Closed) {
+            self.env().revert(Error::MarketNotActive);
         }
+        self.require_complete_set_market();
 
-        // Check not already resolved
-        let status = self.status.get_or_default();
-        if matches!(status, MarketStatus::Resolved) {
-            self.env().revert(Error::MarketAlreadyResolved);
+        let caller = self.env().caller();
+        if sets.is_zero() {
+            self.env().revert(Error::ZeroAmount);
         }
 
-        // Validate outcome
         let metadata = self.metadata.get().unwrap_or_revert(&self.env());
-        if winning_outcome_id >= metadata.outcome_count {
-            self.env().revert(Error::InvalidOutcome);
+        for (outcome_id, cost) in self.split_across_outcomes(sets) {
+            let mut position = self.positions.get(caller, outcome_id);
+            if position.available_shares() < sets {
+                self.env().revert(Error::InsufficientShares);
+            }
+            position.remove_shares(sets, cost);
+            self.positions.set(caller, outcome_id, position);
+            self.notify_token(outcome_id, Some(caller), None, sets);
+
+            let supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
+            self.outcome_shares.set(&outcome_id, supply.saturating_sub(sets));
+            let liquidity = self.outcome_liquidity.get(&outcome_id).unwrap_or_default();
+            self.outcome_liquidity.set(&outcome_id, liquidity.saturating_sub(cost));
         }
 
-        // Set winning outcome and status
-        self.winning_outcome.set(winning_outcome_id);
-        self.status.set(MarketStatus::Resolved);
+        let total = self.total_liquidity.get_or_default();
+        self.total_liquidity.set(total.saturating_sub(sets));
 
-        self.env().emit_event(MarketResolved {
+        self.env().emit_event(CompleteSetsRedeemed {
+            user: caller,
             market_id: metadata.market_id,
-            winning_outcome: winning_outcome_id,
-            resolver: caller,
+            sets,
             timestamp: self.env().get_block_time(),
-            proof,
         });
+
+        self.withdraw_from_vault(metadata.market_id, caller, sets);
     }
 
-    /// Claim winnings from a resolved market.
+    // =========================================================================
+    // Limit Order Functions
+    // =========================================================================
+
+    /// Place a limit order to buy an outcome once its spot price falls to
+    /// `limit_price`.
     ///
-    /// Winners receive their proportional share of the total pool.
-    #[odra(non_reentrant)]
-    pub fn claim_winnings(&mut self) {
-        let status = self.status.get_or_default();
-        if !matches!(status, MarketStatus::Resolved) {
-            self.env().revert(Error::MarketNotResolved);
+    /// The attached CSPR is escrowed in the market: `keeper_reward` of it
+    /// pays the keeper that fills the order, the rest is the budget, buy fee
+    /// included. Returns the order ID.
+    #[odra(payable)]
+    pub fn place_buy_order(
+        &mut self,
+        outcome_id: u64,
+        limit_price: U256,
+        keeper_reward: U256,
+        expires_at: u64,
+    ) -> u64 {
+        let attached_value = u512_to_u256(self.env().attached_value());
+        if attached_value <= keeper_reward {
+            self.env().revert(Error::InsufficientFunds);
         }
+        let budget = attached_value - keeper_reward;
+        self.place_order(outcome_id, OrderSide::Buy, limit_price, budget, keeper_reward, expires_at)
+    }
 
-        let caller = self.env().caller();
+    /// Place a limit order to sell `shares` of an outcome once its spot price
+    /// rises to `limit_price`.
+    ///
+    /// The shares stay in the caller's position but are reserved for the
+    /// order. The attached CSPR is escrowed as the keeper reward. Returns the
+    /// order ID.
+    #[odra(payable)]
+    pub fn place_sell_order(
+        &mut self,
+        outcome_id: u64,
+        shares: U256,
+        limit_price: U256,
+        expires_at: u64,
+    ) -> u64 {
+        let keeper_reward = u512_to_u256(self.env().attached_value());
+        self.place_order(outcome_id, OrderSide::Sell, limit_price, shares, keeper_reward, expires_at)
+    }
 
-        // Check not already claimed
-        if self.user_claimed.get(&caller).unwrap_or(false) {
-            self.env().revert(Error::AlreadyClaimed);
-        }
+    /// Fill every listed order whose limit the spot price has reached.
+    ///
+    /// Each order trades only as many shares as keep the spot price within
+    /// its limit. An order the limit stops part way keeps resting with the
+    /// unspent budget of a buy or the unsold shares of a sell. Callable by
+    /// anyone; the caller collects the part of each order's keeper reward
+    /// matching the part of the order filled. Orders that cannot fill yet
+    /// are skipped, and expired orders are closed and refunded to their
+    /// owners. Returns the number of orders filled, fully or in part.
+    #[odra(non_reentrant)]
+    pub fn execute_orders(&mut self, order_ids: Vec<u64>) -> u64 {
+        self.require_active();
+        self.require_not_ended();
+
+        let keeper = self.env().caller();
+        let now = self.env().get_block_time();
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        let mut filled = 0u64;
+        for order_id in order_ids {
+            let Some(mut order) = self.orders.get(order_id) else {
+                continue;
+            };
+            if !matches!(order.status, OrderStatus::Open) {
+                continue;
+            }
+            if order.is_expired(now) {
+                self.close_order(&metadata, order_id, order);
+                continue;
+            }
+            if !order.is_triggered(self.get_current_price(order.outcome_id)) {
+                continue;
+            }
 
-        let winning_outcome_id = self.winning_outcome.get_or_default();
+            // Part of the order filled, and of the budget left over to refund
+            let (fill, refund) = match order.side {
+                OrderSide::Buy => {
+                    let config = self.config.get().unwrap_or_revert(&self.env());
+                    let budget = config.fees.max_cost_before_buy_fee(order.amount);
+                    let affordable = self.shares_for_cost(order.outcome_id, budget);
+                    let shares = self.shares_within_limit(&order, affordable);
+                    if shares.is_zero() {
+                        continue;
+                    }
+                    let (cost, fee) = self.apply_buy(order.owner, &metadata, order.outcome_id, shares);
+                    let spent = cost.saturating_add(fee).min(order.amount);
+                    self.deposit_to_vault(metadata.market_id, spent);
+                    self.collect_trading_fees(metadata.market_id, fee);
+                    // Unless the limit stopped the fill, the budget is used up
+                    if shares == affordable {
+                        (order.amount, order.amount - spent)
+                    } else {
+                        (spent, U256::zero())
+                    }
+                }
+                OrderSide::Sell => {
+                    let shares = self.shares_within_limit(&order, order.amount);
+                    if shares.is_zero() {
+                        continue;
+                    }
+                    let mut position = self.get_user_position(order.owner, order.outcome_id);
+                    position.locked_shares = position.locked_shares.saturating_sub(shares);
+                    self.positions.set(order.owner, order.outcome_id, position);
+                    self.execute_sell(order.owner, &metadata, order.outcome_id, shares, U256::zero());
+                    (shares, U256::zero())
+                }
+            };
 
-        // Get user's winning position
-        let position = self.user_positions
-            .get(&(caller, winning_outcome_id))
-            .unwrap_or_else(|| UserPosition::new(winning_outcome_id));
+            let keeper_reward = order.keeper_reward.saturating_mul(fill) / order.amount;
+            order.keeper_reward -= keeper_reward;
+            order.amount -= fill;
+            if order.amount.is_zero() {
+                order.status = OrderStatus::Filled;
+            }
+            let (owner, remaining) = (order.owner, order.amount);
+            self.orders.set(order_id, order);
+            filled += 1;
 
-        if position.shares.is_zero() {
-            self.env().revert(Error::NoWinningPosition);
+            self.env().emit_event(OrderFilled {
+                order_id,
+                owner,
+                keeper,
+                market_id: metadata.market_id,
+                keeper_reward,
+                remaining,
+                timestamp: now,
+            });
+            if !keeper_reward.is_zero() {
+                self.env().transfer_tokens(&keeper, &u256_to_u512(keeper_reward));
+            }
+            if !refund.is_zero() {
+                self.env().transfer_tokens(&owner, &u256_to_u512(refund));
+            }
         }
+        filled
+    }
 
-        // Calculate payout
-        // Winner gets: (user_shares / total_winning_shares) * total_pool
-        let total_winning_shares = self.outcome_shares.get(&winning_outcome_id).unwrap_or_default();
-        let total_pool = self.total_liquidity.get_or_default();
+    /// Cancel an open limit order, refunding its escrow to the owner.
+    ///
+    /// The owner can cancel at any time; anyone can close an expired order.
+    #[odra(non_reentrant)]
+    pub fn cancel_order(&mut self, order_id: u64) {
+        let order = self.orders
+            .get(order_id)
+            .unwrap_or_revert_with(&self.env(), Error::OrderNotFound);
+        if !matches!(order.status, OrderStatus::Open) {
+            self.env().revert(Error::OrderNotOpen);
+        }
+        if self.env().caller() != order.owner && !order.is_expired(self.env().get_block_time()) {
+            self.env().revert(Error::NotOrderOwner);
+        }
 
-        // Deduct platform fee from total pool
-        let config = self.config.get().unwrap_or_revert(&self.env());
-        let total_fee = total_pool.saturating_mul(U256::from(config.platform_fee_bps)) / U256::from(10_000u64);
-        let distributable_pool = total_pool.saturating_sub(total_fee);
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        self.close_order(&metadata, order_id, order);
+    }
 
-        // Calculate user's share
-        let payout = if total_winning_shares.is_zero() {
-            U256::zero()
-        } else {
-            distributable_pool.saturating_mul(position.shares) / total_winning_shares
-        };
+    // =========================================================================
+    // Share Transfer Functions
+    // =========================================================================
 
-        if payout.is_zero() {
-            self.env().revert(Error::NothingToClaim);
-        }
+    /// Transfer shares of an outcome to another user.
+    ///
+    /// Prices are untouched: the shares move with their proportional cost
+    /// basis, which is what a refund pays out if the market is cancelled.
+    /// Shares reserved for sell orders cannot move.
+    pub fn transfer_shares(&mut self, to: Address, outcome_id: u64, amount: U256) {
+        let caller = self.env().caller();
+        self.move_shares(caller, to, outcome_id, amount);
+        self.notify_token(outcome_id, Some(caller), Some(to), amount);
+    }
 
-        // Mark as claimed
-        self.user_claimed.set(&caller, true);
-        
-        // Emit event needs market_id
-        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
-        self.env().emit_event(WinningsClaimed {
-            user: caller,
-            market_id: metadata.market_id,
-            payout,
-            timestamp: self.env().get_block_time(),
-        });
+    /// Allow `spender` to transfer up to `amount` of the caller's shares of
+    /// an outcome, replacing any previous allowance.
+    pub fn approve_shares(&mut self, spender: Address, outcome_id: u64, amount: U256) {
+        let owner = self.env().caller();
+        self.approve(owner, spender, outcome_id, amount);
+    }
 
-        // Transfer payout
-        let payout_u512 = u256_to_u512(payout);
-        self.env().transfer_tokens(&caller, &payout_u512);
+    /// Transfer shares of an outcome from `from` to `to` out of the
+    /// allowance `from` granted the caller.
+    pub fn transfer_shares_from(&mut self, from: Address, to: Address, outcome_id: u64, amount: U256) {
+        let spender = self.env().caller();
+        self.spend_allowance(from, spender, outcome_id, amount);
+        self.move_shares(from, to, outcome_id, amount);
+        self.notify_token(outcome_id, Some(from), Some(to), amount);
     }
 
-    /// Claim refund from a cancelled market.
-    #[odra(non_reentrant)]
-    pub fn claim_refund(&mut self) {
-        let status = self.status.get_or_default();
-        if !matches!(status, MarketStatus::Cancelled) {
-            self.env().revert(Error::MarketNotCancelled);
+    // =========================================================================
+    // Outcome Token Functions
+    // =========================================================================
+
+    /// Register the CEP-18 token contract exposing an outcome's shares.
+    ///
+    /// The token keeps no balances of its own: it forwards transfers and
+    /// approvals to the `token_*` entry points below, which only it may call,
+    /// and is told of every other balance change so it can emit its CEP-18
+    /// events. The token must have been deployed for this market and
+    /// outcome, and cannot be replaced once registered. Admin only.
+    pub fn set_outcome_token(&mut self, outcome_id: u64, token: Address) {
+        self.require_admin();
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        if outcome_id >= metadata.outcome_count {
+            self.env().revert(Error::InvalidOutcome);
+        }
+        if self.positions.token(outcome_id).is_some() {
+            self.env().revert(Error::OutcomeTokenAlreadySet);
         }
+        let token_ref = OutcomeTokenContractRef::new(self.env(), token);
+        if token_ref.get_market() != Some(self.env().self_address()) || token_ref.get_outcome_id() != outcome_id {
+            self.env().revert(Error::OutcomeTokenMismatch);
+        }
+        self.positions.set_token(outcome_id, token);
+    }
 
-        let caller = self.env().caller();
+    /// Transfer shares on behalf of `owner`, who called the outcome token.
+    pub fn token_transfer(&mut self, owner: Address, recipient: Address, outcome_id: u64, amount: U256) {
+        self.require_outcome_token(outcome_id);
+        self.move_shares(owner, recipient, outcome_id, amount);
+    }
 
-        // Check not already claimed
-        if self.user_claimed.get(&caller).unwrap_or(false) {
-            self.env().revert(Error::AlreadyClaimed);
+    /// Set an allowance on behalf of `owner`, who called the outcome token.
+    pub fn token_approve(&mut self, owner: Address, spender: Address, outcome_id: u64, amount: U256) {
+        self.require_outcome_token(outcome_id);
+        self.approve(owner, spender, outcome_id, amount);
+    }
+
+    /// Transfer shares out of the allowance `owner` granted `spender`, who
+    /// called the outcome token.
+    pub fn token_transfer_from(
+        &mut self,
+        spender: Address,
+        owner: Address,
+        recipient: Address,
+        outcome_id: u64,
+        amount: U256,
+    ) {
+        self.require_outcome_token(outcome_id);
+        self.spend_allowance(owner, spender, outcome_id, amount);
+        self.move_shares(owner, recipient, outcome_id, amount);
+    }
+
+    // =========================================================================
+    // Liquidity Functions
+    // =========================================================================
+
+    /// Add liquidity to a complete-set AMM market for LP shares.
+    ///
+    /// The attached CSPR mints complete sets. The pool keeps them in
+    /// proportion to its balances so prices do not move, and the provider
+    /// receives the outcome shares left over. LP shares are minted in
+    /// proportion to the pool's largest balance. Fees owed on an existing
+    /// stake are paid out.
+    #[odra(payable)]
+    pub fn add_liquidity(&mut self) {
+        self.require_active();
+        self.require_not_ended();
+        self.require_complete_set_market();
+
+        let caller = self.env().caller();
+        let amount = u512_to_u256(self.env().attached_value());
+        if amount.is_zero() {
+            self.env().revert(Error::ZeroAmount);
         }
 
-        // Calculate total cost basis across all outcomes
         let metadata = self.metadata.get().unwrap_or_revert(&self.env());
-        let mut total_refund = U256::zero();
+        let mut pool = self.liquidity.pool();
+        let mut position = self.liquidity.position(caller);
+        let fees = pool.settle_fees(&mut position);
 
-        for outcome_id in 0..metadata.outcome_count {
-            let position = self.user_positions
-                .get(&(caller, outcome_id))
-                .unwrap_or_else(|| UserPosition::new(outcome_id));
-            total_refund = total_refund.saturating_add(position.total_cost);
+        let pool_weight = pool.balances.iter().copied().max().unwrap_or_default();
+        let lp_shares = if pool.total_shares.is_zero() || pool_weight.is_zero() {
+            amount
+        } else {
+            amount.saturating_mul(pool.total_shares) / pool_weight
+        };
+        if lp_shares.is_zero() {
+            self.env().revert(Error::InsufficientFunds);
         }
 
-        if total_refund.is_zero() {
-            self.env().revert(Error::NoPositionToRefund);
+        for (outcome_id, balance) in (0u64..).zip(pool.balances.iter_mut()) {
+            let kept = if pool_weight.is_zero() {
+                amount
+            } else {
+                amount.saturating_mul(*balance) / pool_weight
+            };
+            *balance = balance.saturating_add(kept);
+
+            let sent_back = amount - kept;
+            if !sent_back.is_zero() {
+                let supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
+                self.outcome_shares.set(&outcome_id, supply.saturating_add(sent_back));
+                let mut user_position = self.positions.get(caller, outcome_id);
+                user_position.add_shares(sent_back, U256::zero());
+                self.positions.set(caller, outcome_id, user_position);
+                self.user_participated.set(&caller, true);
+                self.notify_token(outcome_id, None, Some(caller), sent_back);
+            }
         }
+        pool.mint_shares(&mut position, lp_shares, amount);
+        self.liquidity.set_pool(pool);
+        self.liquidity.set_position(caller, position);
 
-        // Mark as claimed
-        self.user_claimed.set(&caller, true);
+        let total = self.total_liquidity.get_or_default();
+        self.total_liquidity.set(total.saturating_add(amount));
 
-        self.env().emit_event(RefundClaimed {
-            user: caller,
+        self.env().emit_event(LiquidityAdded {
+            provider: caller,
             market_id: metadata.market_id,
-            amount: total_refund,
+            amount,
+            lp_shares,
             timestamp: self.env().get_block_time(),
         });
 
-        // Transfer refund
-        let refund_u512 = u256_to_u512(total_refund);
-        self.env().transfer_tokens(&caller, &refund_u512);
+        self.deposit_to_vault(metadata.market_id, amount);
+        self.withdraw_from_vault(metadata.market_id, caller, fees);
     }
 
-    // =========================================================================
-    // Admin Functions
-    // =========================================================================
-
-    /// Close the market for trading.
-    pub fn close_market(&mut self) {
-        self.require_admin();
-        
-        let status = self.status.get_or_default();
-        if !matches!(status, MarketStatus::Active) {
-            self.env().revert(Error::MarketNotActive);
+    /// Burn LP shares of a complete-set AMM market, paying out the fees owed.
+    ///
+    /// Before resolution the provider receives their share of every pool
+    /// balance as outcome shares, which can be redeemed as complete sets.
+    /// Once resolved they are paid their share of the pool's winning shares,
+    /// and once cancelled their deposit is refunded.
+    #[odra(non_reentrant)]
+    pub fn remove_liquidity(&mut self, lp_shares: U256) {
+        self.require_complete_set_market();
+        if lp_shares.is_zero() {
+            self.env().revert(Error::ZeroAmount);
         }
 
-        self.status.set(MarketStatus::Closed);
-        
+        let caller = self.env().caller();
         let metadata = self.metadata.get().unwrap_or_revert(&self.env());
-        self.env().emit_event(MarketClosed {
-            market_id: metadata.market_id,
-            timestamp: self.env().get_block_time(),
-        });
-    }
+        let mut pool = self.liquidity.pool();
+        let mut position = self.liquidity.position(caller);
+        if position.shares < lp_shares {
+            self.env().revert(Error::InsufficientLiquidityShares);
+        }
 
-    /// Cancel the market and enable refunds.
-    pub fn cancel_market(&mut self, reason: String) {
-        self.require_admin();
+        let mut payout = pool.settle_fees(&mut position);
+        let total_shares = pool.total_shares;
+        let deposit = pool.burn_shares(&mut position, lp_shares);
+        match self.status.get_or_default() {
+            MarketStatus::Active
+            | MarketStatus::Closed
+            | MarketStatus::Proposed
+            | MarketStatus::Disputed => {
+                let costs = self.split_across_outcomes(deposit);
+                for ((outcome_id, cost), balance) in costs.into_iter().zip(pool.balances.iter_mut()) {
+                    let sent = balance.saturating_mul(lp_shares) / total_shares;
+                    *balance = balance.saturating_sub(sent);
 
-        let status = self.status.get_or_default();
-        if matches!(status, MarketStatus::Resolved) {
-            self.env().revert(Error::MarketAlreadyResolved);
+                    let supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
+                    self.outcome_shares.set(&outcome_id, supply.saturating_add(sent));
+                    let mut user_position = self.positions.get(caller, outcome_id);
+                    user_position.add_shares(sent, cost);
+                    self.positions.set(caller, outcome_id, user_position);
+                    self.notify_token(outcome_id, None, Some(caller), sent);
+                }
+                self.user_participated.set(&caller, true);
+            }
+            MarketStatus::Resolved => {
+                // Winning shares left in the pool pay one mote each
+                let mut settlement = self.resolution.settlement();
+                let share = settlement.creator_return.saturating_mul(lp_shares) / total_shares;
+                settlement.creator_return = settlement.creator_return.saturating_sub(share);
+                self.resolution.set_settlement(settlement);
+                payout = payout.saturating_add(share);
+            }
+            MarketStatus::Cancelled => payout = payout.saturating_add(deposit),
         }
+        self.liquidity.set_pool(pool);
+        self.liquidity.set_position(caller, position);
 
-        self.status.set(MarketStatus::Cancelled);
-        
-        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
-        self.env().emit_event(MarketCancelled {
+        self.env().emit_event(LiquidityRemoved {
+            provider: caller,
             market_id: metadata.market_id,
-            reason,
+            lp_shares,
+            payout,
             timestamp: self.env().get_block_time(),
         });
+
+        self.withdraw_from_vault(metadata.market_id, caller, payout);
     }
 
-    /// Update the resolver address.
-    pub fn update_resolver(&mut self, new_resolver: Address) {
-        self.require_admin();
-        
-        let mut config = self.config.get().unwrap_or_revert(&self.env());
-        let previous_resolver = config.resolver;
-        config.resolver = new_resolver;
-        self.config.set(config);
-        
+    /// Claim the LP fees accrued on the caller's stake.
+    #[odra(non_reentrant)]
+    pub fn claim_lp_fees(&mut self) {
+        let caller = self.env().caller();
+        let mut pool = self.liquidity.pool();
+        let mut position = self.liquidity.position(caller);
+        let fees = pool.settle_fees(&mut position);
+        if fees.is_zero() {
+            self.env().revert(Error::NothingToClaim);
+        }
+        self.liquidity.set_pool(pool);
+        self.liquidity.set_position(caller, position);
+
         let metadata = self.metadata.get().unwrap_or_revert(&self.env());
-        self.env().emit_event(ResolverUpdated {
+        self.env().emit_event(LiquidityFeesClaimed {
+            provider: caller,
             market_id: metadata.market_id,
-            previous_resolver,
-            new_resolver,
+            amount: fees,
+            timestamp: self.env().get_block_time(),
         });
+
+        self.withdraw_from_vault(metadata.market_id, caller, fees);
     }
 
     // =========================================================================
-    // View Functions
+    // Resolution Functions
     // =========================================================================
 
-    /// Get the current price for an outcome.
-    pub fn get_current_price(&self, outcome_id: u64) -> U256 {
-        let bonding_params = self.bonding_params.get_or_default();
-        let current_supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
-        bonding_params.price_at_supply(current_supply)
-    }
+    /// Resolve the market with the winning outcome.
+    ///
+    /// Overrides a proposed outcome that is not final yet, settling its bonds.
+    /// Can only be called by the designated resolver or admin; by the admin
+    /// alone if the market has a resolver committee.
+    #[odra(non_reentrant)]
+    pub fn resolve_market(&mut self, winning_outcome_id: u64, proof: String) {
+        self.require_closed_or_ended();
+        
+        let caller = self.env().caller();
+        self.require_resolver();
+        let proposal = self.pending_proposal();
 
-    /// Calculate the cost to buy a specific number of shares.
-    pub fn calculate_buy_cost(&self, outcome_id: u64, shares: U256) -> U256 {
-        let bonding_params = self.bonding_params.get_or_default();
-        let current_supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
-        bonding_params.cost_to_buy(current_supply, shares)
+        self.settle(winning_outcome_id, caller, proof);
+        self.settle_bonds(proposal, Some(winning_outcome_id));
     }
 
-    /// Calculate the revenue from selling a specific number of shares.
-    pub fn calculate_sell_revenue(&self, outcome_id: u64, shares: U256) -> U256 {
-        let bonding_params = self.bonding_params.get_or_default();
-        let config = self.config.get().unwrap_or_revert(&self.env());
-        let current_supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
-        
-        let gross = bonding_params.revenue_from_sell(current_supply, shares);
-        let fee = gross.saturating_mul(U256::from(config.platform_fee_bps)) / U256::from(10_000u64);
-        gross.saturating_sub(fee)
-    }
+    /// Resolve the market as Invalid when its question has no clear answer.
+    ///
+    /// Unlike a cancellation, the market settles: every share of every
+    /// outcome pays an equal part of the pool, less the settlement fee, and
+    /// holders claim it with `claim_winnings` or `claim_refund`. Overrides
+    /// a proposed outcome that is not final yet, settling its bonds.
+    /// Can only be called by the designated resolver or admin; by the admin
+    /// alone if the market has a resolver committee, whose members can also
+    /// vote for Invalid with `vote_invalid`.
+    #[odra(non_reentrant)]
+    pub fn resolve_invalid(&mut self, proof: String) {
+        self.require_closed_or_ended();
 
-    /// Get complete market information.
-    pub fn get_market_info(&self) -> MarketInfo {
-        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
-        
-        MarketInfo {
-            market_id: metadata.market_id,
-            market_type: metadata.market_type,
-            question: metadata.question,
-            creator: metadata.creator,
-            end_time: metadata.end_time,
-            status: self.status.get_or_default(),
-            total_liquidity: self.total_liquidity.get_or_default(),
-            category: metadata.category,
-            resolution_source: metadata.resolution_source,
-        }
-    }
+        let caller = self.env().caller();
+        self.require_resolver();
+        let proposal = self.pending_proposal();
 
-    /// Get a user's position for a specific outcome.
-    pub fn get_user_position(&self, user: Address, outcome_id: u64) -> UserPosition {
-        self.user_positions
-            .get(&(user, outcome_id))
-            .unwrap_or_else(|| UserPosition::new(outcome_id))
+        self.settle_invalid(caller, proof);
+        self.settle_bonds(proposal, None);
     }
 
-    /// Get total shares for an outcome.
-    pub fn get_outcome_shares(&self, outcome_id: u64) -> U256 {
-        self.outcome_shares.get(&outcome_id).unwrap_or_default()
-    }
+    /// Resolve the market with the answer of the oracle it is bound to.
+    ///
+    /// Callable by anyone once the market has ended. The answer must have
+    /// been updated after the market's end time and at most the binding's
+    /// `max_age` ago. An invalid answer resolves the market as Invalid.
+    /// Overrides a proposed outcome that is not final yet, settling its bonds.
+    #[odra(non_reentrant)]
+    pub fn resolve_from_oracle(&mut self) {
+        self.require_closed_or_ended();
+        if matches!(self.status.get_or_default(), MarketStatus::Cancelled) {
+            self.env().revert(Error::MarketCancelled);
+        }
+        let proposal = self.pending_proposal();
 
-    /// Get total liquidity for an outcome.
-    pub fn get_outcome_liquidity(&self, outcome_id: u64) -> U256 {
-        self.outcome_liquidity.get(&outcome_id).unwrap_or_default()
-    }
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        let binding = config.oracle.unwrap_or_revert_with(&self.env(), Error::OracleNotSet);
+        let answer = OracleAdapterContractRef::new(self.env(), binding.oracle)
+            .latest_answer(binding.query_id)
+            .unwrap_or_revert_with(&self.env(), Error::OracleAnswerMissing);
 
-    /// Get the number of outcomes.
-    pub fn get_outcome_count(&self) -> u64 {
         let metadata = self.metadata.get().unwrap_or_revert(&self.env());
-        metadata.outcome_count
-    }
+        let age = self.env().get_block_time().saturating_sub(answer.updated_at);
+        if answer.updated_at < metadata.end_time || age > binding.max_age {
+            self.env().revert(Error::StaleOracleAnswer);
+        }
 
-    /// Get outcome name by ID.
-    pub fn get_outcome_name(&self, outcome_id: u64) -> Option<String> {
-        let metadata = self.metadata.get()?;
-        if (outcome_id as usize) < metadata.outcome_names.len() {
-            Some(metadata.outcome_names.get(outcome_id as usize).unwrap_or(&String::default()).clone())
+        if answer.invalid {
+            self.settle_invalid(binding.oracle, String::from("Oracle answered invalid"));
+            self.settle_bonds(proposal, None);
         } else {
-            None
+            self.settle(answer.outcome_id, binding.oracle, String::from("Oracle answer"));
+            self.settle_bonds(proposal, Some(answer.outcome_id));
         }
     }
 
-    /// Get implied odds for all outcomes (as percentage * 100).
-    /// Returns a vector of (outcome_id, odds_percentage).
-    pub fn get_outcome_odds(&self) -> Vec<(u64, u64)> {
+    /// Resolve a compound market with one result per leg.
+    ///
+    /// The winning outcome is the combination that hits every leg.
+    /// Can only be called by the designated resolver or admin.
+    pub fn resolve_compound_market(&mut self, leg_results: Vec<u64>, proof: String) {
         let metadata = self.metadata.get().unwrap_or_revert(&self.env());
-        let outcome_count = metadata.outcome_count;
-        let mut total_inverse_price = U256::zero();
-        let mut prices = Vec::new();
-
-        let bonding_params = self.bonding_params.get_or_default();
-
-        // Calculate current prices
-        for i in 0..outcome_count {
-            let supply = self.outcome_shares.get(&i).unwrap_or_default();
-            let price = bonding_params.price_at_supply(supply);
-            prices.push((i, price));
-            if !price.is_zero() {
-                // Use a large multiplier for precision
-                total_inverse_price = total_inverse_price.saturating_add(
-                    U256::from(1_000_000_000_000u64) / price
-                );
-            }
-        }
-
-        // Calculate normalized odds
-        let mut odds = Vec::new();
-        for (outcome_id, price) in prices {
-            if price.is_zero() || total_inverse_price.is_zero() {
-                odds.push((outcome_id, 0u64));
-            } else {
-                let inverse = U256::from(1_000_000_000_000u64) / price;
-                let probability = inverse.saturating_mul(U256::from(10000u64)) / total_inverse_price;
-                odds.push((outcome_id, probability.as_u64()));
-            }
+        if !matches!(metadata.market_type, MarketType::Compound) {
+            self.env().revert(Error::NotCompoundMarket);
         }
 
-        odds
-    }
-
-    /// Get the winning outcome (only valid after resolution).
-    pub fn get_winning_outcome(&self) -> Option<u64> {
-        let status = self.status.get_or_default();
-        if matches!(status, MarketStatus::Resolved) {
-            Some(self.winning_outcome.get_or_default())
-        } else {
-            None
-        }
+        let winning_outcome_id = compound_outcome_id(&metadata.legs, &leg_results)
+            .unwrap_or_revert_with(&self.env(), Error::InvalidOutcome);
+        self.resolve_market(winning_outcome_id, proof);
     }
 
-    /// Check if the market is past its end time.
-    pub fn is_ended(&self) -> bool {
+    /// Resolve a scalar market to the value its question asked about.
+    ///
+    /// LONG shares are owed the part of the payout matching where `value`
+    /// lands between the market's bounds, and SHORT shares the rest; values
+    /// outside the range pay one side in full. In a bonding curve market
+    /// each side's part of the pool is shared pro rata among its holders.
+    /// Can only be called by the designated resolver or admin; by the admin
+    /// alone if the market has a resolver committee.
+    pub fn resolve_scalar_market(&mut self, value: i64, proof: String) {
         let metadata = self.metadata.get().unwrap_or_revert(&self.env());
-        self.env().get_block_time() >= metadata.end_time
-    }
+        let range = metadata.scalar.unwrap_or_revert_with(&self.env(), Error::NotScalarMarket);
+        self.require_closed_or_ended();
 
-    /// Get the market status.
+        let caller = self.env().caller();
+        self.require_resolver();
+
+        let settlement = self.freeze_settlement(Settlement {
+            scalar_value: Some(value),
+            payout_weights: range.payout_weights(value),
+            ..Default::default()
+        });
+        self.env().emit_event(ScalarMarketResolved {
+            market_id: metadata.market_id,
+            value,
+            long_payout_bps: range.long_payout_bps(value),
+            resolver: caller,
+            timestamp: self.env().get_block_time(),
+            proof,
+        });
+
+        self.collect_fees(metadata.market_id, settlement.fee_amount);
+        self.sweep_to_fee_recipient(metadata.market_id, settlement.dust);
+    }
+
+    /// Propose the winning outcome of an ended market, bonding the attached CSPR.
+    ///
+    /// Anyone can propose once the market has ended, posting at least the
+    /// market's optimistic bond. The proposal becomes final if nobody
+    /// disputes it within the dispute window, and the bond is returned.
+    /// Only for markets that opted in to optimistic resolution and are bound
+    /// to neither an oracle nor a resolver committee.
+    #[odra(payable)]
+    pub fn propose_outcome(&mut self, outcome_id: u64) {
+        self.require_closed_or_ended();
+        self.require_not_scalar();
+        match self.status.get_or_default() {
+            MarketStatus::Resolved => self.env().revert(Error::MarketAlreadyResolved),
+            MarketStatus::Cancelled => self.env().revert(Error::MarketCancelled),
+            MarketStatus::Proposed | MarketStatus::Disputed => {
+                self.env().revert(Error::ResolutionPending)
+            }
+            MarketStatus::Active | MarketStatus::Closed => {}
+        }
+
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        if outcome_id >= metadata.outcome_count {
+            self.env().revert(Error::InvalidOutcome);
+        }
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        let optimistic = config
+            .optimistic
+            .filter(|_| config.oracle.is_none() && config.committee.is_none())
+            .unwrap_or_revert_with(&self.env(), Error::OptimisticResolutionDisabled);
+        let bond = u512_to_u256(self.env().attached_value());
+        if bond < optimistic.bond || bond.is_zero() {
+            self.env().revert(Error::InsufficientBond);
+        }
+
+        let proposer = self.env().caller();
+        let dispute_deadline = self.env().get_block_time() + optimistic.dispute_window;
+        self.resolution.set_proposal(ResolutionProposal {
+            proposer,
+            outcome_id,
+            bond,
+            dispute_deadline,
+            disputer: None,
+        });
+        self.status.set(MarketStatus::Proposed);
+
+        self.env().emit_event(ResolutionProposed {
+            market_id: metadata.market_id,
+            proposer,
+            outcome_id,
+            bond,
+            dispute_deadline,
+        });
+    }
+
+    /// Dispute the proposed outcome, bonding as much as the proposer.
+    ///
+    /// Allowed until the dispute window closes. Any CSPR attached beyond the
+    /// proposer's bond is refunded. The dispute is then settled by the
+    /// resolver or admin with `arbitrate_dispute`.
+    #[odra(payable)]
+    pub fn dispute_proposal(&mut self) {
+        if !matches!(self.status.get_or_default(), MarketStatus::Proposed) {
+            self.env().revert(Error::NoProposal);
+        }
+        let mut proposal = self.resolution.proposal().unwrap_or_revert(&self.env());
+        if self.env().get_block_time() > proposal.dispute_deadline {
+            self.env().revert(Error::DisputeWindowClosed);
+        }
+        let attached_value = u512_to_u256(self.env().attached_value());
+        if attached_value < proposal.bond {
+            self.env().revert(Error::InsufficientBond);
+        }
+
+        let disputer = self.env().caller();
+        proposal.disputer = Some(disputer);
+        let (outcome_id, bond) = (proposal.outcome_id, proposal.bond);
+        self.resolution.set_proposal(proposal);
+        self.status.set(MarketStatus::Disputed);
+
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        self.env().emit_event(ResolutionDisputed {
+            market_id: metadata.market_id,
+            disputer,
+            outcome_id,
+            bond,
+            timestamp: self.env().get_block_time(),
+        });
+        let excess = attached_value - bond;
+        if !excess.is_zero() {
+            self.env().transfer_tokens(&disputer, &u256_to_u512(excess));
+        }
+    }
+
+    /// Finalise an undisputed proposal once its dispute window has closed,
+    /// returning the proposer's bond. Callable by anyone.
+    #[odra(non_reentrant)]
+    pub fn finalize_proposal(&mut self) {
+        if !matches!(self.status.get_or_default(), MarketStatus::Proposed) {
+            self.env().revert(Error::NoProposal);
+        }
+        if !self.finalize_if_undisputed() {
+            self.env().revert(Error::DisputeWindowOpen);
+        }
+    }
+
+    /// Settle a disputed proposal with the winning outcome.
+    ///
+    /// The proposer is right if `winning_outcome_id` is the proposed outcome,
+    /// the disputer otherwise; the side that is right gets both bonds back.
+    /// Can only be called by the designated resolver or admin; by the admin
+    /// alone if the market has a resolver committee.
+    #[odra(non_reentrant)]
+    pub fn arbitrate_dispute(&mut self, winning_outcome_id: u64, proof: String) {
+        let caller = self.env().caller();
+        self.require_resolver();
+        if !matches!(self.status.get_or_default(), MarketStatus::Disputed) {
+            self.env().revert(Error::NotDisputed);
+        }
+
+        let proposal = self.pending_proposal();
+        self.settle(winning_outcome_id, caller, proof);
+        self.settle_bonds(proposal, Some(winning_outcome_id));
+    }
+
+    /// Vote for the winning outcome as a member of the resolver committee.
+    ///
+    /// Each member votes once per committee; conflicting votes are recorded
+    /// as cast. The market resolves as soon as the threshold of members
+    /// agree on an outcome, overriding a proposed outcome that is not final
+    /// yet. If votes deadlock, the admin can rotate the committee, which
+    /// starts the vote over.
+    #[odra(non_reentrant)]
+    pub fn vote_outcome(&mut self, outcome_id: u64) {
+        self.require_not_scalar();
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        if outcome_id >= metadata.outcome_count {
+            self.env().revert(Error::InvalidOutcome);
+        }
+
+        let (proposal, agreed) = self.cast_vote(outcome_id);
+        if agreed {
+            self.settle(outcome_id, self.env().caller(), String::from("Committee vote"));
+            self.settle_bonds(proposal, Some(outcome_id));
+        }
+    }
+
+    /// Vote to resolve the market as Invalid as a member of the resolver
+    /// committee.
+    ///
+    /// Counts as a vote for `INVALID_VOTE`, under the same rules as
+    /// `vote_outcome`; the market resolves as Invalid once the threshold of
+    /// members agree.
+    #[odra(non_reentrant)]
+    pub fn vote_invalid(&mut self) {
+        let (proposal, agreed) = self.cast_vote(INVALID_VOTE);
+        if agreed {
+            self.settle_invalid(self.env().caller(), String::from("Committee vote"));
+            self.settle_bonds(proposal, None);
+        }
+    }
+
+    /// Claim winnings from a resolved market.
+    ///
+    /// Winners receive their proportional share of the settlement pool, as
+    /// does every holder of a market resolved Invalid.
+    /// The last claim sweeps any rounding dust to the fee recipient. A
+    /// proposal whose dispute window has closed is finalised first.
+    #[odra(non_reentrant)]
+    pub fn claim_winnings(&mut self) {
+        self.finalize_if_undisputed();
+        let status = self.status.get_or_default();
+        if !matches!(status, MarketStatus::Resolved) {
+            self.env().revert(Error::MarketNotResolved);
+        }
+
+        let caller = self.env().caller();
+        let (payout, dust) = self.claim_settlement(caller, Error::NoWinningPosition);
+
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        self.env().emit_event(WinningsClaimed {
+            user: caller,
+            market_id: metadata.market_id,
+            payout,
+            timestamp: self.env().get_block_time(),
+        });
+
+        // Pay the winner from the vault
+        self.withdraw_from_vault(metadata.market_id, caller, payout);
+        self.sweep_to_fee_recipient(metadata.market_id, dust);
+    }
+
+    /// Claim refund from a cancelled market.
+    ///
+    /// In a market resolved Invalid, pays the caller's share of the
+    /// settlement pool, as `claim_winnings` does.
+    #[odra(non_reentrant)]
+    pub fn claim_refund(&mut self) {
+        let caller = self.env().caller();
+        let status = self.status.get_or_default();
+        if matches!(status, MarketStatus::Resolved) && self.resolution.settlement().invalid {
+            let (amount, dust) = self.claim_settlement(caller, Error::NoPositionToRefund);
+            let market_id = self.metadata.get().unwrap_or_revert(&self.env()).market_id;
+            self.env().emit_event(RefundClaimed {
+                user: caller,
+                market_id,
+                amount,
+                timestamp: self.env().get_block_time(),
+            });
+            self.withdraw_from_vault(market_id, caller, amount);
+            self.sweep_to_fee_recipient(market_id, dust);
+            return;
+        }
+        if !matches!(status, MarketStatus::Cancelled) {
+            self.env().revert(Error::MarketNotCancelled);
+        }
+
+        // Check not already claimed
+        if self.user_claimed.get(&caller).unwrap_or(false) {
+            self.env().revert(Error::AlreadyClaimed);
+        }
+
+        // Calculate total cost basis across all outcomes
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        let mut total_refund = U256::zero();
+
+        for outcome_id in 0..metadata.outcome_count {
+            let position = self.positions.get(caller, outcome_id);
+            total_refund = total_refund.saturating_add(position.total_cost);
+        }
+
+        if total_refund.is_zero() {
+            self.env().revert(Error::NoPositionToRefund);
+        }
+
+        // Mark as claimed
+        self.user_claimed.set(&caller, true);
+        self.burn_claimed_shares(caller);
+
+        self.env().emit_event(RefundClaimed {
+            user: caller,
+            market_id: metadata.market_id,
+            amount: total_refund,
+            timestamp: self.env().get_block_time(),
+        });
+
+        // Pay the refund from the vault
+        self.withdraw_from_vault(metadata.market_id, caller, total_refund);
+    }
+
+    /// Withdraw the creator's seed liquidity when no trader can receive it.
+    ///
+    /// Available once the market is cancelled, or resolved with funds left
+    /// for the creator: the seed when nobody holds the winning outcome, what
+    /// an LMSR market maker keeps after covering the winners, or the winning
+    /// shares left in a complete-set pool.
+    /// Can only be called by the market creator.
+    ///
+    /// The seed of a complete-set AMM market is the creator's LP stake and is
+    /// withdrawn with `remove_liquidity` instead.
+    #[odra(non_reentrant)]
+    pub fn withdraw_seed_liquidity(&mut self) {
+        let caller = self.env().caller();
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        if caller != metadata.creator {
+            self.env().revert(Error::NotCreator);
+        }
+
+        let mut seed = self.seed_liquidity.get_or_default();
+        let pricing = self.config.get().unwrap_or_revert(&self.env()).pricing;
+        let amount = match self.status.get_or_default() {
+            _ if matches!(pricing, PricingMode::CompleteSetAmm) => U256::zero(),
+            MarketStatus::Cancelled => seed.amount,
+            MarketStatus::Resolved => self.resolution.settlement().creator_return,
+            _ => self.env().revert(Error::MarketNotResolved),
+        };
+        if amount.is_zero() {
+            self.env().revert(Error::NothingToClaim);
+        }
+        if seed.withdrawn {
+            self.env().revert(Error::AlreadyClaimed);
+        }
+        seed.withdrawn = true;
+        self.seed_liquidity.set(seed);
+
+        self.env().emit_event(CreatorLiquidityWithdrawn {
+            creator: caller,
+            market_id: metadata.market_id,
+            amount,
+            timestamp: self.env().get_block_time(),
+        });
+
+        self.withdraw_from_vault(metadata.market_id, caller, amount);
+    }
+
+    // =========================================================================
+    // Admin Functions
+    // =========================================================================
+
+    /// Close the market for trading.
+    pub fn close_market(&mut self) {
+        self.require_admin();
+        
+        let status = self.status.get_or_default();
+        if !matches!(status, MarketStatus::Active) {
+            self.env().revert(Error::MarketNotActive);
+        }
+
+        self.status.set(MarketStatus::Closed);
+        
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        self.env().emit_event(MarketClosed {
+            market_id: metadata.market_id,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Cancel the market and enable refunds.
+    pub fn cancel_market(&mut self, reason: String) {
+        self.require_admin();
+        self.cancel(reason);
+    }
+
+    /// Update the resolver address.
+    pub fn update_resolver(&mut self, new_resolver: Address) {
+        self.require_admin();
+        
+        let mut config = self.config.get().unwrap_or_revert(&self.env());
+        let previous_resolver = config.resolver;
+        config.resolver = new_resolver;
+        self.config.set(config);
+        
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        self.env().emit_event(ResolverUpdated {
+            market_id: metadata.market_id,
+            previous_resolver,
+            new_resolver,
+        });
+    }
+
+    /// Replace the resolver committee, or remove it with `None`.
+    ///
+    /// Votes cast by the previous committee are discarded. Can only be called
+    /// by admin.
+    pub fn set_resolver_committee(&mut self, committee: Option<ResolverCommittee>) {
+        self.require_admin();
+        if committee.as_ref().is_some_and(|committee| !committee.is_valid()) {
+            self.env().revert(Error::InvalidCommittee);
+        }
+
+        let mut config = self.config.get().unwrap_or_revert(&self.env());
+        let (members, threshold) = committee
+            .as_ref()
+            .map(|committee| (committee.members.clone(), committee.threshold))
+            .unwrap_or_default();
+        config.committee = committee;
+        self.config.set(config);
+        self.resolution.reset_votes();
+
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        self.env().emit_event(ResolverCommitteeUpdated {
+            market_id: metadata.market_id,
+            members,
+            threshold,
+        });
+    }
+
+    // =========================================================================
+    // View Functions
+    // =========================================================================
+
+    /// Get the current price for an outcome.
+    ///
+    /// In an LMSR market this is the outcome's probability times `LMSR_SHARE_VALUE`,
+    /// and in a complete-set AMM market the price of one CSPR's worth of shares.
+    pub fn get_current_price(&self, outcome_id: u64) -> U256 {
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        if outcome_id >= metadata.outcome_count {
+            self.env().revert(Error::InvalidOutcome);
+        }
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        match config.pricing {
+            PricingMode::BondingCurve => {
+                let current_supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
+                config.bonding_params.price_at_supply(current_supply)
+            }
+            PricingMode::Lmsr => Lmsr { liquidity: config.lmsr_liquidity }
+                .price(&self.outcome_supplies(), outcome_id as usize),
+            PricingMode::CompleteSetAmm => self.pool().price(outcome_id as usize),
+        }
+    }
+
+    /// Get the time-weighted average price of an outcome over at least the
+    /// last `window` milliseconds.
+    ///
+    /// The average starts at the latest checkpoint no later than `window`
+    /// ago. A checkpoint is taken in every `PRICE_CHECKPOINT_PERIOD` that
+    /// sees a trade and kept for `PRICE_CHECKPOINTS` periods, so the window
+    /// can reach back about a day. For other windows, checkpoint
+    /// `get_cumulative_price` instead.
+    pub fn get_twap(&self, outcome_id: u64, window: u64) -> U256 {
+        let end = self.cumulative_price(outcome_id);
+        let start = end
+            .timestamp
+            .checked_sub(window)
+            .filter(|_| window > 0)
+            .unwrap_or_revert_with(&self.env(), Error::InvalidTwapWindow);
+        let latest = start / PRICE_CHECKPOINT_PERIOD;
+        let oldest = (end.period() + 1).saturating_sub(PRICE_CHECKPOINTS);
+        (oldest..=latest)
+            .rev()
+            .filter_map(|period| self.prices.checkpoint(outcome_id, period))
+            .find(|checkpoint| checkpoint.timestamp <= start)
+            .and_then(|checkpoint| end.average_since(&checkpoint))
+            .unwrap_or_revert_with(&self.env(), Error::InvalidTwapWindow)
+    }
+
+    /// Get the cumulative price of an outcome: the sum of its price times
+    /// elapsed milliseconds since the market was created.
+    ///
+    /// A trade's price only counts from the block after it. Two readings
+    /// taken at different times give the average price between them, over
+    /// any window.
+    pub fn get_cumulative_price(&self, outcome_id: u64) -> U256 {
+        self.cumulative_price(outcome_id).cumulative_price
+    }
+
+    /// Calculate the cost to buy a specific number of shares, including the buy fee.
+    pub fn calculate_buy_cost(&self, outcome_id: u64, shares: U256) -> U256 {
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        let cost = self.cost_to_buy(outcome_id, shares);
+        cost.saturating_add(config.fees.buy_fee(cost))
+    }
+
+    /// Calculate the revenue from selling a specific number of shares, net of the sell fee.
+    pub fn calculate_sell_revenue(&self, outcome_id: u64, shares: U256) -> U256 {
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        let gross = self.revenue_from_sell(outcome_id, shares);
+        gross.saturating_sub(config.fees.sell_fee(gross))
+    }
+
+    /// Get complete market information.
+    pub fn get_market_info(&self) -> MarketInfo {
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        
+        MarketInfo {
+            market_id: metadata.market_id,
+            market_type: metadata.market_type,
+            question: metadata.question,
+            creator: metadata.creator,
+            end_time: metadata.end_time,
+            status: self.status.get_or_default(),
+            total_liquidity: self.total_liquidity.get_or_default(),
+            category: metadata.category,
+            resolution_source: metadata.resolution_source,
+        }
+    }
+
+    /// Get a user's position for a specific outcome.
+    pub fn get_user_position(&self, user: Address, outcome_id: u64) -> UserPosition {
+        self.positions.get(user, outcome_id)
+    }
+
+    /// Get total shares for an outcome.
+    pub fn get_outcome_shares(&self, outcome_id: u64) -> U256 {
+        self.outcome_shares.get(&outcome_id).unwrap_or_default()
+    }
+
+    /// Get total liquidity for an outcome.
+    pub fn get_outcome_liquidity(&self, outcome_id: u64) -> U256 {
+        self.outcome_liquidity.get(&outcome_id).unwrap_or_default()
+    }
+
+    /// Get the number of outcomes.
+    pub fn get_outcome_count(&self) -> u64 {
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        metadata.outcome_count
+    }
+
+    /// Get outcome name by ID.
+    pub fn get_outcome_name(&self, outcome_id: u64) -> Option<String> {
+        let metadata = self.metadata.get()?;
+        if (outcome_id as usize) < metadata.outcome_names.len() {
+            Some(metadata.outcome_names.get(outcome_id as usize).unwrap_or(&String::default()).clone())
+        } else {
+            None
+        }
+    }
+
+    /// Get the sub-questions of a compound market.
+    pub fn get_compound_legs(&self) -> Vec<CompoundLeg> {
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        metadata.legs
+    }
+
+    /// Get the outcome ID of the combination hitting the given leg results.
+    pub fn get_compound_outcome_id(&self, leg_results: Vec<u64>) -> Option<u64> {
+        let metadata = self.metadata.get()?;
+        compound_outcome_id(&metadata.legs, &leg_results)
+    }
+
+    /// Get the implied probability of every outcome in basis points.
+    ///
+    /// The probabilities sum to exactly 10_000 (see `implied_probabilities_bps`).
+    /// In a bonding curve market an outcome's probability is its marginal
+    /// price over the sum of all marginal prices: the outcome traders have
+    /// bought up the most is the likeliest. In an LMSR or complete-set AMM
+    /// market the prices already are probabilities.
+    pub fn get_implied_probabilities(&self) -> Vec<u64> {
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        match config.pricing {
+            PricingMode::BondingCurve => {
+                let prices: Vec<U256> = self
+                    .outcome_supplies()
+                    .into_iter()
+                    .map(|supply| config.bonding_params.price_at_supply(supply))
+                    .collect();
+                implied_probabilities_bps(&prices)
+            }
+            PricingMode::Lmsr => Lmsr { liquidity: config.lmsr_liquidity }
+                .probabilities_bps(&self.outcome_supplies()),
+            PricingMode::CompleteSetAmm => self.pool().probabilities_bps(),
+        }
+    }
+
+    /// Get implied odds for all outcomes (as percentage * 100).
+    /// Returns a vector of (outcome_id, odds_percentage).
+    ///
+    /// Pairs `get_implied_probabilities` with outcome IDs.
+    pub fn get_outcome_odds(&self) -> Vec<(u64, u64)> {
+        (0u64..).zip(self.get_implied_probabilities()).collect()
+    }
+
+    /// Get a limit order.
+    pub fn get_order(&self, order_id: u64) -> Option<LimitOrder> {
+        self.orders.get(order_id)
+    }
+
+    /// Get the shares of an outcome `spender` may transfer for `owner`.
+    pub fn get_share_allowance(&self, owner: Address, spender: Address, outcome_id: u64) -> U256 {
+        self.positions.allowance(owner, spender, outcome_id)
+    }
+
+    /// Get the CEP-18 token contract registered for an outcome, if any.
+    pub fn get_outcome_token(&self, outcome_id: u64) -> Option<Address> {
+        self.positions.token(outcome_id)
+    }
+
+    /// Get the number of decimals of a share: complete-set AMM shares are
+    /// worth a mote each, other markets trade whole shares.
+    pub fn get_share_decimals(&self) -> u8 {
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        match config.pricing {
+            PricingMode::CompleteSetAmm => 9,
+            PricingMode::BondingCurve | PricingMode::Lmsr => 0,
+        }
+    }
+
+    /// Get the number of limit orders ever placed; order IDs run from zero.
+    pub fn get_order_count(&self) -> u64 {
+        self.orders.count()
+    }
+
+    /// Get the complete-set AMM pool and its LP share accounting.
+    pub fn get_liquidity_pool(&self) -> LiquidityPool {
+        self.liquidity.pool()
+    }
+
+    /// Get a liquidity provider's stake.
+    pub fn get_liquidity_position(&self, provider: Address) -> LiquidityPosition {
+        self.liquidity.position(provider)
+    }
+
+    /// Get the LP fees a liquidity provider can claim.
+    pub fn get_pending_lp_fees(&self, provider: Address) -> U256 {
+        self.liquidity.pool().pending_fees(&self.liquidity.position(provider))
+    }
+
+    /// Get the creator's seed liquidity.
+    pub fn get_seed_liquidity(&self) -> SeedLiquidity {
+        self.seed_liquidity.get_or_default()
+    }
+
+    /// Get the winning outcome (only valid after resolution, and not for a
+    /// market resolved Invalid or a scalar market).
+    pub fn get_winning_outcome(&self) -> Option<u64> {
+        self.get_settlement()
+            .filter(|settlement| settlement.payout_weights.is_empty())
+            .map(|settlement| settlement.winning_outcome)
+    }
+
+    /// Get the outcome proposed under optimistic resolution, if any.
+    pub fn get_proposal(&self) -> Option<ResolutionProposal> {
+        self.resolution.proposal()
+    }
+
+    /// Get a committee member's vote for the current committee, if any:
+    /// an outcome, or `INVALID_VOTE`.
+    pub fn get_committee_vote(&self, member: Address) -> Option<u64> {
+        self.resolution.vote(member)
+    }
+
+    /// Get the settlement snapshot (only valid after resolution).
+    pub fn get_settlement(&self) -> Option<Settlement> {
+        let status = self.status.get_or_default();
+        if matches!(status, MarketStatus::Resolved) {
+            Some(self.resolution.settlement())
+        } else {
+            None
+        }
+    }
+
+    /// Check if the market is past its end time.
+    pub fn is_ended(&self) -> bool {
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        self.env().get_block_time() >= metadata.end_time
+    }
+
+    /// Get the market status.
     pub fn get_status(&self) -> MarketStatus {
         self.status.get_or_default()
     }
 
-    /// Get metadata
-    pub fn get_metadata(&self) -> MarketMetadata {
-        self.metadata.get().unwrap_or_revert(&self.env())
+    /// Get metadata
+    pub fn get_metadata(&self) -> MarketMetadata {
+        self.metadata.get().unwrap_or_revert(&self.env())
+    }
+    
+    /// Get config
+    pub fn get_config(&self) -> MarketConfig {
+        self.config.get().unwrap_or_revert(&self.env())
+    }
+
+    // =========================================================================
+    // Internal Helper Functions
+    // =========================================================================
+
+    /// Ensure market is in Active status.
+    fn require_active(&self) {
+        let status = self.status.get_or_default();
+        if !matches!(status, MarketStatus::Active) {
+            self.env().revert(Error::MarketNotActive);
+        }
+    }
+
+    /// Ensure market hasn't ended.
+    fn require_not_ended(&self) {
+        if self.is_ended() {
+            self.env().revert(Error::MarketAlreadyEnded);
+        }
+    }
+
+    /// Ensure market is closed or has ended.
+    fn require_closed_or_ended(&self) {
+        let status = self.status.get_or_default();
+        let is_closed = matches!(status, MarketStatus::Closed);
+        let is_ended = self.is_ended();
+        
+        if !is_closed && !is_ended {
+            self.env().revert(Error::MarketNotEnded);
+        }
+    }
+
+    /// Ensure the market trades against a complete-set AMM pool.
+    fn require_complete_set_market(&self) {
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        if !matches!(config.pricing, PricingMode::CompleteSetAmm) {
+            self.env().revert(Error::NotCompleteSetMarket);
+        }
+    }
+
+    /// Freeze the payout terms for `winning_outcome_id` and mark the market
+    /// resolved by `resolver`.
+    fn settle(&mut self, winning_outcome_id: u64, resolver: Address, proof: String) {
+        self.require_not_scalar();
+
+        // Validate outcome
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        if winning_outcome_id >= metadata.outcome_count {
+            self.env().revert(Error::InvalidOutcome);
+        }
+
+        let settlement = self.freeze_settlement(Settlement {
+            winning_outcome: winning_outcome_id,
+            ..Default::default()
+        });
+        self.env().emit_event(MarketResolved {
+            market_id: metadata.market_id,
+            winning_outcome: winning_outcome_id,
+            resolver,
+            timestamp: self.env().get_block_time(),
+            proof,
+        });
+
+        // Book the settlement fee, and sweep the whole pool if nobody holds the winner
+        self.collect_fees(metadata.market_id, settlement.fee_amount);
+        self.sweep_to_fee_recipient(metadata.market_id, settlement.dust);
+    }
+
+    /// Freeze payout terms that pay every outcome alike and mark the market
+    /// resolved Invalid by `resolver`.
+    fn settle_invalid(&mut self, resolver: Address, proof: String) {
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        let payout_weights = vec![1; metadata.outcome_count as usize];
+        let settlement = self.freeze_settlement(Settlement {
+            invalid: true,
+            payout_weights,
+            ..Default::default()
+        });
+        self.env().emit_event(MarketResolvedInvalid {
+            market_id: metadata.market_id,
+            resolver,
+            timestamp: self.env().get_block_time(),
+            proof,
+        });
+
+        self.collect_fees(metadata.market_id, settlement.fee_amount);
+        self.sweep_to_fee_recipient(metadata.market_id, settlement.dust);
+    }
+
+    /// Freeze the payout terms against the current pool and mark the market
+    /// resolved. `terms` gives the winning outcome, or the payout weights
+    /// if several outcomes pay, and how the market was resolved.
+    fn freeze_settlement(&mut self, terms: Settlement) -> Settlement {
+        let config = self.config.get().unwrap_or_revert(&self.env());
+
+        // Check not already resolved or cancelled
+        match self.status.get_or_default() {
+            MarketStatus::Resolved => self.env().revert(Error::MarketAlreadyResolved),
+            MarketStatus::Cancelled => self.env().revert(Error::MarketCancelled),
+            _ => {}
+        }
+
+        let winning_outcome_id = terms.winning_outcome;
+        let supplies = self.outcome_supplies();
+        let total_winning_shares = terms.payout_shares(&supplies);
+        let weight_total = terms.payout_weights.iter().sum::<u64>().max(1);
+        let pool = self.total_liquidity.get_or_default();
+        let seed = self.seed_liquidity.get_or_default().amount;
+        let settlement = Settlement {
+            invalid: terms.invalid,
+            scalar_value: terms.scalar_value,
+            payout_weights: terms.payout_weights,
+            ..match config.pricing {
+                PricingMode::BondingCurve => Settlement::new(
+                    winning_outcome_id,
+                    pool,
+                    config.fees.settlement_fee_bps,
+                    seed,
+                    total_winning_shares,
+                ),
+                // Winning shares pay a fixed value; the market maker keeps the rest
+                PricingMode::Lmsr => Settlement::fixed_payout(
+                    winning_outcome_id,
+                    pool.saturating_add(seed),
+                    U256::from(LMSR_SHARE_VALUE),
+                    weight_total,
+                    config.fees.settlement_fee_bps,
+                    total_winning_shares,
+                ),
+                // Every outstanding complete set pays one mote; the creator keeps
+                // the pool's winning shares
+                PricingMode::CompleteSetAmm => Settlement::fixed_payout(
+                    winning_outcome_id,
+                    pool.saturating_add(seed),
+                    U256::one(),
+                    weight_total,
+                    config.fees.settlement_fee_bps,
+                    total_winning_shares,
+                ),
+            }
+        };
+        // A scalar pool is split between LONG and SHORT, each side paid pro rata
+        let settlement = match config.pricing {
+            PricingMode::BondingCurve if terms.scalar_value.is_some() => settlement.split_by_outcome(&supplies),
+            _ => settlement,
+        };
+        if !settlement.shortfall.is_zero() {
+            let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+            self.env().emit_event(SettlementShortfall {
+                market_id: metadata.market_id,
+                shortfall: settlement.shortfall,
+            });
+        }
+        self.resolution.set_settlement(settlement.clone());
+        self.status.set(MarketStatus::Resolved);
+        settlement
+    }
+
+    /// Record the caller's claim on the settlement, returning the payout and
+    /// the dust to sweep if it was the last claim. Reverts with `no_position`
+    /// if the caller holds no paying shares.
+    fn claim_settlement(&mut self, caller: Address, no_position: Error) -> (U256, U256) {
+        if self.user_claimed.get(&caller).unwrap_or(false) {
+            self.env().revert(Error::AlreadyClaimed);
+        }
+
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        let holdings: Vec<U256> = (0..metadata.outcome_count)
+            .map(|outcome_id| self.positions.get(caller, outcome_id).shares)
+            .collect();
+        let mut settlement = self.resolution.settlement();
+        let shares = settlement.payout_shares(&holdings);
+        if shares.is_zero() {
+            self.env().revert(no_position);
+        }
+
+        // The holder gets (shares / total_winning_shares) * distributable_pool,
+        // or their part of each outcome's pool if it is split. A position too
+        // small to earn a mote still counts towards the last claim.
+        let payout = settlement.record_claim(&holdings);
+        let dust = if settlement.is_fully_claimed() { settlement.dust } else { U256::zero() };
+        self.resolution.set_settlement(settlement);
+        self.user_claimed.set(&caller, true);
+        self.burn_claimed_shares(caller);
+        (payout, dust)
+    }
+
+    /// Burn every share `holder` holds once their claim is paid.
+    fn burn_claimed_shares(&mut self, holder: Address) {
+        let outcome_count = self.metadata.get().unwrap_or_revert(&self.env()).outcome_count;
+        for outcome_id in 0..outcome_count {
+            let position = self.positions.get(holder, outcome_id);
+            if position.shares.is_zero() {
+                continue;
+            }
+            let supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
+            self.outcome_shares.set(&outcome_id, supply.saturating_sub(position.shares));
+            self.positions.set(holder, outcome_id, UserPosition { claimed: true, ..UserPosition::new(outcome_id) });
+            self.notify_token(outcome_id, Some(holder), None, position.shares);
+        }
+    }
+
+    /// Report shares of an outcome issued (`from` is `None`), burned (`to`
+    /// is `None`) or moved by the market to the outcome's token, if any, so
+    /// its CEP-18 events track every balance.
+    fn notify_token(&self, outcome_id: u64, from: Option<Address>, to: Option<Address>, amount: U256) {
+        let Some(token) = self.positions.token(outcome_id) else {
+            return;
+        };
+        if amount.is_zero() {
+            return;
+        }
+        let mut token = OutcomeTokenContractRef::new(self.env(), token);
+        match (from, to) {
+            (None, Some(recipient)) => token.record_mint(recipient, amount),
+            (Some(owner), None) => token.record_burn(owner, amount),
+            (Some(sender), Some(recipient)) => token.record_transfer(sender, recipient, amount),
+            (None, None) => {}
+        }
+    }
+
+    /// Resolve the market with the proposed outcome if it went undisputed
+    /// through its dispute window, returning the proposer's bond. Returns
+    /// whether the proposal was finalised.
+    fn finalize_if_undisputed(&mut self) -> bool {
+        if !matches!(self.status.get_or_default(), MarketStatus::Proposed) {
+            return false;
+        }
+        let proposal = self.resolution.proposal().unwrap_or_revert(&self.env());
+        if self.env().get_block_time() <= proposal.dispute_deadline {
+            return false;
+        }
+
+        self.settle(proposal.outcome_id, proposal.proposer, String::from("Undisputed proposal"));
+        self.env().transfer_tokens(&proposal.proposer, &u256_to_u512(proposal.bond));
+        true
+    }
+
+    /// Record the caller's committee vote for `choice`, returning the pending
+    /// proposal a resolution would override and whether the threshold of
+    /// members now agree on `choice`.
+    fn cast_vote(&mut self, choice: u64) -> (Option<ResolutionProposal>, bool) {
+        self.require_closed_or_ended();
+        match self.status.get_or_default() {
+            MarketStatus::Resolved => self.env().revert(Error::MarketAlreadyResolved),
+            MarketStatus::Cancelled => self.env().revert(Error::MarketCancelled),
+            _ => {}
+        }
+        let proposal = self.pending_proposal();
+
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        let committee = config.committee.unwrap_or_revert_with(&self.env(), Error::NoResolverCommittee);
+        let member = self.env().caller();
+        if !committee.is_member(&member) {
+            self.env().revert(Error::NotCommitteeMember);
+        }
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        if self.resolution.vote(member).is_some() {
+            self.env().revert(Error::AlreadyVoted);
+        }
+
+        self.resolution.set_vote(member, choice);
+        let votes = committee
+            .members
+            .iter()
+            .filter(|other| self.resolution.vote(**other) == Some(choice))
+            .count() as u64;
+
+        self.env().emit_event(ResolutionVoted {
+            market_id: metadata.market_id,
+            member,
+            outcome_id: choice,
+            votes,
+            threshold: committee.threshold,
+            timestamp: self.env().get_block_time(),
+        });
+
+        (proposal, votes >= committee.threshold)
+    }
+
+    /// Ensure the caller may resolve directly: the admin, or the designated
+    /// resolver unless the market has a resolver committee.
+    fn require_resolver(&self) {
+        let caller = self.env().caller();
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        let is_resolver = caller == config.resolver && config.committee.is_none();
+        if !is_resolver && caller != config.admin {
+            self.env().revert(Error::NotResolver);
+        }
+    }
+
+    /// Ensure the market resolves to an outcome rather than a scalar value.
+    fn require_not_scalar(&self) {
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        if matches!(metadata.market_type, MarketType::Scalar) {
+            self.env().revert(Error::ScalarValueRequired);
+        }
+    }
+
+    /// The proposed outcome awaiting finality, if any, which resolving the
+    /// market directly overrides. Reverts if the proposal went undisputed
+    /// through its dispute window, as it is then final.
+    fn pending_proposal(&self) -> Option<ResolutionProposal> {
+        let status = self.status.get_or_default();
+        if !matches!(status, MarketStatus::Proposed | MarketStatus::Disputed) {
+            return None;
+        }
+        let proposal = self.resolution.proposal().unwrap_or_revert(&self.env());
+        if proposal.disputer.is_none() && self.env().get_block_time() > proposal.dispute_deadline {
+            self.env().revert(Error::DisputeWindowClosed);
+        }
+        Some(proposal)
+    }
+
+    /// Pay out the bonds of a proposal overridden by resolving the market to
+    /// `winning_outcome`, or Invalid if `None`.
+    ///
+    /// A proposer who was right gets back their bond and the disputer's. One
+    /// who was wrong forfeits theirs to the disputer, or to the fee recipient
+    /// if nobody disputed.
+    fn settle_bonds(&mut self, proposal: Option<ResolutionProposal>, winning_outcome: Option<u64>) {
+        let Some(proposal) = proposal else {
+            return;
+        };
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        let proposer_right = winning_outcome == Some(proposal.outcome_id);
+        let Some(disputer) = proposal.disputer else {
+            if proposer_right {
+                self.env().transfer_tokens(&proposal.proposer, &u256_to_u512(proposal.bond));
+            } else {
+                self.deposit_to_vault(metadata.market_id, proposal.bond);
+                self.sweep_to_fee_recipient(metadata.market_id, proposal.bond);
+            }
+            return;
+        };
+
+        let winner = if proposer_right { proposal.proposer } else { disputer };
+        self.env().emit_event(DisputeResolved {
+            market_id: metadata.market_id,
+            outcome_id: winning_outcome,
+            winner,
+            slashed_bond: proposal.bond,
+            timestamp: self.env().get_block_time(),
+        });
+        self.env().transfer_tokens(&winner, &u256_to_u512(proposal.bond.saturating_mul(U256::from(2u64))));
+    }
+
+    /// Cancel the market, enabling refunds. Bonds of a pending proposal and
+    /// its dispute are returned.
+    fn cancel(&mut self, reason: String) {
+        let status = self.status.get_or_default();
+        if matches!(status, MarketStatus::Resolved) {
+            self.env().revert(Error::MarketAlreadyResolved);
+        }
+        if matches!(status, MarketStatus::Proposed | MarketStatus::Disputed) {
+            let proposal = self.resolution.proposal().unwrap_or_revert(&self.env());
+            let bond = u256_to_u512(proposal.bond);
+            self.env().transfer_tokens(&proposal.proposer, &bond);
+            if let Some(disputer) = proposal.disputer {
+                self.env().transfer_tokens(&disputer, &bond);
+            }
+        }
+
+        self.status.set(MarketStatus::Cancelled);
+        
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        self.env().emit_event(MarketCancelled {
+            market_id: metadata.market_id,
+            reason,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Ensure caller is the token contract registered for an outcome.
+    fn require_outcome_token(&self, outcome_id: u64) {
+        if self.positions.token(outcome_id) != Some(self.env().caller()) {
+            self.env().revert(Error::NotOutcomeToken);
+        }
+    }
+
+    /// Ensure caller is admin.
+    fn require_admin(&self) {
+        let caller = self.env().caller();
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        if caller != config.admin {
+            self.env().revert(Error::NotAdmin);
+        }
+    }
+
+    /// Fold the price every outcome has stood at since the last update into
+    /// its cumulative price.
+    ///
+    /// Runs before a trade moves prices, at most once per block, so a price
+    /// set within a block only starts counting once the block is over.
+    fn accrue_prices(&mut self) {
+        let now = self.env().get_block_time();
+        let outcome_count = self.metadata.get().unwrap_or_revert(&self.env()).outcome_count;
+        for outcome_id in 0..outcome_count {
+            let accumulator = self.prices.accumulator(outcome_id).unwrap_or_default();
+            if accumulator.timestamp >= now {
+                return;
+            }
+            let price = self.get_current_price(outcome_id);
+            self.prices.set_accumulator(outcome_id, accumulator.advanced(now, price));
+        }
+    }
+
+    /// An outcome's cumulative price as of the current block.
+    fn cumulative_price(&self, outcome_id: u64) -> PriceAccumulator {
+        let accumulator = self.prices
+            .accumulator(outcome_id)
+            .unwrap_or_revert_with(&self.env(), Error::InvalidOutcome);
+        accumulator.advanced(self.env().get_block_time(), self.get_current_price(outcome_id))
+    }
+
+    /// Outstanding shares of every outcome, indexed by outcome ID.
+    fn outcome_supplies(&self) -> Vec<U256> {
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        (0..metadata.outcome_count)
+            .map(|outcome_id| self.outcome_shares.get(&outcome_id).unwrap_or_default())
+            .collect()
+    }
+
+    /// Cost of `shares` of an outcome before fees, on the market's bonding
+    /// curve or LMSR market maker.
+    fn cost_to_buy(&self, outcome_id: u64, shares: U256) -> U256 {
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        match config.pricing {
+            PricingMode::BondingCurve => {
+                let current_supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
+                config.bonding_params.cost_to_buy(current_supply, shares)
+            }
+            PricingMode::Lmsr => Lmsr { liquidity: config.lmsr_liquidity }
+                .cost_to_buy(&self.outcome_supplies(), outcome_id as usize, shares),
+            PricingMode::CompleteSetAmm => self.pool().cost_to_buy(outcome_id as usize, shares),
+        }
+    }
+
+    /// Revenue from selling `shares` of an outcome before fees.
+    fn revenue_from_sell(&self, outcome_id: u64, shares: U256) -> U256 {
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        match config.pricing {
+            PricingMode::BondingCurve => {
+                let current_supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
+                config.bonding_params.revenue_from_sell(current_supply, shares)
+            }
+            PricingMode::Lmsr => Lmsr { liquidity: config.lmsr_liquidity }
+                .revenue_from_sell(&self.outcome_supplies(), outcome_id as usize, shares),
+            PricingMode::CompleteSetAmm => {
+                self.pool().revenue_from_sell(outcome_id as usize, shares)
+            }
+        }
+    }
+
+    /// Largest number of shares of an outcome costing at most `max_cost` before fees.
+    fn shares_for_cost(&self, outcome_id: u64, max_cost: U256) -> U256 {
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        match config.pricing {
+            PricingMode::BondingCurve => {
+                let current_supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
+                config.bonding_params.shares_for_cost(current_supply, max_cost)
+            }
+            PricingMode::Lmsr => Lmsr { liquidity: config.lmsr_liquidity }
+                .shares_for_cost(&self.outcome_supplies(), outcome_id as usize, max_cost),
+            PricingMode::CompleteSetAmm => {
+                self.pool().shares_for_cost(outcome_id as usize, max_cost)
+            }
+        }
+    }
+
+    /// Spot price of an outcome once `shares` of it are traded on `side`.
+    fn price_after_trade(&self, outcome_id: u64, side: OrderSide, shares: U256) -> U256 {
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        let supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
+        let supply = match side {
+            OrderSide::Buy => supply.saturating_add(shares),
+            OrderSide::Sell => supply.saturating_sub(shares),
+        };
+        match config.pricing {
+            PricingMode::BondingCurve => config.bonding_params.price_at_supply(supply),
+            PricingMode::Lmsr => {
+                let mut supplies = self.outcome_supplies();
+                supplies[outcome_id as usize] = supply;
+                Lmsr { liquidity: config.lmsr_liquidity }.price(&supplies, outcome_id as usize)
+            }
+            PricingMode::CompleteSetAmm => {
+                let mut pool = self.pool();
+                let outcome = outcome_id as usize;
+                match side {
+                    OrderSide::Buy => pool.buy(outcome, pool.cost_to_buy(outcome, shares), shares),
+                    OrderSide::Sell => pool.sell(outcome, shares, pool.revenue_from_sell(outcome, shares)),
+                }
+                pool.price(outcome)
+            }
+        }
+    }
+
+    /// Largest number of shares, up to `max_shares`, an order can trade
+    /// without the spot price crossing its limit.
+    fn shares_within_limit(&self, order: &LimitOrder, max_shares: U256) -> U256 {
+        let within_limit =
+            |shares| order.is_triggered(self.price_after_trade(order.outcome_id, order.side, shares));
+        if within_limit(max_shares) {
+            return max_shares;
+        }
+        // Prices move monotonically with the shares traded
+        let (mut low, mut high) = (U256::zero(), max_shares);
+        while high - low > U256::one() {
+            let middle = low + (high - low) / 2;
+            if within_limit(middle) {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        low
+    }
+
+    /// The complete-set AMM pool of this market.
+    fn pool(&self) -> CompleteSetPool {
+        CompleteSetPool { balances: self.liquidity.pool().balances }
+    }
+
+    /// Split `amount` evenly across the outcomes, the remainder going to the first.
+    fn split_across_outcomes(&self, amount: U256) -> Vec<(u64, U256)> {
+        let outcome_count = self.metadata.get().unwrap_or_revert(&self.env()).outcome_count;
+        let part = amount / U256::from(outcome_count);
+        let remainder = amount - part * U256::from(outcome_count);
+        (0..outcome_count)
+            .map(|outcome_id| (outcome_id, if outcome_id == 0 { part + remainder } else { part }))
+            .collect()
+    }
+
+    /// Store the balances of the complete-set AMM pool.
+    fn set_pool(&mut self, pool: CompleteSetPool) {
+        let mut liquidity_pool = self.liquidity.pool();
+        liquidity_pool.balances = pool.balances;
+        self.liquidity.set_pool(liquidity_pool);
+    }
+
+    /// Deposit CSPR held by this call into the vault under the market's balance.
+    fn deposit_to_vault(&self, market_id: u64, amount: U256) {
+        if amount.is_zero() {
+            return;
+        }
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        VaultContractRef::new(self.env(), config.vault_contract)
+            .with_tokens(u256_to_u512(amount))
+            .deposit(market_id);
+    }
+
+    /// Pay CSPR out of the market's vault balance to a recipient.
+    fn withdraw_from_vault(&self, market_id: u64, recipient: Address, amount: U256) {
+        if amount.is_zero() {
+            return;
+        }
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        VaultContractRef::new(self.env(), config.vault_contract)
+            .withdraw(market_id, recipient, amount);
+    }
+
+    /// Validate and store a new limit order, reserving the shares of a sell.
+    fn place_order(
+        &mut self,
+        outcome_id: u64,
+        side: OrderSide,
+        limit_price: U256,
+        amount: U256,
+        keeper_reward: U256,
+        expires_at: u64,
+    ) -> u64 {
+        self.require_active();
+        self.require_not_ended();
+
+        let caller = self.env().caller();
+        if amount.is_zero() {
+            self.env().revert(Error::ZeroAmount);
+        }
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        if outcome_id >= metadata.outcome_count {
+            self.env().revert(Error::InvalidOutcome);
+        }
+        if expires_at <= self.env().get_block_time() {
+            self.env().revert(Error::InvalidOrderExpiry);
+        }
+
+        if matches!(side, OrderSide::Sell) {
+            let mut position = self.get_user_position(caller, outcome_id);
+            if position.available_shares() < amount {
+                self.env().revert(Error::InsufficientShares);
+            }
+            position.locked_shares = position.locked_shares.saturating_add(amount);
+            self.positions.set(caller, outcome_id, position);
+        }
+
+        let order_id = self.orders.insert(LimitOrder {
+            owner: caller,
+            outcome_id,
+            side,
+            limit_price,
+            amount,
+            keeper_reward,
+            expires_at,
+            status: OrderStatus::Open,
+        });
+
+        self.env().emit_event(OrderPlaced {
+            order_id,
+            owner: caller,
+            market_id: metadata.market_id,
+            outcome_id,
+            side,
+            limit_price,
+            amount,
+        });
+        order_id
+    }
+
+    /// Allow `spender` to transfer up to `amount` of `owner`'s shares of an
+    /// outcome.
+    fn approve(&mut self, owner: Address, spender: Address, outcome_id: u64, amount: U256) {
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        if outcome_id >= metadata.outcome_count {
+            self.env().revert(Error::InvalidOutcome);
+        }
+        self.positions.set_allowance(owner, spender, outcome_id, amount);
+
+        self.env().emit_event(SharesApproved {
+            owner,
+            spender,
+            market_id: metadata.market_id,
+            outcome_id,
+            amount,
+        });
+    }
+
+    /// Deduct `amount` from the allowance `owner` granted `spender`.
+    fn spend_allowance(&mut self, owner: Address, spender: Address, outcome_id: u64, amount: U256) {
+        let allowance = self.positions.allowance(owner, spender, outcome_id);
+        if allowance < amount {
+            self.env().revert(Error::InsufficientAllowance);
+        }
+        self.positions.set_allowance(owner, spender, outcome_id, allowance - amount);
+    }
+
+    /// Move `amount` shares of an outcome and their cost basis from one
+    /// user to another.
+    fn move_shares(&mut self, from: Address, to: Address, outcome_id: u64, amount: U256) {
+        if amount.is_zero() {
+            self.env().revert(Error::ZeroAmount);
+        }
+        let metadata = self.metadata.get().unwrap_or_revert(&self.env());
+        if outcome_id >= metadata.outcome_count {
+            self.env().revert(Error::InvalidOutcome);
+        }
+        // Claims are settled once per user, so claimed positions are frozen
+        if self.user_claimed.get(&from).unwrap_or(false) || self.user_claimed.get(&to).unwrap_or(false) {
+            self.env().revert(Error::AlreadyClaimed);
+        }
+
+        let mut sender = self.positions.get(from, outcome_id);
+        if sender.available_shares() < amount {
+            self.env().revert(Error::InsufficientShares);
+        }
+        let cost_before = sender.total_cost;
+        sender.remove_shares(amount, U256::zero());
+        let cost_basis = cost_before - sender.total_cost;
+        self.positions.set(from, outcome_id, sender);
+
+        let mut recipient = self.positions.get(to, outcome_id);
+        recipient.add_shares(amount, cost_basis);
+        self.positions.set(to, outcome_id, recipient);
+        self.user_participated.set(&to, true);
+
+        self.env().emit_event(SharesTransferred {
+            from,
+            to,
+            market_id: metadata.market_id,
+            outcome_id,
+            shares: amount,
+            cost_basis,
+            timestamp: self.env().get_block_time(),
+        });
+    }
+
+    /// Cancel an open order: release the shares of a sell and refund the
+    /// escrowed CSPR to the owner.
+    fn close_order(&mut self, metadata: &MarketMetadata, order_id: u64, mut order: LimitOrder) {
+        let mut refund = order.keeper_reward;
+        match order.side {
+            OrderSide::Buy => refund = refund.saturating_add(order.amount),
+            OrderSide::Sell => {
+                let mut position = self.get_user_position(order.owner, order.outcome_id);
+                position.locked_shares = position.locked_shares.saturating_sub(order.amount);
+                self.positions.set(order.owner, order.outcome_id, position);
+            }
+        }
+        order.status = OrderStatus::Cancelled;
+        let owner = order.owner;
+        self.orders.set(order_id, order);
+
+        self.env().emit_event(OrderCancelled {
+            order_id,
+            owner,
+            market_id: metadata.market_id,
+            timestamp: self.env().get_block_time(),
+        });
+        if !refund.is_zero() {
+            self.env().transfer_tokens(&owner, &u256_to_u512(refund));
+        }
+    }
+
+    /// Sell `shares` of `caller` back to the market's pricing, pay them the
+    /// net revenue and book the sell fee.
+    fn execute_sell(
+        &mut self,
+        caller: Address,
+        metadata: &MarketMetadata,
+        outcome_id: u64,
+        shares: U256,
+        min_receive: U256,
+    ) {
+        let (net_revenue, fee) = self.apply_sell(caller, metadata, outcome_id, shares, min_receive);
+
+        // Pay the seller from the vault and book the fee
+        self.withdraw_from_vault(metadata.market_id, caller, net_revenue);
+        self.collect_trading_fees(metadata.market_id, fee);
+    }
+
+    /// Take `shares` from `caller` and return them to the market's pricing,
+    /// leaving the gross revenue in the vault. Returns the net revenue owed
+    /// to the seller and the sell fee.
+    fn apply_sell(
+        &mut self,
+        caller: Address,
+        metadata: &MarketMetadata,
+        outcome_id: u64,
+        shares: U256,
+        min_receive: U256,
+    ) -> (U256, U256) {
+        // Check user has enough shares
+        let mut position = self.positions.get(caller, outcome_id);
+        
+        if position.available_shares() < shares {
+            self.env().revert(Error::InsufficientShares);
+        }
+
+        // Calculate revenue from the pricing
+        self.accrue_prices();
+        let current_supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
+        let gross_revenue = self.revenue_from_sell(outcome_id, shares);
+
+        // Deduct sell fee
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        let fee = config.fees.sell_fee(gross_revenue);
+        let net_revenue = gross_revenue.saturating_sub(fee);
+
+        // Check slippage
+        if net_revenue < min_receive {
+            self.env().revert(Error::SlippageExceeded);
+        }
+
+        // Update outcome state
+        let new_supply = current_supply.saturating_sub(shares);
+        self.outcome_shares.set(&outcome_id, new_supply);
+        if matches!(config.pricing, PricingMode::CompleteSetAmm) {
+            let mut pool = self.pool();
+            pool.sell(outcome_id as usize, shares, gross_revenue);
+            self.set_pool(pool);
+        }
+
+        let current_liquidity = self.outcome_liquidity.get(&outcome_id).unwrap_or_default();
+        self.outcome_liquidity.set(&outcome_id, current_liquidity.saturating_sub(gross_revenue));
+
+        // The whole gross revenue leaves the pool: net to the seller, fee to the ledger
+        let total = self.total_liquidity.get_or_default();
+        self.total_liquidity.set(total.saturating_sub(gross_revenue));
+
+        // Update user position
+        position.remove_shares(shares, net_revenue);
+        self.positions.set(caller, outcome_id, position);
+        self.notify_token(outcome_id, Some(caller), None, shares);
+
+        // Calculate new price for event
+        let new_price = self.get_current_price(outcome_id);
+
+        // Emit event
+        self.env().emit_event(SharesSold {
+            user: caller,
+            market_id: metadata.market_id,
+            outcome_id,
+            shares,
+            revenue: net_revenue,
+            new_price,
+            timestamp: self.env().get_block_time(),
+        });
+
+        (net_revenue, fee)
+    }
+
+    /// Issue `shares` to `caller`, escrow their cost, book the buy fee and
+    /// refund whatever part of `paid` is left over.
+    fn execute_buy(
+        &mut self,
+        caller: Address,
+        metadata: &MarketMetadata,
+        outcome_id: u64,
+        shares: U256,
+        paid: U256,
+    ) {
+        let (actual_cost, fee) = self.apply_buy(caller, metadata, outcome_id, shares);
+
+        // Refund excess if any
+        let refund = paid.saturating_sub(actual_cost).saturating_sub(fee);
+
+        // Escrow the cost in the vault, book the fee and refund excess CSPR if any
+        self.deposit_to_vault(metadata.market_id, actual_cost.saturating_add(fee));
+        self.collect_trading_fees(metadata.market_id, fee);
+        if !refund.is_zero() {
+            let refund_u512 = u256_to_u512(refund);
+            self.env().transfer_tokens(&caller, &refund_u512);
+        }
+    }
+
+    /// Issue `shares` to `caller` from the market's pricing without moving
+    /// any CSPR. Returns their cost and the buy fee.
+    fn apply_buy(
+        &mut self,
+        caller: Address,
+        metadata: &MarketMetadata,
+        outcome_id: u64,
+        shares: U256,
+    ) -> (U256, U256) {
+        self.accrue_prices();
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        let current_supply = self.outcome_shares.get(&outcome_id).unwrap_or_default();
+
+        // Calculate actual cost and fee
+        let actual_cost = self.cost_to_buy(outcome_id, shares);
+        let fee = config.fees.buy_fee(actual_cost);
+
+        // Update outcome state
+        let new_supply = current_supply.saturating_add(shares);
+        self.outcome_shares.set(&outcome_id, new_supply);
+        if matches!(config.pricing, PricingMode::CompleteSetAmm) {
+            let mut pool = self.pool();
+            pool.buy(outcome_id as usize, actual_cost, shares);
+            self.set_pool(pool);
+        }
+        
+        let current_liquidity = self.outcome_liquidity.get(&outcome_id).unwrap_or_default();
+        self.outcome_liquidity.set(&outcome_id, current_liquidity.saturating_add(actual_cost));
+
+        // Update total liquidity
+        let total = self.total_liquidity.get_or_default();
+        self.total_liquidity.set(total.saturating_add(actual_cost));
+
+        // Update user position
+        let mut position = self.positions.get(caller, outcome_id);
+        position.add_shares(shares, actual_cost);
+        self.positions.set(caller, outcome_id, position);
+        self.user_participated.set(&caller, true);
+        self.notify_token(outcome_id, None, Some(caller), shares);
+
+        // Calculate new price for event
+        let new_price = self.get_current_price(outcome_id);
+
+        // Emit event
+        self.env().emit_event(SharesPurchased {
+            user: caller,
+            market_id: metadata.market_id,
+            outcome_id,
+            shares,
+            cost: actual_cost,
+            new_price,
+            timestamp: self.env().get_block_time(),
+        });
+
+        (actual_cost, fee)
+    }
+
+    /// Move a fee out of the market's vault balance, splitting off the
+    /// creator's share and booking the rest on the platform fee ledger.
+    fn collect_fees(&self, market_id: u64, amount: U256) {
+        if amount.is_zero() {
+            return;
+        }
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        let mut vault = VaultContractRef::new(self.env(), config.vault_contract);
+        let creator_share = config.fees.creator_fee(amount);
+        if !creator_share.is_zero() {
+            let creator = self.metadata.get().unwrap_or_revert(&self.env()).creator;
+            vault.collect_creator_fees(market_id, creator, creator_share);
+        }
+        vault.collect_platform_fees(market_id, amount.saturating_sub(creator_share));
+    }
+
+    /// Book a buy or sell fee, leaving the LP share of a complete-set AMM
+    /// market's fee in its vault balance for the liquidity providers.
+    fn collect_trading_fees(&mut self, market_id: u64, fee: U256) {
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        let mut pool = self.liquidity.pool();
+        let mut lp_fee = U256::zero();
+        if matches!(config.pricing, PricingMode::CompleteSetAmm) && !pool.total_shares.is_zero() {
+            lp_fee = config.fees.lp_fee(fee);
+            pool.accrue_fees(lp_fee);
+            self.liquidity.set_pool(pool);
+        }
+        self.collect_fees(market_id, fee.saturating_sub(lp_fee));
+    }
+
+    /// Sweep an unclaimable remainder of the market's vault balance to the
+    /// fee recipient, outside the market's fee accounting.
+    fn sweep_to_fee_recipient(&self, market_id: u64, amount: U256) {
+        if amount.is_zero() {
+            return;
+        }
+        let config = self.config.get().unwrap_or_revert(&self.env());
+        VaultContractRef::new(self.env(), config.vault_contract)
+            .sweep_unclaimable(market_id, amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use odra::casper_types::U512;
+    use crate::types::{CurveKind, MAX_FEE_BPS, SCALAR_LONG, SCALAR_SHORT};
+    use crate::oracle::mock::{MockOracle, MockOracleHostRef};
+    use crate::vault::{Vault, VaultHostRef, VaultInitArgs};
+    use odra::host::{Deployer, HostEnv, HostRef, NoArgs};
+
+    const ONE_CSPR: u64 = 1_000_000_000;
+
+    fn setup_binary_market() -> (MarketHostRef, VaultHostRef, HostEnv) {
+        deploy_market(|_| {})
+    }
+
+    /// Deploy a binary market whose creator (account 0) seeded `seed` motes.
+    fn setup_seeded_market(seed: U256) -> (MarketHostRef, VaultHostRef, HostEnv) {
+        deploy_market(|params| params.initial_liquidity = seed)
+    }
+
+    /// Deploy a compound market over two yes/no legs.
+    fn setup_compound_market() -> (MarketHostRef, VaultHostRef, HostEnv) {
+        deploy_market(|params| {
+            let yes_no = vec!["Yes".to_string(), "No".to_string()];
+            params.market_type = MarketType::Compound;
+            params.legs = vec![
+                CompoundLeg { question: "BTC above $100k?".to_string(), outcomes: yes_no.clone() },
+                CompoundLeg { question: "ETH above $5k?".to_string(), outcomes: yes_no },
+            ];
+            params.outcome_names = crate::types::compound_outcome_names(&params.legs);
+        })
+    }
+
+    /// Deploy a market with default binary params adjusted by `configure`,
+    /// authorized in a fresh vault administered by account 0.
+    fn deploy_market(
+        configure: impl FnOnce(&mut MarketInitParams),
+    ) -> (MarketHostRef, VaultHostRef, HostEnv) {
+        deploy_market_in(odra_test::env(), configure)
+    }
+
+    /// Like `deploy_market`, in an existing environment.
+    fn deploy_market_in(
+        env: HostEnv,
+        configure: impl FnOnce(&mut MarketInitParams),
+    ) -> (MarketHostRef, VaultHostRef, HostEnv) {
+        let admin = env.get_account(0);
+        let mut vault = Vault::deploy(&env, VaultInitArgs { admin, fee_recipient: admin });
+
+        let mut params = binary_params(&env, vault.address());
+        configure(&mut params);
+        if !params.initial_liquidity.is_zero() {
+            vault.with_tokens(u256_to_u512(params.initial_liquidity)).deposit(params.market_id);
+        }
+
+        let market_id = params.market_id;
+        let market = Market::deploy(&env, MarketInitArgs { args: params });
+        vault.authorize_market(market.address(), market_id);
+        (market, vault, env)
+    }
+
+    /// Default binary market params, created and administered by account 0.
+    fn binary_params(env: &HostEnv, vault_contract: Address) -> MarketInitParams {
+        let admin = env.get_account(0);
+        MarketInitParams {
+            market_id: 1,
+            market_type: MarketType::Binary,
+            question: "Will BTC reach $100k?".to_string(),
+            outcome_names: vec!["Yes".to_string(), "No".to_string()],
+            end_time: env.block_time() + 86400, // 1 day from now
+            resolution_source: "Price oracle".to_string(),
+            category: "crypto".to_string(),
+            creator: admin,
+            admin,
+            resolver: admin,
+            fees: FeeSchedule::flat(200), // 2%
+            vault_contract,
+            factory_contract: admin, // Placeholder
+            bonding_params: BondingCurveParams::default_params(),
+            pricing: PricingMode::BondingCurve,
+            initial_liquidity: U256::zero(),
+            legs: Vec::new(),
+            scalar: None,
+            oracle: None,
+            optimistic: None,
+            committee: None,
+        }
+    }
+
+    /// Buy outcome shares as `user`, spending `cspr` whole CSPR.
+    fn buy(market: &mut MarketHostRef, env: &HostEnv, user: Address, outcome_id: u64, cspr: u64) {
+        env.set_caller(user);
+        market
+            .with_tokens(U512::from(cspr * ONE_CSPR))
+            .buy_shares(outcome_id, U256::one());
+    }
+
+    #[test]
+    fn test_market_init() {
+        let (market, _vault, _env) = setup_binary_market();
+        
+        let info = market.get_market_info();
+        assert_eq!(info.market_id, 1);
+        assert_eq!(info.question, "Will BTC reach $100k?");
+        assert!(matches!(info.status, MarketStatus::Active));
+        assert_eq!(market.get_outcome_count(), 2);
+    }
+
+    #[test]
+    fn test_get_outcome_names() {
+        let (market, _vault, _env) = setup_binary_market();
+        
+        assert_eq!(market.get_outcome_name(0), Some("Yes".to_string()));
+        assert_eq!(market.get_outcome_name(1), Some("No".to_string()));
+        assert_eq!(market.get_outcome_name(2), None);
+    }
+
+    #[test]
+    fn test_initial_price() {
+        let (market, _vault, _env) = setup_binary_market();
+        
+        // Initial price should be the base price
+        let price = market.get_current_price(0);
+        assert_eq!(price, U256::from(10_000_000u64)); // 0.01 CSPR
+    }
+
+    #[test]
+    fn test_implied_probabilities_follow_demand() {
+        let (mut market, _vault, env) = setup_compound_market();
+        assert_eq!(market.get_implied_probabilities(), vec![2_500; 4]);
+
+        // The outcome bought up the most is the likeliest
+        buy(&mut market, &env, env.get_account(1), 2, 5);
+        buy(&mut market, &env, env.get_account(2), 0, 1);
+        let probabilities = market.get_implied_probabilities();
+        assert_eq!(probabilities.iter().sum::<u64>(), 10_000);
+        assert!(probabilities[2] > probabilities[0] && probabilities[0] > probabilities[1]);
+        assert_eq!(probabilities[1], probabilities[3]);
+        assert_eq!(market.get_outcome_odds(), (0u64..).zip(probabilities).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_twap_weights_prices_by_time() {
+        let (mut market, _vault, env) = deploy_market(|params| params.end_time += 2 * PRICE_CHECKPOINT_PERIOD);
+        let initial_price = market.get_current_price(0);
+
+        // Prices set by trades only count from the next block
+        env.advance_block_time(1_000);
+        buy(&mut market, &env, env.get_account(1), 0, 1);
+        assert_eq!(market.get_cumulative_price(0), initial_price * 1_000);
+        buy(&mut market, &env, env.get_account(2), 0, 1);
+        assert_eq!(market.get_cumulative_price(0), initial_price * 1_000);
+        let new_price = market.get_current_price(0);
+        env.advance_block_time(3_000);
+
+        let expected = (initial_price * 1_000 + new_price * 3_000) / 4_000;
+        assert_eq!(market.get_cumulative_price(0), initial_price * 1_000 + new_price * 3_000);
+        assert_eq!(market.get_twap(0, 4_000), expected);
+        // The average starts at the latest checkpoint, taken at creation
+        assert_eq!(market.get_twap(0, 3_000), expected);
+        assert_eq!(market.get_twap(1, 4_000), initial_price);
+
+        // A trade in a later period checkpoints the cumulative price
+        env.advance_block_time(PRICE_CHECKPOINT_PERIOD);
+        buy(&mut market, &env, env.get_account(1), 0, 1);
+        let latest_price = market.get_current_price(0);
+        env.advance_block_time(1_000);
+        assert_eq!(market.get_twap(0, 1_000), latest_price);
+        assert_eq!(market.get_twap(1, 1_000), initial_price);
+
+        // The window cannot reach past the market's creation
+        let lifetime = PRICE_CHECKPOINT_PERIOD + 5_000;
+        assert!(market.try_get_twap(0, lifetime).is_ok());
+        assert_eq!(market.try_get_twap(0, lifetime + 1), Err(Error::InvalidTwapWindow.into()));
+        assert_eq!(market.try_get_twap(0, 0), Err(Error::InvalidTwapWindow.into()));
+        assert_eq!(market.try_get_twap(2, 1_000), Err(Error::InvalidOutcome.into()));
+    }
+
+    #[test]
+    fn test_limit_orders_fill_when_price_crosses() {
+        let (mut market, _vault, env) = setup_binary_market();
+        let (whale, bidder, seller, keeper) =
+            (env.get_account(1), env.get_account(2), env.get_account(3), env.get_account(4));
+        let expires_at = env.block_time() + 10_000;
+        let reward = U256::from(ONE_CSPR / 100);
+
+        // A bid below the spot price rests in escrow
+        buy(&mut market, &env, whale, 0, 5);
+        let limit_price = market.get_current_price(0) - 1;
+        env.set_caller(bidder);
+        let bid = market
+            .with_tokens(U512::from(2 * ONE_CSPR) + u256_to_u512(reward))
+            .place_buy_order(0, limit_price, reward, expires_at);
+        assert_eq!(u512_to_u256(env.balance_of(&market.address())), U256::from(2 * ONE_CSPR) + reward);
+
+        // A seller reserves shares for an ask above the spot price
+        buy(&mut market, &env, seller, 1, 1);
+        let shares = market.get_user_position(seller, 1).shares;
+        let ask_price = market.get_current_price(1) + 1;
+        let ask = market
+            .with_tokens(u256_to_u512(reward))
+            .place_sell_order(1, shares, ask_price, expires_at);
+        assert_eq!(market.try_sell_shares(1, shares, U256::zero()), Err(Error::InsufficientShares.into()));
+
+        env.set_caller(keeper);
+        assert_eq!(market.execute_orders(vec![bid, ask]), 0);
+
+        // Once the prices cross the limits, any keeper fills both orders
+        let whale_shares = market.get_user_position(whale, 0).shares;
+        env.set_caller(whale);
+        market.sell_shares(0, whale_shares, U256::zero());
+        buy(&mut market, &env, whale, 1, 4);
+        let (keeper_before, seller_before) = (env.balance_of(&keeper), env.balance_of(&seller));
+        env.set_caller(keeper);
+        assert_eq!(market.execute_orders(vec![bid, ask, 99]), 2);
+        assert_eq!(env.balance_of(&keeper) - keeper_before, u256_to_u512(reward * 2));
+        assert!(env.balance_of(&seller) > seller_before);
+        assert!(!market.get_user_position(bidder, 0).shares.is_zero());
+        assert!(market.get_user_position(seller, 1).shares.is_zero());
+        assert!(matches!(market.get_order(bid).unwrap().status, OrderStatus::Filled));
+        assert_eq!(env.balance_of(&market.address()), U512::zero());
+        assert_eq!(market.try_cancel_order(ask), Err(Error::OrderNotOpen.into()));
+    }
+
+    #[test]
+    fn test_limit_orders_fill_only_within_the_limit() {
+        let (mut market, _vault, env) = setup_binary_market();
+        let (bidder, seller, keeper) = (env.get_account(1), env.get_account(2), env.get_account(3));
+        let expires_at = env.block_time() + 10_000;
+        let budget = U256::from(20 * ONE_CSPR);
+        let reward = U256::from(ONE_CSPR / 100);
+
+        // A bid whose budget would push the price past the limit buys less
+        buy(&mut market, &env, seller, 0, 5);
+        let limit_price = market.get_current_price(0);
+        let seller_shares = market.get_user_position(seller, 0).shares;
+        env.set_caller(seller);
+        market.sell_shares(0, seller_shares / 2, U256::zero());
+        env.set_caller(bidder);
+        let bid = market
+            .with_tokens(u256_to_u512(budget + reward))
+            .place_buy_order(0, limit_price, reward, expires_at);
+        let keeper_before = env.balance_of(&keeper);
+        env.set_caller(keeper);
+        assert_eq!(market.execute_orders(vec![bid]), 1);
+        assert!(market.get_current_price(0) <= limit_price);
+
+        // It keeps resting with the unspent budget, the keeper paid for the part filled
+        let order = market.get_order(bid).unwrap();
+        assert!(matches!(order.status, OrderStatus::Open));
+        let spent = budget - order.amount;
+        assert!(!spent.is_zero() && spent < U256::from(5 * ONE_CSPR));
+        let paid = reward * spent / budget;
+        assert_eq!(env.balance_of(&keeper) - keeper_before, u256_to_u512(paid));
+        assert_eq!(order.keeper_reward, reward - paid);
+        assert!(env.emitted_event(
+            &market,
+            OrderFilled {
+                order_id: bid,
+                owner: bidder,
+                keeper,
+                market_id: 1,
+                keeper_reward: paid,
+                remaining: order.amount,
+                timestamp: env.block_time(),
+            }
+        ));
+        assert_eq!(u512_to_u256(env.balance_of(&market.address())), order.amount + order.keeper_reward);
+
+        // It fills again once the price falls back, and cancelling refunds the rest
+        let bought = market.get_user_position(bidder, 0).shares;
+        env.set_caller(seller);
+        market.sell_shares(0, market.get_user_position(seller, 0).shares, U256::zero());
+        env.set_caller(keeper);
+        assert_eq!(market.execute_orders(vec![bid]), 1);
+        assert!(market.get_user_position(bidder, 0).shares > bought);
+        assert!(market.get_order(bid).unwrap().amount < order.amount);
+        env.set_caller(bidder);
+        market.cancel_order(bid);
+        assert_eq!(env.balance_of(&market.address()), U512::zero());
+
+        // An ask sells only down to its limit and keeps the rest reserved
+        let shares = market.get_user_position(bidder, 0).shares;
+        let ask_price = market.get_current_price(0) * 9 / 10;
+        let ask = market.place_sell_order(0, shares, ask_price, expires_at);
+        env.set_caller(keeper);
+        assert_eq!(market.execute_orders(vec![ask]), 1);
+        assert!(market.get_current_price(0) >= ask_price);
+        let order = market.get_order(ask).unwrap();
+        assert!(matches!(order.status, OrderStatus::Open));
+        let position = market.get_user_position(bidder, 0);
+        assert!(position.shares > U256::zero() && position.shares < shares);
+        assert_eq!(position.locked_shares, order.amount);
+        assert_eq!(position.shares, order.amount);
+    }
+
+    #[test]
+    fn test_limit_orders_cancel_and_expire() {
+        let (mut market, _vault, env) = setup_binary_market();
+        let owner = env.get_account(1);
+        let expires_at = env.block_time() + 10_000;
+
+        env.set_caller(owner);
+        let order = market
+            .with_tokens(U512::from(ONE_CSPR))
+            .place_buy_order(0, U256::one(), U256::zero(), expires_at);
+        assert_eq!(
+            market
+                .with_tokens(U512::from(ONE_CSPR))
+                .try_place_buy_order(0, U256::one(), U256::zero(), env.block_time()),
+            Err(Error::InvalidOrderExpiry.into())
+        );
+
+        // Only the owner can cancel before expiry; anyone can close the order after
+        env.set_caller(env.get_account(2));
+        assert_eq!(market.try_cancel_order(order), Err(Error::NotOrderOwner.into()));
+        env.advance_block_time(10_001);
+        let balance_before = env.balance_of(&owner);
+        market.cancel_order(order);
+        assert_eq!(env.balance_of(&owner) - balance_before, U512::from(ONE_CSPR));
+        assert!(matches!(market.get_order(order).unwrap().status, OrderStatus::Cancelled));
+        assert_eq!(market.try_cancel_order(7), Err(Error::OrderNotFound.into()));
+        assert_eq!(market.get_order_count(), 1);
+    }
+
+    #[test]
+    fn test_transfer_moves_shares_with_cost_basis() {
+        let (mut market, vault, env) = setup_binary_market();
+        let (alice, bob) = (env.get_account(1), env.get_account(2));
+        buy(&mut market, &env, alice, 0, 1);
+        let bought = market.get_user_position(alice, 0);
+        let price = market.get_current_price(0);
+
+        env.set_caller(alice);
+        let half = bought.shares / 2;
+        market.transfer_shares(bob, 0, half);
+        assert_eq!(market.try_transfer_shares(bob, 0, bought.shares), Err(Error::InsufficientShares.into()));
+        assert_eq!(market.try_transfer_shares(bob, 2, half), Err(Error::InvalidOutcome.into()));
+
+        // Shares and cost basis move; supply and price do not
+        let (kept, received) = (market.get_user_position(alice, 0), market.get_user_position(bob, 0));
+        assert_eq!(kept.shares + received.shares, bought.shares);
+        assert_eq!(kept.total_cost + received.total_cost, bought.total_cost);
+        assert_eq!(received.total_cost, bought.total_cost * half / bought.shares);
+        assert_eq!(market.get_outcome_shares(0), bought.shares);
+        assert_eq!(market.get_current_price(0), price);
+
+        // Both refunds together return exactly what was paid
+        env.set_caller(env.get_account(0));
+        market.cancel_market("ambiguous".to_string());
+        for user in [alice, bob] {
+            env.set_caller(user);
+            market.claim_refund();
+        }
+        assert_eq!(vault.get_market_balance(1), U256::zero());
+
+        env.set_caller(alice);
+        assert_eq!(market.try_transfer_shares(bob, 0, kept.shares), Err(Error::AlreadyClaimed.into()));
+    }
+
+    #[test]
+    fn test_transfer_from_spends_allowance() {
+        let (mut market, _vault, env) = setup_binary_market();
+        let (owner, desk, client) = (env.get_account(1), env.get_account(2), env.get_account(3));
+        buy(&mut market, &env, owner, 1, 1);
+        let shares = market.get_user_position(owner, 1).shares;
+
+        env.set_caller(desk);
+        assert_eq!(
+            market.try_transfer_shares_from(owner, client, 1, shares),
+            Err(Error::InsufficientAllowance.into())
+        );
+
+        env.set_caller(owner);
+        market.approve_shares(desk, 1, shares);
+        assert_eq!(market.get_share_allowance(owner, desk, 1), shares);
+
+        env.set_caller(desk);
+        market.transfer_shares_from(owner, client, 1, shares - 10);
+        assert_eq!(market.get_share_allowance(owner, desk, 1), U256::from(10u64));
+        assert_eq!(market.get_user_position(client, 1).shares, shares - 10);
+        assert_eq!(
+            market.try_transfer_shares_from(owner, client, 1, U256::from(11u64)),
+            Err(Error::InsufficientAllowance.into())
+        );
+
+        // The winner is whoever holds the shares at resolution
+        env.advance_block_time(86_401);
+        env.set_caller(env.get_account(0));
+        market.resolve_market(1, "proof".to_string());
+        env.set_caller(client);
+        market.claim_winnings();
+    }
+
+    #[test]
+    fn test_buy_deposits_cost_into_vault() {
+        let (mut market, vault, env) = setup_binary_market();
+        let trader = env.get_account(1);
+        let balance_before = env.balance_of(&trader);
+
+        buy(&mut market, &env, trader, 0, 1);
+
+        let position = market.get_user_position(trader, 0);
+        assert_eq!(vault.get_market_balance(1), position.total_cost);
+        assert_eq!(vault.get_total_locked(), position.total_cost);
+        // Only the actual cost leaves the trader; the excess is refunded
+        assert_eq!(
+            balance_before - env.balance_of(&trader),
+            u256_to_u512(position.total_cost)
+        );
+        assert_eq!(env.balance_of(&market), U512::zero());
+    }
+
+    #[test]
+    fn test_buy_fee_is_charged_on_top_of_cost() {
+        let fees = FeeSchedule { buy_fee_bps: 100, ..FeeSchedule::flat(200) };
+        let (mut market, vault, env) = deploy_market(|params| params.fees = fees);
+        let trader = env.get_account(1);
+        let balance_before = env.balance_of(&trader);
+
+        buy(&mut market, &env, trader, 0, 1);
+
+        let position = market.get_user_position(trader, 0);
+        let fee = fees.buy_fee(position.total_cost);
+        assert!(!fee.is_zero());
+        assert_eq!(
+            balance_before - env.balance_of(&trader),
+            u256_to_u512(position.total_cost + fee)
+        );
+        assert_eq!(vault.get_market_balance(1), position.total_cost);
+        assert_eq!(vault.get_market_fees(1), fee);
+
+        // Quotes include the fee
+        let quote = market.calculate_buy_cost(0, U256::from(10u64));
+        let supply = market.get_outcome_shares(0);
+        let cost = BondingCurveParams::default_params().cost_to_buy(supply, U256::from(10u64));
+        assert_eq!(quote, cost + fees.buy_fee(cost));
+    }
+
+    #[test]
+    fn test_creator_share_of_every_fee_accrues_in_vault() {
+        let fees = FeeSchedule { buy_fee_bps: 100, creator_share_bps: 2_500, ..FeeSchedule::flat(200) };
+        let (mut market, mut vault, env) = deploy_market(|params| params.fees = fees);
+        let creator = env.get_account(0);
+        let trader = env.get_account(1);
+        buy(&mut market, &env, trader, 0, 2);
+        let buy_fee = vault.get_market_fees(1);
+        let shares = market.get_user_position(trader, 0).shares;
+        market.sell_shares(0, shares / 2, U256::zero());
+        let sell_fee = vault.get_market_fees(1) - buy_fee;
+
+        env.set_caller(creator);
+        market.close_market();
+        market.resolve_market(0, "oracle".to_string());
+        let settlement_fee = market.get_settlement().unwrap().fee_amount;
+
+        let creator_fees = vault.get_creator_fees(creator);
+        assert_eq!(
+            creator_fees,
+            fees.creator_fee(buy_fee) + fees.creator_fee(sell_fee) + fees.creator_fee(settlement_fee)
+        );
+        assert_eq!(vault.get_platform_fees() + creator_fees, buy_fee + sell_fee + settlement_fee);
+
+        let balance_before = env.balance_of(&creator);
+        vault.claim_creator_fees();
+        assert_eq!(env.balance_of(&creator) - balance_before, u256_to_u512(creator_fees));
+    }
+
+    #[test]
+    fn test_buy_exact_shares_matches_buy_shares() {
+        let fees = FeeSchedule { buy_fee_bps: 100, ..FeeSchedule::flat(200) };
+        let (mut by_budget, _vault, env) = deploy_market(|params| params.fees = fees);
+        let trader = env.get_account(1);
+        let before = env.balance_of(&trader);
+        buy(&mut by_budget, &env, trader, 0, 1);
+        let budget_spent = before - env.balance_of(&trader);
+        let bought = by_budget.get_user_position(trader, 0);
+
+        let (exact, exact_vault, env) = deploy_market(|params| params.fees = fees);
+        let before = env.balance_of(&trader);
+        let quote = exact.calculate_buy_cost(0, bought.shares);
+        env.set_caller(trader);
+        exact
+            .with_tokens(U512::from(ONE_CSPR))
+            .buy_exact_shares(0, bought.shares, quote);
+
+        // Same shares, same cost basis and the same refund of the excess
+        assert_eq!(exact.get_user_position(trader, 0), bought);
+        assert_eq!(before - env.balance_of(&trader), budget_spent);
+        assert_eq!(u512_to_u256(budget_spent), quote);
+        assert_eq!(exact_vault.get_market_balance(1), bought.total_cost);
     }
-    
-    /// Get config
-    pub fn get_config(&self) -> MarketConfig {
-        self.config.get().unwrap_or_revert(&self.env())
+
+    #[test]
+    fn test_buy_exact_shares_slippage_and_funds() {
+        let (market, _vault, env) = setup_binary_market();
+        let trader = env.get_account(1);
+        let shares = U256::from(50u64);
+        let quote = market.calculate_buy_cost(0, shares);
+        env.set_caller(trader);
+
+        // Asking for more than the budget buys fails like the budget entry point
+        let achievable = market.calculate_buy_cost(0, U256::one());
+        assert_eq!(
+            market.with_tokens(u256_to_u512(achievable)).try_buy_shares(0, U256::from(2u64)),
+            Err(Error::SlippageExceeded.into())
+        );
+        assert_eq!(
+            market.with_tokens(u256_to_u512(quote)).try_buy_exact_shares(0, shares, quote - 1),
+            Err(Error::SlippageExceeded.into())
+        );
+        assert_eq!(
+            market.with_tokens(u256_to_u512(quote - 1)).try_buy_exact_shares(0, shares, quote),
+            Err(Error::InsufficientFunds.into())
+        );
+        assert_eq!(
+            market.with_tokens(u256_to_u512(quote)).try_buy_exact_shares(0, U256::zero(), quote),
+            Err(Error::ZeroAmount.into())
+        );
+
+        market.with_tokens(u256_to_u512(quote)).buy_exact_shares(0, shares, quote);
+        assert_eq!(market.get_user_position(trader, 0).shares, shares);
     }
 
-    // =========================================================================
-    // Internal Helper Functions
-    // =========================================================================
+    #[test]
+    fn test_trades_on_every_curve_kind() {
+        for kind in [CurveKind::Linear, CurveKind::Exponential, CurveKind::Sqrt, CurveKind::CappedSigmoid] {
+            let (mut market, vault, env) =
+                deploy_market(|params| params.bonding_params = BondingCurveParams::preset(kind));
+            let trader = env.get_account(1);
+            let price_before = market.get_current_price(0);
+            buy(&mut market, &env, trader, 0, 1);
+            assert!(market.get_current_price(0) > price_before);
 
-    /// Ensure market is in Active status.
-    fn require_active(&self) {
-        let status = self.status.get_or_default();
-        if !matches!(status, MarketStatus::Active) {
-            self.env().revert(Error::MarketNotActive);
+            // Selling everything back returns the cost minus the sell fee
+            let position = market.get_user_position(trader, 0);
+            let revenue = market.calculate_sell_revenue(0, position.shares);
+            let fee = FeeSchedule::flat(200).sell_fee(position.total_cost);
+            assert_eq!(revenue, position.total_cost - fee);
+            market.sell_shares(0, position.shares, revenue);
+            assert_eq!(vault.get_market_balance(1), U256::zero());
         }
     }
 
-    /// Ensure market hasn't ended.
-    fn require_not_ended(&self) {
-        if self.is_ended() {
-            self.env().revert(Error::MarketAlreadyEnded);
-        }
+    #[test]
+    fn test_lmsr_prices_are_probabilities() {
+        let seed = U256::from(10 * ONE_CSPR);
+        let (mut market, _vault, env) = deploy_market(|params| {
+            params.pricing = PricingMode::Lmsr;
+            params.initial_liquidity = seed;
+        });
+        assert_eq!(market.get_current_price(0), U256::from(LMSR_SHARE_VALUE / 2));
+        assert_eq!(market.get_outcome_odds(), vec![(0, 5_000), (1, 5_000)]);
+
+        buy(&mut market, &env, env.get_account(1), 0, 3);
+        let (yes, no) = (market.get_current_price(0), market.get_current_price(1));
+        assert!(yes > no);
+        assert!(yes + no <= U256::from(LMSR_SHARE_VALUE) && yes + no >= U256::from(LMSR_SHARE_VALUE - 2));
+        assert_eq!(market.try_get_current_price(2), Err(Error::InvalidOutcome.into()));
+        let odds = market.get_outcome_odds();
+        assert!(odds[0].1 > 5_000 && odds[0].1 + odds[1].1 >= 9_998);
+
+        // A market maker without a subsidy cannot be deployed
+        let mut params = binary_params(&env, env.get_account(0));
+        params.pricing = PricingMode::Lmsr;
+        assert!(Market::try_deploy(&env, MarketInitArgs { args: params }).is_err());
     }
 
-    /// Ensure market is closed or has ended.
-    fn require_closed_or_ended(&self) {
-        let status = self.status.get_or_default();
-        let is_closed = matches!(status, MarketStatus::Closed);
-        let is_ended = self.is_ended();
-        
-        if !is_closed && !is_ended {
-            self.env().revert(Error::MarketNotEnded);
+    #[test]
+    fn test_lmsr_winners_are_paid_a_share_value_each() {
+        let seed = U256::from(10 * ONE_CSPR);
+        let (mut market, vault, env) = deploy_market(|params| {
+            params.pricing = PricingMode::Lmsr;
+            params.initial_liquidity = seed;
+        });
+        let creator = env.get_account(0);
+        let winner = env.get_account(1);
+        // The favourite is bought up far past the subsidy; the loser buys a little
+        for _ in 0..5 {
+            buy(&mut market, &env, winner, 0, 20);
         }
+        buy(&mut market, &env, env.get_account(2), 1, 2);
+        let shares = market.get_user_position(winner, 0).shares;
+
+        env.set_caller(creator);
+        market.close_market();
+        market.resolve_market(0, "oracle".to_string());
+        let settlement = market.get_settlement().unwrap();
+        let owed = shares * U256::from(LMSR_SHARE_VALUE);
+        assert_eq!(settlement.fee_amount + settlement.distributable_pool, owed);
+
+        let balance_before = env.balance_of(&winner);
+        env.set_caller(winner);
+        market.claim_winnings();
+        assert_eq!(u512_to_u256(env.balance_of(&winner) - balance_before), settlement.distributable_pool);
+
+        // The market maker's loss stays within its subsidy; the creator takes what is left
+        assert!(settlement.creator_return < seed);
+        let balance_before = env.balance_of(&creator);
+        env.set_caller(creator);
+        market.withdraw_seed_liquidity();
+        assert_eq!(u512_to_u256(env.balance_of(&creator) - balance_before), settlement.creator_return);
+        assert_eq!(vault.get_market_balance(1), U256::zero());
     }
 
-    /// Ensure caller is admin.
-    fn require_admin(&self) {
-        let caller = self.env().caller();
-        let config = self.config.get().unwrap_or_revert(&self.env());
-        if caller != config.admin {
-            self.env().revert(Error::NotAdmin);
+    #[test]
+    fn test_complete_sets_mint_and_redeem() {
+        let (mut market, vault, env) = deploy_market(|params| {
+            params.pricing = PricingMode::CompleteSetAmm;
+            params.initial_liquidity = U256::from(10 * ONE_CSPR);
+        });
+        assert_eq!(market.get_current_price(0), U256::from(ONE_CSPR / 2));
+
+        // Minting gives one share of each outcome per mote and leaves prices alone
+        let user = env.get_account(1);
+        env.set_caller(user);
+        market.with_tokens(U512::from(3 * ONE_CSPR + 1)).mint_complete_sets();
+        let sets = U256::from(3 * ONE_CSPR + 1);
+        for outcome_id in 0..2 {
+            assert_eq!(market.get_user_position(user, outcome_id).shares, sets);
+            assert_eq!(market.get_outcome_shares(outcome_id), sets);
         }
+        assert_eq!(market.get_user_position(user, 0).total_cost, U256::from(1_500_000_001u64));
+        assert_eq!(market.get_outcome_odds(), vec![(0, 5_000), (1, 5_000)]);
+        assert_eq!(vault.get_market_balance(1), sets + U256::from(10 * ONE_CSPR));
+
+        // Redeeming pays one mote per set, but only for complete sets held
+        let balance_before = env.balance_of(&user);
+        market.redeem_complete_sets(U256::from(ONE_CSPR));
+        assert_eq!(env.balance_of(&user) - balance_before, U512::from(ONE_CSPR));
+        assert_eq!(market.get_user_position(user, 1).shares, U256::from(2 * ONE_CSPR + 1));
+        assert_eq!(
+            market.try_redeem_complete_sets(U256::from(3 * ONE_CSPR)),
+            Err(Error::InsufficientShares.into())
+        );
+
+        // Sets can still be redeemed after trading closes
+        env.set_caller(env.get_account(0));
+        market.close_market();
+        env.set_caller(user);
+        market.redeem_complete_sets(U256::from(2 * ONE_CSPR + 1));
+        assert_eq!(market.get_market_info().total_liquidity, U256::zero());
+        assert_eq!(vault.get_market_balance(1), U256::from(10 * ONE_CSPR));
+
+        // Bonding curve markets have no complete sets
+        let (market, _vault, env) = setup_binary_market();
+        env.set_caller(user);
+        assert_eq!(
+            market.with_tokens(U512::from(ONE_CSPR)).try_mint_complete_sets(),
+            Err(Error::NotCompleteSetMarket.into())
+        );
+    }
+
+    #[test]
+    fn test_complete_set_amm_trades_and_settles() {
+        let seed = U256::from(10 * ONE_CSPR);
+        let (mut market, vault, env) = deploy_market(|params| {
+            params.pricing = PricingMode::CompleteSetAmm;
+            params.initial_liquidity = seed;
+        });
+        let creator = env.get_account(0);
+        let winner = env.get_account(1);
+        let loser = env.get_account(2);
+
+        // Buying an outcome makes it more likely and returns more than a share per mote
+        buy(&mut market, &env, winner, 0, 5);
+        let shares = market.get_user_position(winner, 0).shares;
+        assert!(shares > U256::from(5 * ONE_CSPR));
+        assert!(market.get_current_price(0) > market.get_current_price(1));
+        let odds = market.get_outcome_odds();
+        assert!(odds[0].1 > 5_000 && odds[0].1 + odds[1].1 >= 9_998);
+
+        buy(&mut market, &env, loser, 1, 2);
+        let loser_shares = market.get_user_position(loser, 1).shares;
+        let expected = market.calculate_sell_revenue(1, loser_shares / 2);
+        market.sell_shares(1, loser_shares / 2, expected);
+
+        env.set_caller(creator);
+        market.close_market();
+        market.resolve_market(0, "oracle".to_string());
+
+        // Every winning share pays one mote, less the settlement fee
+        let settlement = market.get_settlement().unwrap();
+        assert_eq!(settlement.fee_amount + settlement.distributable_pool, shares);
+        let balance_before = env.balance_of(&winner);
+        env.set_caller(winner);
+        market.claim_winnings();
+        assert_eq!(u512_to_u256(env.balance_of(&winner) - balance_before), settlement.distributable_pool);
+
+        // The creator, as sole liquidity provider, takes the winning shares left in the pool
+        assert_eq!(settlement.creator_return, market.get_market_info().total_liquidity + seed - shares);
+        env.set_caller(creator);
+        assert_eq!(market.try_withdraw_seed_liquidity(), Err(Error::NothingToClaim.into()));
+        market.remove_liquidity(seed);
+        assert_eq!(vault.get_market_balance(1), U256::zero());
     }
 
-    /// Binary search to find how many shares can be bought for a given cost.
-    fn calculate_shares_for_cost(
-        &self,
-        params: &BondingCurveParams,
-        current_supply: U256,
-        max_cost: U256,
-    ) -> U256 {
-        if max_cost.is_zero() {
-            return U256::zero();
+    #[test]
+    fn test_liquidity_providers_earn_fees_and_exit() {
+        let seed = U256::from(10 * ONE_CSPR);
+        let (mut market, vault, env) = deploy_market(|params| {
+            params.pricing = PricingMode::CompleteSetAmm;
+            params.initial_liquidity = seed;
+            params.fees = FeeSchedule { buy_fee_bps: 100, lp_share_bps: 5_000, ..FeeSchedule::flat(200) };
+        });
+        let creator = env.get_account(0);
+        let provider = env.get_account(1);
+        let trader = env.get_account(2);
+        assert_eq!(market.get_liquidity_position(creator).shares, seed);
+
+        // Liquidity added at even odds mints LP shares one for one
+        env.set_caller(provider);
+        market.with_tokens(U512::from(10 * ONE_CSPR)).add_liquidity();
+        assert_eq!(market.get_liquidity_position(provider).shares, seed);
+        assert_eq!(market.get_liquidity_pool().total_shares, seed * 2);
+
+        // Half of the trading fee is split between the two providers
+        env.set_caller(trader);
+        market.with_tokens(U512::from(5 * ONE_CSPR)).buy_shares(0, U256::one());
+        let lp_fees = market.get_liquidity_pool().unpaid_fees;
+        assert!(!lp_fees.is_zero());
+        assert_eq!(market.get_pending_lp_fees(provider), lp_fees / 2);
+        assert_eq!(market.get_pending_lp_fees(creator), lp_fees / 2);
+        assert_eq!(vault.get_platform_fees(), vault.get_market_fees(1));
+
+        // Adding liquidity does not move prices; the provider keeps the surplus shares
+        let odds = market.get_outcome_odds();
+        env.set_caller(provider);
+        market.with_tokens(U512::from(ONE_CSPR)).add_liquidity();
+        assert_eq!(market.get_outcome_odds(), odds);
+        assert!(!market.get_user_position(provider, 0).shares.is_zero());
+        assert!(market.get_user_position(provider, 1).shares.is_zero());
+        assert!(market.get_pending_lp_fees(provider).is_zero());
+
+        env.set_caller(creator);
+        market.claim_lp_fees();
+        assert_eq!(market.try_claim_lp_fees(), Err(Error::NothingToClaim.into()));
+        market.close_market();
+        market.resolve_market(0, "oracle".to_string());
+
+        // Winners claim, then providers split the winning shares left in the pool
+        for user in [trader, provider] {
+            env.set_caller(user);
+            market.claim_winnings();
         }
+        let creator_return = market.get_settlement().unwrap().creator_return;
+        let provider_shares = market.get_liquidity_position(provider).shares;
+        let total_shares = market.get_liquidity_pool().total_shares;
+        let balance_before = env.balance_of(&provider);
+        env.set_caller(provider);
+        assert_eq!(
+            market.try_remove_liquidity(provider_shares + 1),
+            Err(Error::InsufficientLiquidityShares.into())
+        );
+        market.remove_liquidity(provider_shares);
+        assert_eq!(
+            u512_to_u256(env.balance_of(&provider) - balance_before),
+            creator_return * provider_shares / total_shares
+        );
+
+        env.set_caller(creator);
+        market.remove_liquidity(seed);
+        assert!(market.get_liquidity_pool().total_shares.is_zero());
+        assert_eq!(vault.get_market_balance(1), U256::zero());
+    }
 
-        // Binary search for the maximum number of shares
-        let mut low = U256::zero();
-        let mut high = max_cost / params.initial_price; // Upper bound estimate
-        high = high.saturating_add(U256::from(1000u64)); // Add buffer
+    #[test]
+    fn test_market_rejects_fees_above_max() {
+        let env = odra_test::env();
+        let mut params = binary_params(&env, env.get_account(0));
+        params.fees.buy_fee_bps = MAX_FEE_BPS + 1;
+        assert!(Market::try_deploy(&env, MarketInitArgs { args: params }).is_err());
 
-        while low < high {
-            let mid = (low + high + U256::one()) / U256::from(2u64);
-            let cost = params.cost_to_buy(current_supply, mid);
-            
-            if cost <= max_cost {
-                low = mid;
-            } else {
-                high = mid - U256::one();
+        let mut params = binary_params(&env, env.get_account(0));
+        params.bonding_params.initial_price = U256::zero();
+        assert!(Market::try_deploy(&env, MarketInitArgs { args: params }).is_err());
+    }
+
+    #[test]
+    fn test_sell_is_paid_from_vault() {
+        let (mut market, vault, env) = setup_binary_market();
+        let trader = env.get_account(1);
+        buy(&mut market, &env, trader, 0, 1);
+
+        let shares = market.get_user_position(trader, 0).shares;
+        let expected = market.calculate_sell_revenue(0, shares);
+        let balance_before = env.balance_of(&trader);
+        let vault_before = vault.get_market_balance(1);
+
+        market.sell_shares(0, shares, expected);
+
+        // The seller gets the net revenue and the fee moves to the fee ledger
+        let fee = vault.get_market_fees(1);
+        assert!(!fee.is_zero());
+        assert_eq!(env.balance_of(&trader) - balance_before, u256_to_u512(expected));
+        assert_eq!(vault.get_platform_fees(), fee);
+        assert_eq!(vault.get_market_balance(1), vault_before - expected - fee);
+        assert_eq!(market.get_market_info().total_liquidity, vault.get_market_balance(1));
+    }
+
+    #[test]
+    fn test_batch_trade_funds_buys_with_sells() {
+        let (mut market, vault, env) = setup_binary_market();
+        let trader = env.get_account(1);
+        buy(&mut market, &env, trader, 0, 2);
+        let shares = market.get_user_position(trader, 0).shares;
+
+        let proceeds = market.calculate_sell_revenue(0, shares);
+        let cost = market.calculate_buy_cost(1, U256::from(5u64));
+        let legs = vec![
+            TradeLeg { outcome_id: 1, side: OrderSide::Buy, shares: U256::from(5u64) },
+            TradeLeg { outcome_id: 0, side: OrderSide::Sell, shares },
+        ];
+
+        // The bound applies to what comes back in total, attached value included
+        let attached = U512::from(ONE_CSPR);
+        assert_eq!(
+            market.with_tokens(attached).try_batch_trade(legs.clone(), proceeds - cost + ONE_CSPR + 1),
+            Err(Error::SlippageExceeded.into())
+        );
+        let balance_before = env.balance_of(&trader);
+        market.with_tokens(attached).batch_trade(legs, proceeds - cost + ONE_CSPR);
+
+        assert_eq!(env.balance_of(&trader) - balance_before, u256_to_u512(proceeds - cost));
+        assert_eq!(market.get_user_position(trader, 0).shares, U256::zero());
+        assert_eq!(market.get_user_position(trader, 1).shares, U256::from(5u64));
+        assert_eq!(market.get_market_info().total_liquidity, vault.get_market_balance(1));
+        assert_eq!(env.balance_of(&market), U512::zero());
+
+        // Buys beyond the proceeds need attached CSPR
+        let legs = vec![TradeLeg { outcome_id: 0, side: OrderSide::Buy, shares: U256::from(5u64) }];
+        assert_eq!(market.try_batch_trade(legs, U256::zero()), Err(Error::InsufficientFunds.into()));
+        assert_eq!(market.try_batch_trade(Vec::new(), U256::zero()), Err(Error::ZeroAmount.into()));
+    }
+
+    /// Deploy a mock oracle and a market of `outcome_count` outcomes bound
+    /// to it, accepting answers up to an hour old.
+    fn setup_oracle_market(outcome_count: usize) -> (MarketHostRef, MockOracleHostRef, HostEnv) {
+        let env = odra_test::env();
+        let oracle = MockOracle::deploy(&env, NoArgs);
+        let oracle_address = oracle.address();
+        let (market, _vault, env) = deploy_market_in(env, |params| {
+            params.outcome_names = (0..outcome_count).map(|o| format!("Outcome {}", o)).collect();
+            if outcome_count > 2 {
+                params.market_type = MarketType::MultipleChoice;
             }
+            params.oracle = Some(OracleBinding { oracle: oracle_address, query_id: 42, max_age: 3_600_000 });
+        });
+        (market, oracle, env)
+    }
+
+    #[test]
+    fn test_oracle_resolves_binary_and_multiple_choice_markets() {
+        for (outcome_count, answer) in [(2usize, 1u64), (4, 2)] {
+            let (mut market, mut oracle, env) = setup_oracle_market(outcome_count);
+            let trader = env.get_account(1);
+            buy(&mut market, &env, trader, answer, 1);
+
+            // Answers count only once reported after the market ended
+            oracle.report(42, answer, false);
+            assert_eq!(market.try_resolve_from_oracle(), Err(Error::MarketNotEnded.into()));
+            env.advance_block_time(86_401);
+            assert_eq!(market.try_resolve_from_oracle(), Err(Error::StaleOracleAnswer.into()));
+            oracle.report(42, answer, false);
+            env.advance_block_time(3_600_001);
+            assert_eq!(market.try_resolve_from_oracle(), Err(Error::StaleOracleAnswer.into()));
+            oracle.report(42, answer, false);
+
+            // Anyone can resolve once the answer is fresh
+            env.set_caller(env.get_account(4));
+            market.resolve_from_oracle();
+            assert_eq!(market.get_winning_outcome(), Some(answer));
+            env.set_caller(trader);
+            market.claim_winnings();
         }
+    }
 
-        low
+    #[test]
+    fn test_invalid_oracle_answer_resolves_market_invalid() {
+        let (mut market, mut oracle, env) = setup_oracle_market(2);
+        let trader = env.get_account(1);
+        buy(&mut market, &env, trader, 0, 1);
+        env.advance_block_time(86_401);
+        assert_eq!(market.try_resolve_from_oracle(), Err(Error::OracleAnswerMissing.into()));
+
+        oracle.report(42, 0, true);
+        market.resolve_from_oracle();
+        assert!(matches!(market.get_status(), MarketStatus::Resolved));
+        assert!(env.emitted_event(
+            &market,
+            MarketResolvedInvalid {
+                market_id: 1,
+                resolver: oracle.address(),
+                timestamp: env.block_time(),
+                proof: "Oracle answered invalid".to_string(),
+            }
+        ));
+        let settlement = market.get_settlement().unwrap();
+        assert!(settlement.invalid);
+        let shares = market.get_user_position(trader, 0).shares;
+        let before = env.balance_of(&trader);
+        env.set_caller(trader);
+        market.claim_refund();
+        assert_eq!(u512_to_u256(env.balance_of(&trader) - before), settlement.payout_for(shares));
+        assert_eq!(market.try_resolve_from_oracle(), Err(Error::MarketAlreadyResolved.into()));
+
+        // Markets without an oracle are resolved by hand
+        let (mut unbound, _vault, env) = setup_binary_market();
+        env.advance_block_time(86_401);
+        assert_eq!(unbound.try_resolve_from_oracle(), Err(Error::OracleNotSet.into()));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use odra::host::{Deployer, HostRef};
+    /// Deploy a binary market accepting proposed outcomes.
+    fn setup_optimistic_market() -> (MarketHostRef, VaultHostRef, HostEnv) {
+        deploy_market(|params| params.optimistic = Some(OptimisticParams::default_params()))
+    }
+
+    #[test]
+    fn test_undisputed_proposal_finalizes_after_window() {
+        let (mut market, _vault, env) = setup_optimistic_market();
+        let (trader, proposer) = (env.get_account(1), env.get_account(2));
+        buy(&mut market, &env, trader, 0, 1);
+        let bond = OptimisticParams::default_params().bond;
+        let window = OptimisticParams::default_params().dispute_window;
+
+        env.set_caller(proposer);
+        assert_eq!(
+            market.with_tokens(u256_to_u512(bond)).try_propose_outcome(0),
+            Err(Error::MarketNotEnded.into())
+        );
+        env.advance_block_time(86_401);
+        assert_eq!(
+            market.with_tokens(u256_to_u512(bond - 1)).try_propose_outcome(0),
+            Err(Error::InsufficientBond.into())
+        );
+        let balance_before = env.balance_of(&proposer);
+        market.with_tokens(u256_to_u512(bond)).propose_outcome(0);
+        assert!(matches!(market.get_status(), MarketStatus::Proposed));
+
+        // Claims wait for finality
+        env.set_caller(trader);
+        assert_eq!(market.try_claim_winnings(), Err(Error::MarketNotResolved.into()));
+        assert_eq!(market.try_finalize_proposal(), Err(Error::DisputeWindowOpen.into()));
+
+        // Once the window closes the proposal is final and cannot be overridden
+        env.advance_block_time(window + 1);
+        env.set_caller(env.get_account(0));
+        assert_eq!(
+            market.try_resolve_market(1, "proof".to_string()),
+            Err(Error::DisputeWindowClosed.into())
+        );
+
+        // The first claim after the window finalises the proposal
+        env.set_caller(trader);
+        market.claim_winnings();
+        assert_eq!(market.get_winning_outcome(), Some(0));
+        assert_eq!(env.balance_of(&proposer), balance_before);
+        assert_eq!(env.balance_of(&market), U512::zero());
+    }
+
+    #[test]
+    fn test_disputed_proposal_slashes_the_wrong_side() {
+        let (mut market, _vault, env) = setup_optimistic_market();
+        let (trader, proposer, disputer) = (env.get_account(1), env.get_account(2), env.get_account(3));
+        buy(&mut market, &env, trader, 1, 1);
+        let bond = OptimisticParams::default_params().bond;
+        env.advance_block_time(86_401);
+
+        env.set_caller(proposer);
+        market.with_tokens(u256_to_u512(bond * 2)).propose_outcome(0);
+        env.set_caller(disputer);
+        assert_eq!(
+            market.with_tokens(u256_to_u512(bond)).try_dispute_proposal(),
+            Err(Error::InsufficientBond.into())
+        );
+        let disputer_before = env.balance_of(&disputer);
+        market.with_tokens(u256_to_u512(bond * 3)).dispute_proposal();
+        assert!(matches!(market.get_status(), MarketStatus::Disputed));
+        assert_eq!(disputer_before - env.balance_of(&disputer), u256_to_u512(bond * 2));
+
+        // Disputes wait for the arbitrator, however long it takes
+        env.advance_block_time(OptimisticParams::default_params().dispute_window + 1);
+        assert_eq!(market.try_finalize_proposal(), Err(Error::NoProposal.into()));
+        assert_eq!(
+            market.try_arbitrate_dispute(1, "proof".to_string()),
+            Err(Error::NotResolver.into())
+        );
+
+        env.set_caller(env.get_account(0));
+        market.arbitrate_dispute(1, "proof".to_string());
+        assert_eq!(market.get_winning_outcome(), Some(1));
+        assert_eq!(env.balance_of(&disputer) - disputer_before, u256_to_u512(bond * 2));
+        assert_eq!(env.balance_of(&market), U512::zero());
+        env.set_caller(trader);
+        market.claim_winnings();
+    }
+
+    #[test]
+    fn test_resolver_overrides_a_pending_proposal() {
+        let (mut market, vault, env) = setup_optimistic_market();
+        let (trader, proposer) = (env.get_account(1), env.get_account(2));
+        buy(&mut market, &env, trader, 1, 1);
+        let bond = OptimisticParams::default_params().bond;
+        env.advance_block_time(86_401);
 
-    fn setup_binary_market() -> (MarketHostRef, odra::host::HostEnv) {
+        // A wrong proposal cannot hold up resolution; its bond is forfeited
+        env.set_caller(proposer);
+        let proposer_before = env.balance_of(&proposer);
+        market.with_tokens(u256_to_u512(bond)).propose_outcome(0);
+        env.set_caller(env.get_account(0));
+        market.resolve_market(1, "proof".to_string());
+        assert_eq!(market.get_winning_outcome(), Some(1));
+        assert_eq!(proposer_before - env.balance_of(&proposer), u256_to_u512(bond));
+        assert_eq!(vault.get_market_swept_funds(1), bond);
+        assert_eq!(env.balance_of(&market), U512::zero());
+
+        // A right one gets its bond back
+        let (mut market, _vault, env) = setup_optimistic_market();
+        env.advance_block_time(86_401);
+        env.set_caller(proposer);
+        let proposer_before = env.balance_of(&proposer);
+        market.with_tokens(u256_to_u512(bond)).propose_outcome(0);
+        env.set_caller(env.get_account(0));
+        market.resolve_market(0, "proof".to_string());
+        assert_eq!(env.balance_of(&proposer), proposer_before);
+
+        // Resolving a disputed proposal Invalid pays the disputer both bonds
+        let (mut market, _vault, env) = setup_optimistic_market();
+        let disputer = env.get_account(3);
+        env.advance_block_time(86_401);
+        env.set_caller(proposer);
+        market.with_tokens(u256_to_u512(bond)).propose_outcome(0);
+        env.set_caller(disputer);
+        let disputer_before = env.balance_of(&disputer);
+        market.with_tokens(u256_to_u512(bond)).dispute_proposal();
+        env.set_caller(env.get_account(0));
+        market.resolve_invalid("ambiguous".to_string());
+        assert_eq!(env.balance_of(&disputer) - disputer_before, u256_to_u512(bond));
+        assert!(env.emitted_event(
+            &market,
+            DisputeResolved {
+                market_id: 1,
+                outcome_id: None,
+                winner: disputer,
+                slashed_bond: bond,
+                timestamp: env.block_time(),
+            }
+        ));
+    }
+
+    #[test]
+    fn test_proposals_need_an_optimistic_market_without_other_resolvers() {
+        let bond = u256_to_u512(OptimisticParams::default_params().bond);
+        let (market, _vault, env) = setup_binary_market();
+        env.advance_block_time(86_401);
+        assert_eq!(
+            market.with_tokens(bond).try_propose_outcome(0),
+            Err(Error::OptimisticResolutionDisabled.into())
+        );
+
+        let env = odra_test::env();
+        let members = vec![env.get_account(1), env.get_account(2)];
+        let (market, _vault, env) = deploy_market_in(env, |params| {
+            params.optimistic = Some(OptimisticParams::default_params());
+            params.committee = Some(ResolverCommittee { members, threshold: 2 });
+        });
+        env.advance_block_time(86_401);
+        assert_eq!(
+            market.with_tokens(bond).try_propose_outcome(0),
+            Err(Error::OptimisticResolutionDisabled.into())
+        );
+    }
+
+    /// Deploy a binary market resolved by a 2-of-3 committee of accounts 1-3.
+    fn setup_committee_market() -> (MarketHostRef, VaultHostRef, HostEnv) {
+        let env = odra_test::env();
+        let members = vec![env.get_account(1), env.get_account(2), env.get_account(3)];
+        deploy_market_in(env, |params| {
+            params.resolver = params.creator;
+            params.committee = Some(ResolverCommittee { members, threshold: 2 });
+        })
+    }
+
+    #[test]
+    fn test_committee_resolves_once_threshold_agrees() {
+        let (mut market, _vault, env) = setup_committee_market();
+        let (first, second, third) = (env.get_account(1), env.get_account(2), env.get_account(3));
+
+        env.set_caller(first);
+        assert_eq!(market.try_vote_outcome(0), Err(Error::MarketNotEnded.into()));
+        env.advance_block_time(86_401);
+        env.set_caller(env.get_account(4));
+        assert_eq!(market.try_vote_outcome(0), Err(Error::NotCommitteeMember.into()));
+
+        // Conflicting votes are recorded without resolving
+        env.set_caller(first);
+        market.vote_outcome(0);
+        assert_eq!(market.try_vote_outcome(1), Err(Error::AlreadyVoted.into()));
+        env.set_caller(second);
+        market.vote_outcome(1);
+        assert_eq!(market.get_committee_vote(second), Some(1));
+        assert!(env.emitted_event(
+            &market,
+            ResolutionVoted {
+                market_id: 1,
+                member: second,
+                outcome_id: 1,
+                votes: 1,
+                threshold: 2,
+                timestamp: env.block_time(),
+            }
+        ));
+        assert!(market.get_winning_outcome().is_none());
+
+        env.set_caller(third);
+        market.vote_outcome(0);
+        assert_eq!(market.get_winning_outcome(), Some(0));
+        assert!(matches!(market.get_status(), MarketStatus::Resolved));
+        env.set_caller(second);
+        assert_eq!(market.try_vote_outcome(0), Err(Error::MarketAlreadyResolved.into()));
+    }
+
+    #[test]
+    fn test_committee_rotation_restarts_the_vote() {
+        let (mut market, _vault, env) = setup_committee_market();
+        let (admin, first, second) = (env.get_account(0), env.get_account(1), env.get_account(2));
+        env.advance_block_time(86_401);
+
+        // The single resolver key has no power over a committee market
+        env.set_caller(admin);
+        market.update_resolver(first);
+        env.set_caller(first);
+        assert_eq!(
+            market.try_resolve_market(0, "proof".to_string()),
+            Err(Error::NotResolver.into())
+        );
+        market.vote_outcome(0);
+
+        env.set_caller(admin);
+        let duplicated = ResolverCommittee { members: vec![first, first], threshold: 2 };
+        assert_eq!(
+            market.try_set_resolver_committee(Some(duplicated)),
+            Err(Error::InvalidCommittee.into())
+        );
+        let rotated = ResolverCommittee { members: vec![first, second], threshold: 2 };
+        market.set_resolver_committee(Some(rotated));
+        assert_eq!(market.get_committee_vote(first), None);
+
+        env.set_caller(first);
+        market.vote_outcome(1);
+        env.set_caller(second);
+        market.vote_outcome(1);
+        assert_eq!(market.get_winning_outcome(), Some(1));
+    }
+
+    #[test]
+    fn test_committee_can_vote_a_market_invalid() {
+        let (mut market, _vault, env) = setup_committee_market();
+        let (first, second, third) = (env.get_account(1), env.get_account(2), env.get_account(3));
+        env.advance_block_time(86_401);
+
+        // Invalid votes count apart from outcome votes
+        env.set_caller(first);
+        market.vote_invalid();
+        assert_eq!(market.get_committee_vote(first), Some(INVALID_VOTE));
+        assert_eq!(market.try_vote_outcome(0), Err(Error::AlreadyVoted.into()));
+        env.set_caller(second);
+        market.vote_outcome(0);
+        assert!(market.get_settlement().is_none());
+
+        env.set_caller(third);
+        market.vote_invalid();
+        assert!(env.emitted_event(
+            &market,
+            ResolutionVoted {
+                market_id: 1,
+                member: third,
+                outcome_id: INVALID_VOTE,
+                votes: 2,
+                threshold: 2,
+                timestamp: env.block_time(),
+            }
+        ));
+        assert!(env.emitted_event(
+            &market,
+            MarketResolvedInvalid {
+                market_id: 1,
+                resolver: third,
+                timestamp: env.block_time(),
+                proof: "Committee vote".to_string(),
+            }
+        ));
+        assert!(market.get_settlement().unwrap().invalid);
+        assert_eq!(market.get_winning_outcome(), None);
+    }
+
+    #[test]
+    fn test_invalid_resolution_pays_every_outcome_pro_rata() {
+        let (mut market, vault, env) = setup_binary_market();
+        let (yes, no) = (env.get_account(1), env.get_account(2));
+        buy(&mut market, &env, yes, 0, 1);
+        buy(&mut market, &env, no, 1, 3);
+        let yes_shares = market.get_user_position(yes, 0).shares;
+        let no_shares = market.get_user_position(no, 1).shares;
+
+        env.advance_block_time(86_401);
+        env.set_caller(env.get_account(3));
+        assert_eq!(market.try_resolve_invalid("ambiguous".to_string()), Err(Error::NotResolver.into()));
+        env.set_caller(env.get_account(0));
+        market.resolve_invalid("ambiguous".to_string());
+        assert!(env.emitted_event(
+            &market,
+            MarketResolvedInvalid {
+                market_id: 1,
+                resolver: env.get_account(0),
+                timestamp: env.block_time(),
+                proof: "ambiguous".to_string(),
+            }
+        ));
+        assert_eq!(market.get_winning_outcome(), None);
+        let settlement = market.get_settlement().unwrap();
+        assert!(settlement.invalid);
+        assert_eq!(settlement.total_winning_shares, yes_shares + no_shares);
+        assert_eq!(market.try_resolve_market(0, "late".to_string()), Err(Error::MarketAlreadyResolved.into()));
+        assert_eq!(market.try_resolve_invalid("again".to_string()), Err(Error::MarketAlreadyResolved.into()));
+
+        // Both sides are paid by shares held, through either claim
+        let before = env.balance_of(&yes);
+        env.set_caller(yes);
+        market.claim_winnings();
+        let yes_payout = u512_to_u256(env.balance_of(&yes) - before);
+        assert_eq!(yes_payout, settlement.payout_for(yes_shares));
+        assert_eq!(market.try_claim_refund(), Err(Error::AlreadyClaimed.into()));
+
+        let before = env.balance_of(&no);
+        env.set_caller(no);
+        market.claim_refund();
+        assert_eq!(u512_to_u256(env.balance_of(&no) - before), settlement.payout_for(no_shares));
+        env.set_caller(env.get_account(4));
+        assert_eq!(market.try_claim_refund(), Err(Error::NoPositionToRefund.into()));
+        assert_eq!(vault.get_market_balance(1), U256::zero());
+    }
+
+    #[test]
+    fn test_invalid_complete_set_amm_pays_each_share_its_part_of_a_set() {
+        let (mut market, _vault, env) = deploy_market(|params| {
+            params.pricing = PricingMode::CompleteSetAmm;
+            params.initial_liquidity = U256::from(10 * ONE_CSPR);
+            params.fees = FeeSchedule::flat(0);
+        });
+        let holder = env.get_account(1);
+        env.set_caller(holder);
+        market.with_tokens(U512::from(4 * ONE_CSPR)).mint_complete_sets();
+        buy(&mut market, &env, holder, 0, 2);
+        let shares = market.get_user_position(holder, 0).shares + market.get_user_position(holder, 1).shares;
+
+        env.set_caller(env.get_account(0));
+        market.close_market();
+        market.resolve_invalid("ambiguous".to_string());
+
+        // Every share pays half a mote, so the complete sets return their cost
+        let before = env.balance_of(&holder);
+        env.set_caller(holder);
+        market.claim_winnings();
+        assert_eq!(u512_to_u256(env.balance_of(&holder) - before), shares / 2);
+        assert!(shares / 2 > U256::from(4 * ONE_CSPR));
+    }
+
+    #[test]
+    fn test_scalar_market_splits_payout_between_long_and_short() {
+        let (mut market, vault, env) = deploy_market(|params| {
+            params.market_type = MarketType::Scalar;
+            params.outcome_names = vec!["Long".to_string(), "Short".to_string()];
+            params.scalar = Some(ScalarRange { lower_bound: 100, upper_bound: 1_000 });
+        });
+        let (long, other_long, short) = (env.get_account(1), env.get_account(2), env.get_account(3));
+        buy(&mut market, &env, long, SCALAR_LONG, 1);
+        buy(&mut market, &env, other_long, SCALAR_LONG, 2);
+        buy(&mut market, &env, short, SCALAR_SHORT, 6);
+        let long_supply = market.get_outcome_shares(SCALAR_LONG);
+        let short_supply = market.get_outcome_shares(SCALAR_SHORT);
+        assert_ne!(long_supply, short_supply);
+
+        env.advance_block_time(86_401);
+        env.set_caller(env.get_account(0));
+        assert_eq!(
+            market.try_resolve_market(SCALAR_LONG, "proof".to_string()),
+            Err(Error::ScalarValueRequired.into())
+        );
+        assert_eq!(market.try_propose_outcome(SCALAR_LONG), Err(Error::ScalarValueRequired.into()));
+        market.resolve_scalar_market(730, "proof".to_string());
+        assert!(env.emitted_event(
+            &market,
+            ScalarMarketResolved {
+                market_id: 1,
+                value: 730,
+                long_payout_bps: 7_000,
+                resolver: env.get_account(0),
+                timestamp: env.block_time(),
+                proof: "proof".to_string(),
+            }
+        ));
+        assert_eq!(market.get_winning_outcome(), None);
+
+        // LONG holders share 70% of the pool and SHORT holders 30%, however
+        // many shares each side holds
+        let settlement = market.get_settlement().unwrap();
+        assert_eq!(settlement.scalar_value, Some(730));
+        let pool = settlement.distributable_pool + settlement.dust;
+        assert_eq!(settlement.outcome_pools, vec![pool * 7_000u64 / 10_000u64, pool * 3_000u64 / 10_000u64]);
+
+        let mut long_paid = U256::zero();
+        for (holder, outcome_id) in [(long, SCALAR_LONG), (other_long, SCALAR_LONG), (short, SCALAR_SHORT)] {
+            let shares = market.get_user_position(holder, outcome_id).shares;
+            let supply = if outcome_id == SCALAR_LONG { long_supply } else { short_supply };
+            let before = env.balance_of(&holder);
+            env.set_caller(holder);
+            market.claim_winnings();
+            let payout = u512_to_u256(env.balance_of(&holder) - before);
+            assert_eq!(payout, settlement.outcome_pools[outcome_id as usize] * shares / supply);
+            if outcome_id == SCALAR_LONG {
+                long_paid += payout;
+            }
+        }
+        assert!(settlement.outcome_pools[SCALAR_LONG as usize] - long_paid < U256::from(2u64));
+        assert_eq!(vault.get_market_balance(1), U256::zero());
+    }
+
+    #[test]
+    fn test_scalar_market_requires_a_range() {
         let env = odra_test::env();
         let admin = env.get_account(0);
-        
-        let init_args = MarketInitArgs {
-            market_id: 1,
-            market_type: MarketType::Binary,
-            question: "Will BTC reach $100k?".to_string(),
-            outcome_names: vec!["Yes".to_string(), "No".to_string()],
-            end_time: env.get_block_time() + 86400, // 1 day from now
-            resolution_source: "Price oracle".to_string(),
-            category: "crypto".to_string(),
-            creator: admin,
-            admin,
-            resolver: admin,
-            platform_fee_bps: 200, // 2%
-            vault_contract: admin, // Placeholder
-            factory_contract: admin, // Placeholder
-            bonding_params: BondingCurveParams::default_params(),
-        };
+        let vault = Vault::deploy(&env, VaultInitArgs { admin, fee_recipient: admin });
+        let mut args = binary_params(&env, vault.address());
+        args.market_type = MarketType::Scalar;
+        assert!(Market::try_deploy(&env, MarketInitArgs { args: args.clone() }).is_err());
+        args.scalar = Some(ScalarRange { lower_bound: 1, upper_bound: 1 });
+        assert!(Market::try_deploy(&env, MarketInitArgs { args: args.clone() }).is_err());
+        args.market_type = MarketType::Binary;
+        args.scalar = Some(ScalarRange { lower_bound: 0, upper_bound: 1 });
+        assert!(Market::try_deploy(&env, MarketInitArgs { args }).is_err());
 
-        let market = Market::deploy(&env, init_args);
-        (market, env)
+        let (mut market, _vault, env) = setup_binary_market();
+        env.advance_block_time(86_401);
+        env.set_caller(env.get_account(0));
+        assert_eq!(
+            market.try_resolve_scalar_market(5, "proof".to_string()),
+            Err(Error::NotScalarMarket.into())
+        );
     }
 
     #[test]
-    fn test_market_init() {
-        let (market, _env) = setup_binary_market();
-        
-        let info = market.get_market_info();
-        assert_eq!(info.market_id, 1);
-        assert_eq!(info.question, "Will BTC reach $100k?");
-        assert!(matches!(info.status, MarketStatus::Active));
-        assert_eq!(market.get_outcome_count(), 2);
+    fn test_claims_are_paid_from_vault() {
+        let (mut market, vault, env) = setup_binary_market();
+        let winner = env.get_account(1);
+        let loser = env.get_account(2);
+        buy(&mut market, &env, winner, 0, 1);
+        buy(&mut market, &env, loser, 1, 1);
+
+        env.set_caller(env.get_account(0));
+        market.close_market();
+        market.resolve_market(0, "oracle".to_string());
+
+        let vault_before = vault.get_market_balance(1);
+        let balance_before = env.balance_of(&winner);
+        env.set_caller(winner);
+        market.claim_winnings();
+
+        let payout = env.balance_of(&winner) - balance_before;
+        assert!(!payout.is_zero());
+        assert_eq!(vault.get_market_balance(1), vault_before - u512_to_u256(payout));
     }
 
     #[test]
-    fn test_get_outcome_names() {
-        let (market, _env) = setup_binary_market();
-        
-        assert_eq!(market.get_outcome_name(0), Some("Yes".to_string()));
-        assert_eq!(market.get_outcome_name(1), Some("No".to_string()));
-        assert_eq!(market.get_outcome_name(2), None);
+    fn test_claims_pay_exactly_the_settlement_snapshot() {
+        let (mut market, vault, env) = setup_seeded_market(U256::from(3 * ONE_CSPR));
+        let winners = [env.get_account(1), env.get_account(2), env.get_account(3)];
+        for (i, winner) in winners.iter().enumerate() {
+            buy(&mut market, &env, *winner, 0, 1 + i as u64);
+        }
+        buy(&mut market, &env, env.get_account(4), 1, 7);
+        assert!(market.get_settlement().is_none());
+
+        env.set_caller(env.get_account(0));
+        market.close_market();
+        market.resolve_market(0, "oracle".to_string());
+
+        let snapshot = market.get_settlement().unwrap();
+        assert_eq!(snapshot.total_winning_shares, market.get_outcome_shares(0));
+        assert_eq!(snapshot.total_paid_out, U256::zero());
+
+        let mut paid = U256::zero();
+        for winner in winners {
+            let balance_before = env.balance_of(&winner);
+            env.set_caller(winner);
+            market.claim_winnings();
+            paid += u512_to_u256(env.balance_of(&winner) - balance_before);
+            // Later trades cannot move the frozen terms
+            assert_eq!(market.get_settlement().unwrap().distributable_pool, snapshot.distributable_pool);
+        }
+
+        let settlement = market.get_settlement().unwrap();
+        assert_eq!(settlement.total_paid_out, paid);
+        assert_eq!(settlement.claimed_shares, snapshot.total_winning_shares);
+        assert_eq!(paid + settlement.dust, snapshot.distributable_pool);
+        // The settlement fee is booked as fees and the dust swept; nothing is left behind
+        assert_eq!(vault.get_market_fees(1), snapshot.fee_amount);
+        assert_eq!(vault.get_market_swept_funds(1), settlement.dust);
+        assert_eq!(vault.get_market_balance(1), U256::zero());
     }
 
     #[test]
-    fn test_initial_price() {
-        let (market, _env) = setup_binary_market();
-        
-        // Initial price should be the base price
-        let price = market.get_current_price(0);
-        assert_eq!(price, U256::from(10_000_000u64)); // 0.01 CSPR
+    fn test_pool_without_winners_is_swept_at_resolution() {
+        let (mut market, vault, env) = setup_binary_market();
+        buy(&mut market, &env, env.get_account(1), 1, 1);
+        let pool = market.get_market_info().total_liquidity;
+
+        env.set_caller(env.get_account(0));
+        market.close_market();
+        market.resolve_market(0, "oracle".to_string());
+
+        let settlement = market.get_settlement().unwrap();
+        assert_eq!(settlement.dust, pool - settlement.fee_amount);
+        assert_eq!(vault.get_market_fees(1), settlement.fee_amount);
+        assert_eq!(vault.get_market_swept_funds(1), settlement.dust);
+        assert_eq!(vault.get_platform_fees(), pool);
+        assert_eq!(vault.get_market_balance(1), U256::zero());
+    }
+
+    #[test]
+    fn test_refund_is_paid_from_vault() {
+        let (mut market, vault, env) = setup_binary_market();
+        let trader = env.get_account(1);
+        buy(&mut market, &env, trader, 0, 1);
+        let cost = market.get_user_position(trader, 0).total_cost;
+
+        env.set_caller(env.get_account(0));
+        market.cancel_market("ambiguous".to_string());
+
+        let balance_before = env.balance_of(&trader);
+        env.set_caller(trader);
+        market.claim_refund();
+
+        assert_eq!(env.balance_of(&trader) - balance_before, u256_to_u512(cost));
+        assert_eq!(vault.get_market_balance(1), U256::zero());
+
+        // A cancelled market cannot be settled afterwards
+        env.advance_block_time(86_401);
+        env.set_caller(env.get_account(0));
+        assert_eq!(market.try_resolve_invalid("late".to_string()), Err(Error::MarketCancelled.into()));
+        assert_eq!(market.try_resolve_market(0, "late".to_string()), Err(Error::MarketCancelled.into()));
+    }
+
+    #[test]
+    fn test_seed_liquidity_is_paid_to_winners() {
+        let seed = U256::from(5 * ONE_CSPR);
+        let (mut market, vault, env) = setup_seeded_market(seed);
+        let winner = env.get_account(1);
+        buy(&mut market, &env, winner, 0, 1);
+        let cost = market.get_user_position(winner, 0).total_cost;
+
+        env.set_caller(env.get_account(0));
+        market.close_market();
+        market.resolve_market(0, "oracle".to_string());
+
+        let balance_before = env.balance_of(&winner);
+        env.set_caller(winner);
+        market.claim_winnings();
+
+        // Sole winner receives their stake minus the fee, plus the whole seed
+        let fee = cost * U256::from(200u64) / U256::from(10_000u64);
+        let payout = u512_to_u256(env.balance_of(&winner) - balance_before);
+        assert_eq!(payout, cost - fee + seed);
+        assert_eq!(vault.get_market_fees(1), fee);
+        assert_eq!(vault.get_market_balance(1), U256::zero());
+
+        env.set_caller(env.get_account(0));
+        assert_eq!(market.try_withdraw_seed_liquidity(), Err(Error::NothingToClaim.into()));
+    }
+
+    #[test]
+    fn test_creator_withdraws_unused_seed() {
+        let seed = U256::from(5 * ONE_CSPR);
+        let (mut market, _vault, env) = setup_seeded_market(seed);
+        let creator = env.get_account(0);
+        buy(&mut market, &env, env.get_account(1), 1, 1);
+
+        env.set_caller(creator);
+        assert_eq!(market.try_withdraw_seed_liquidity(), Err(Error::MarketNotResolved.into()));
+        market.close_market();
+        market.resolve_market(0, "oracle".to_string());
+
+        // Only the creator may withdraw
+        env.set_caller(env.get_account(1));
+        assert_eq!(market.try_withdraw_seed_liquidity(), Err(Error::NotCreator.into()));
+
+        // Nobody holds the winning outcome, so the seed goes back to the creator
+        let balance_before = env.balance_of(&creator);
+        env.set_caller(creator);
+        market.withdraw_seed_liquidity();
+        assert_eq!(u512_to_u256(env.balance_of(&creator) - balance_before), seed);
+        assert!(market.get_seed_liquidity().withdrawn);
+        assert_eq!(market.try_withdraw_seed_liquidity(), Err(Error::AlreadyClaimed.into()));
+    }
+
+    #[test]
+    fn test_compound_market_pays_combination_hitting_every_leg() {
+        let (mut market, _vault, env) = setup_compound_market();
+        assert_eq!(market.get_outcome_count(), 4);
+        assert_eq!(market.get_outcome_name(1), Some("Yes & No".to_string()));
+
+        let yes_no = market.get_compound_outcome_id(vec![0, 1]).unwrap();
+        let yes_yes = market.get_compound_outcome_id(vec![0, 0]).unwrap();
+        let parlay = env.get_account(1);
+        let half_right = env.get_account(2);
+        buy(&mut market, &env, parlay, yes_no, 1);
+        buy(&mut market, &env, half_right, yes_yes, 1);
+
+        env.set_caller(env.get_account(0));
+        market.close_market();
+        assert_eq!(
+            market.try_resolve_compound_market(vec![0], "oracle".to_string()),
+            Err(Error::InvalidOutcome.into())
+        );
+        market.resolve_compound_market(vec![0, 1], "oracle".to_string());
+        assert_eq!(market.get_winning_outcome(), Some(yes_no));
+
+        env.set_caller(half_right);
+        assert_eq!(market.try_claim_winnings(), Err(Error::NoWinningPosition.into()));
+        env.set_caller(parlay);
+        market.claim_winnings();
+    }
+
+    #[test]
+    fn test_resolve_compound_rejects_other_market_types() {
+        let (mut market, _vault, _env) = setup_binary_market();
+        market.close_market();
+        assert_eq!(
+            market.try_resolve_compound_market(vec![0, 0], "oracle".to_string()),
+            Err(Error::NotCompoundMarket.into())
+        );
     }
 }
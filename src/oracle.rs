@@ -0,0 +1,52 @@
+//! Oracle interface for resolving markets.
+//!
+//! A market bound to an oracle at creation (see
+//! [`OracleBinding`](crate::types::OracleBinding)) can be resolved by anyone
+//! once it has ended: `Market::resolve_from_oracle` reads the oracle's answer
+//! through [`OracleAdapter`], checks that it was updated after the market
+//! ended and within the binding's `max_age`, and settles on it. An invalid
+//! answer resolves the market as Invalid, every outcome paying its holders
+//! an equal part of the pool.
+//!
+//! Any contract exposing `latest_answer` can act as the oracle, typically an
+//! adapter in front of a price feed or a reporting protocol.
+
+use odra::prelude::*;
+
+use crate::types::OracleAnswer;
+
+/// External interface of an oracle contract.
+#[odra::external_contract]
+pub trait OracleAdapter {
+    /// Latest answer to a query, or `None` if the oracle has none yet.
+    fn latest_answer(&self, query_id: u64) -> Option<OracleAnswer>;
+}
+
+/// An oracle whose answers are set by hand, for tests.
+#[cfg(test)]
+pub mod mock {
+    use odra::prelude::*;
+
+    use crate::types::OracleAnswer;
+
+    /// Oracle answering whatever was last reported for a query.
+    #[odra::module]
+    pub struct MockOracle {
+        /// Answers by query ID
+        answers: Mapping<u64, OracleAnswer>,
+    }
+
+    #[odra::module]
+    impl MockOracle {
+        /// Report an answer to a query, updated now.
+        pub fn report(&mut self, query_id: u64, outcome_id: u64, invalid: bool) {
+            let updated_at = self.env().get_block_time();
+            self.answers.set(&query_id, OracleAnswer { outcome_id, invalid, updated_at });
+        }
+
+        /// Latest answer to a query.
+        pub fn latest_answer(&self, query_id: u64) -> Option<OracleAnswer> {
+            self.answers.get(&query_id)
+        }
+    }
+}
@@ -0,0 +1,372 @@
+//! Outcome Token Contract for Casper Predict.
+//!
+//! An Outcome Token exposes the shares of one outcome of a Market through
+//! the CEP-18 fungible token interface, so wallets, explorers and other
+//! protocols can hold and move them like any other token on Casper.
+//!
+//! The token keeps no balances of its own. Balances, supply and allowances
+//! are read from the market's positions, and transfers and approvals are
+//! forwarded to the market, which only accepts them from the token it has
+//! registered for the outcome. Shares moved through the token or directly
+//! through the market are the same shares, and the market reports the
+//! shares it issues, burns and moves itself so the token emits a CEP-18
+//! event for every balance change.
+
+use odra::prelude::*;
+use odra::casper_types::U256;
+use odra::ContractRef;
+
+use crate::errors::Error;
+use crate::events::{Burn, Mint, SetAllowance, Transfer, TransferFrom};
+use crate::market::MarketContractRef;
+
+/// A CEP-18 token over the shares of one market outcome.
+#[odra::module(
+    errors = Error,
+    events = [Transfer, TransferFrom, SetAllowance, Mint, Burn]
+)]
+pub struct OutcomeToken {
+    /// Market contract holding the shares
+    market: Var<Address>,
+    /// Outcome of the market this token represents
+    outcome_id: Var<u64>,
+    /// Token name
+    name: Var<String>,
+    /// Token symbol
+    symbol: Var<String>,
+    /// Decimals of a share, as reported by the market
+    decimals: Var<u8>,
+}
+
+#[odra::module]
+impl OutcomeToken {
+    /// Initialize the token for an outcome of `market`.
+    ///
+    /// The token only becomes transferable once the market admin registers
+    /// it with `set_outcome_token`.
+    pub fn init(&mut self, market: Address, outcome_id: u64, name: String, symbol: String) {
+        let market_ref = MarketContractRef::new(self.env(), market);
+        if market_ref.get_outcome_name(outcome_id).is_none() {
+            self.env().revert(Error::InvalidOutcome);
+        }
+
+        self.decimals.set(market_ref.get_share_decimals());
+        self.market.set(market);
+        self.outcome_id.set(outcome_id);
+        self.name.set(name);
+        self.symbol.set(symbol);
+    }
+
+    // =========================================================================
+    // CEP-18 Interface
+    // =========================================================================
+
+    /// Get the token name.
+    pub fn name(&self) -> String {
+        self.name.get_or_default()
+    }
+
+    /// Get the token symbol.
+    pub fn symbol(&self) -> String {
+        self.symbol.get_or_default()
+    }
+
+    /// Get the number of decimals of the token.
+    pub fn decimals(&self) -> u8 {
+        self.decimals.get_or_default()
+    }
+
+    /// Get the outstanding shares of the outcome.
+    pub fn total_supply(&self) -> U256 {
+        self.market_ref().get_outcome_shares(self.outcome_id.get_or_default())
+    }
+
+    /// Get the shares of the outcome held by `address`.
+    pub fn balance_of(&self, address: Address) -> U256 {
+        self.market_ref()
+            .get_user_position(address, self.outcome_id.get_or_default())
+            .shares
+    }
+
+    /// Get the shares `spender` may transfer on behalf of `owner`.
+    pub fn allowance(&self, owner: Address, spender: Address) -> U256 {
+        self.market_ref()
+            .get_share_allowance(owner, spender, self.outcome_id.get_or_default())
+    }
+
+    /// Allow `spender` to transfer up to `amount` of the caller's shares.
+    pub fn approve(&mut self, spender: Address, amount: U256) {
+        let owner = self.env().caller();
+        self.market_ref()
+            .token_approve(owner, spender, self.outcome_id.get_or_default(), amount);
+
+        self.env().emit_event(SetAllowance {
+            owner,
+            spender,
+            allowance: amount,
+        });
+    }
+
+    /// Transfer `amount` of the caller's shares to `recipient`.
+    pub fn transfer(&mut self, recipient: Address, amount: U256) {
+        let sender = self.env().caller();
+        self.market_ref()
+            .token_transfer(sender, recipient, self.outcome_id.get_or_default(), amount);
+
+        self.env().emit_event(Transfer {
+            sender,
+            recipient,
+            amount,
+        });
+    }
+
+    /// Transfer `amount` of `owner`'s shares to `recipient` out of the
+    /// allowance `owner` granted the caller.
+    pub fn transfer_from(&mut self, owner: Address, recipient: Address, amount: U256) {
+        let spender = self.env().caller();
+        self.market_ref().token_transfer_from(
+            spender,
+            owner,
+            recipient,
+            self.outcome_id.get_or_default(),
+            amount,
+        );
+
+        self.env().emit_event(TransferFrom {
+            spender,
+            owner,
+            recipient,
+            amount,
+        });
+    }
+
+    // =========================================================================
+    // Market Notifications
+    // =========================================================================
+
+    /// Report shares of the outcome the market issued to `recipient`.
+    ///
+    /// Callable by the market only, like the other `record_*` entry points.
+    pub fn record_mint(&mut self, recipient: Address, amount: U256) {
+        self.require_market();
+        self.env().emit_event(Mint { recipient, amount });
+    }
+
+    /// Report shares of the outcome sold back to the market or claimed.
+    pub fn record_burn(&mut self, owner: Address, amount: U256) {
+        self.require_market();
+        self.env().emit_event(Burn { owner, amount });
+    }
+
+    /// Report shares of the outcome moved through the market rather than
+    /// this token.
+    pub fn record_transfer(&mut self, sender: Address, recipient: Address, amount: U256) {
+        self.require_market();
+        self.env().emit_event(Transfer {
+            sender,
+            recipient,
+            amount,
+        });
+    }
+
+    // =========================================================================
+    // View Functions
+    // =========================================================================
+
+    /// Get the market contract holding the shares.
+    pub fn get_market(&self) -> Option<Address> {
+        self.market.get()
+    }
+
+    /// Get the outcome this token represents.
+    pub fn get_outcome_id(&self) -> u64 {
+        self.outcome_id.get_or_default()
+    }
+}
+
+impl OutcomeToken {
+    /// Reference to the market contract holding the shares.
+    fn market_ref(&self) -> MarketContractRef {
+        MarketContractRef::new(self.env(), self.market.get().unwrap_or_revert(&self.env()))
+    }
+
+    /// Ensure the caller is the market holding the shares.
+    fn require_market(&self) {
+        if self.market.get() != Some(self.env().caller()) {
+            self.env().revert(Error::NotAuthorizedMarket);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market::{Market, MarketHostRef, MarketInitArgs, MarketInitParams};
+    use crate::types::{BondingCurveParams, FeeSchedule, MarketType, PricingMode};
+    use crate::vault::{Vault, VaultInitArgs};
+    use odra::casper_types::U512;
+    use odra::host::{Deployer, HostEnv, HostRef};
+
+    /// Deploy a binary market with a token over its first outcome, where
+    /// account 1 holds shares bought for one CSPR.
+    fn setup() -> (OutcomeTokenHostRef, MarketHostRef, HostEnv) {
+        let env = odra_test::env();
+        let admin = env.get_account(0);
+        let mut vault = Vault::deploy(&env, VaultInitArgs { admin, fee_recipient: admin });
+        let args = MarketInitParams {
+            market_id: 1,
+            market_type: MarketType::Binary,
+            question: "Will BTC reach $100k?".to_string(),
+            outcome_names: vec!["Yes".to_string(), "No".to_string()],
+            end_time: env.block_time() + 86400,
+            resolution_source: "Price oracle".to_string(),
+            category: "crypto".to_string(),
+            creator: admin,
+            admin,
+            resolver: admin,
+            fees: FeeSchedule::flat(200),
+            vault_contract: vault.address(),
+            factory_contract: admin,
+            bonding_params: BondingCurveParams::default_params(),
+            pricing: PricingMode::BondingCurve,
+            initial_liquidity: U256::zero(),
+            legs: Vec::new(),
+            scalar: None,
+            oracle: None,
+            optimistic: None,
+            committee: None,
+        };
+        let market = Market::deploy(&env, MarketInitArgs { args });
+        vault.authorize_market(market.address(), 1);
+
+        let token = OutcomeToken::deploy(
+            &env,
+            OutcomeTokenInitArgs {
+                market: market.address(),
+                outcome_id: 0,
+                name: "BTC $100k Yes".to_string(),
+                symbol: "BTCYES".to_string(),
+            },
+        );
+
+        env.set_caller(env.get_account(1));
+        market.with_tokens(U512::from(1_000_000_000u64)).buy_shares(0, U256::one());
+        env.set_caller(admin);
+        (token, market, env)
+    }
+
+    #[test]
+    fn test_token_reads_market_positions() {
+        let (token, market, env) = setup();
+        let holder = env.get_account(1);
+        let shares = market.get_user_position(holder, 0).shares;
+
+        assert_eq!(token.symbol(), "BTCYES");
+        assert_eq!(token.decimals(), 0);
+        assert_eq!(token.balance_of(holder), shares);
+        assert_eq!(token.total_supply(), market.get_outcome_shares(0));
+        assert_eq!(token.balance_of(env.get_account(2)), U256::zero());
+    }
+
+    #[test]
+    fn test_token_moves_shares_once_registered() {
+        let (mut token, mut market, env) = setup();
+        let (holder, spender, recipient) = (env.get_account(1), env.get_account(2), env.get_account(3));
+        let shares = token.balance_of(holder);
+
+        // Unregistered tokens cannot move shares
+        env.set_caller(holder);
+        assert_eq!(token.try_transfer(recipient, U256::one()), Err(Error::NotOutcomeToken.into()));
+        env.set_caller(holder);
+        assert_eq!(
+            market.try_set_outcome_token(0, token.address()),
+            Err(Error::NotAdmin.into())
+        );
+
+        env.set_caller(env.get_account(0));
+        market.set_outcome_token(0, token.address());
+        assert_eq!(market.get_outcome_token(0), Some(token.address()));
+
+        env.set_caller(holder);
+        token.transfer(recipient, U256::from(5u64));
+        assert_eq!(market.get_user_position(recipient, 0).shares, U256::from(5u64));
+        assert!(env.emitted_event(&token, Transfer { sender: holder, recipient, amount: U256::from(5u64) }));
+
+        // Allowances are shared with the market's own approve_shares
+        token.approve(spender, U256::from(3u64));
+        assert_eq!(market.get_share_allowance(holder, spender, 0), U256::from(3u64));
+        env.set_caller(spender);
+        token.transfer_from(holder, recipient, U256::from(3u64));
+        assert_eq!(token.balance_of(holder), shares - 8);
+        assert_eq!(token.allowance(holder, spender), U256::zero());
+        assert_eq!(
+            token.try_transfer_from(holder, recipient, U256::one()),
+            Err(Error::InsufficientAllowance.into())
+        );
+
+        // Only the registered token can act for users
+        assert_eq!(
+            market.try_token_transfer(holder, spender, 0, U256::one()),
+            Err(Error::NotOutcomeToken.into())
+        );
+    }
+
+    #[test]
+    fn test_only_the_outcomes_own_token_registers_once() {
+        let (token, mut market, env) = setup();
+        let other_outcome = OutcomeToken::deploy(
+            &env,
+            OutcomeTokenInitArgs {
+                market: market.address(),
+                outcome_id: 1,
+                name: "BTC $100k No".to_string(),
+                symbol: "BTCNO".to_string(),
+            },
+        );
+        assert_eq!(
+            market.try_set_outcome_token(0, other_outcome.address()),
+            Err(Error::OutcomeTokenMismatch.into())
+        );
+
+        market.set_outcome_token(0, token.address());
+        market.set_outcome_token(1, other_outcome.address());
+        assert_eq!(
+            market.try_set_outcome_token(0, token.address()),
+            Err(Error::OutcomeTokenAlreadySet.into())
+        );
+    }
+
+    #[test]
+    fn test_token_reports_every_balance_change() {
+        let (mut token, mut market, env) = setup();
+        let (holder, recipient) = (env.get_account(1), env.get_account(2));
+        market.set_outcome_token(0, token.address());
+        assert_eq!(token.try_record_mint(holder, U256::one()), Err(Error::NotAuthorizedMarket.into()));
+
+        // Buying mints, selling burns
+        env.set_caller(holder);
+        let before = token.balance_of(holder);
+        market.with_tokens(U512::from(1_000_000_000u64)).buy_shares(0, U256::one());
+        let bought = token.balance_of(holder) - before;
+        assert!(env.emitted_event(&token, Mint { recipient: holder, amount: bought }));
+        market.sell_shares(0, U256::from(2u64), U256::zero());
+        assert!(env.emitted_event(&token, Burn { owner: holder, amount: U256::from(2u64) }));
+
+        // Transfers through the market are reported by the token too
+        market.transfer_shares(recipient, 0, U256::from(3u64));
+        assert!(env.emitted_event(&token, Transfer { sender: holder, recipient, amount: U256::from(3u64) }));
+
+        // Claiming burns the claimed shares
+        env.advance_block_time(86_401);
+        env.set_caller(env.get_account(0));
+        market.resolve_market(0, "BTC hit $100k".to_string());
+        let shares = token.balance_of(holder);
+        env.set_caller(holder);
+        market.claim_winnings();
+        assert!(env.emitted_event(&token, Burn { owner: holder, amount: shares }));
+        assert_eq!(token.balance_of(holder), U256::zero());
+        assert_eq!(token.total_supply(), U256::from(3u64));
+        env.set_caller(holder);
+        assert_eq!(token.try_transfer(recipient, U256::one()), Err(Error::AlreadyClaimed.into()));
+    }
+}
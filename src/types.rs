@@ -7,6 +7,16 @@ use odra::prelude::*;
 use odra::casper_types::U256;
 use odra::prelude::Address;
 
+use crate::curves::{
+    BondingCurve, ExponentialCurve, LinearCurve, SigmoidCurve, SqrtCurve, GROWTH_RATE_SCALE,
+};
+
+/// Maximum for any single fee of a `FeeSchedule` (10% = 1000 basis points)
+pub const MAX_FEE_BPS: u64 = 1000;
+
+/// Basis points in 100%
+const BPS_DENOMINATOR: u64 = 10_000;
+
 /// The type of prediction market.
 #[odra::odra_type]
 #[derive(Default)]
@@ -16,8 +26,12 @@ pub enum MarketType {
     Binary,
     /// Multiple choice market with 3+ outcomes
     MultipleChoice,
-    /// Compound market with multiple related sub-predictions
+    /// Compound (parlay) market over several related sub-questions.
+    /// Each outcome is one combination of leg results.
     Compound,
+    /// Scalar (range) market with LONG and SHORT outcomes, resolved by a
+    /// numeric value and paying both sides linearly across a range
+    Scalar,
 }
 
 /// The current state of a prediction market.
@@ -33,6 +47,10 @@ pub enum MarketStatus {
     Resolved,
     /// Market has been cancelled, refunds available
     Cancelled,
+    /// An outcome has been proposed and can be disputed until the window closes
+    Proposed,
+    /// A proposed outcome has been disputed, awaiting arbitration
+    Disputed,
 }
 
 /// Represents a single outcome option in a prediction market.
@@ -69,6 +87,8 @@ pub struct UserPosition {
     pub total_cost: U256,
     /// Whether winnings have been claimed (for resolved markets)
     pub claimed: bool,
+    /// Shares reserved for open sell orders, still owned and claimable
+    pub locked_shares: U256,
 }
 
 impl UserPosition {
@@ -79,9 +99,15 @@ impl UserPosition {
             shares: U256::zero(),
             total_cost: U256::zero(),
             claimed: false,
+            locked_shares: U256::zero(),
         }
     }
 
+    /// Shares that can be sold or moved, i.e. not reserved for sell orders.
+    pub fn available_shares(&self) -> U256 {
+        self.shares.saturating_sub(self.locked_shares)
+    }
+
     /// Adds shares to this position.
     pub fn add_shares(&mut self, shares: U256, cost: U256) {
         self.shares = self.shares.saturating_add(shares);
@@ -106,6 +132,456 @@ impl UserPosition {
     }
 }
 
+/// Initial liquidity seeded into a market by its creator.
+///
+/// In a bonding curve market the seed is added to the winners' pool. If
+/// nobody holds the winning outcome, or the market is cancelled, the creator
+/// can withdraw it. In an LMSR market the seed is the market maker's subsidy,
+/// and the creator withdraws whatever is left once winners are covered. In a
+/// complete-set AMM market the seed mints the pool's first complete sets, and
+/// the creator withdraws the pool's winning shares.
+#[odra::odra_type]
+#[derive(Default)]
+pub struct SeedLiquidity {
+    /// Amount deposited into the vault at creation, in motes
+    pub amount: U256,
+    /// Whether the creator has withdrawn the seed
+    pub withdrawn: bool,
+}
+
+/// Fixed-point scale of `LiquidityPool::fees_per_share` (1e18)
+pub const LP_FEE_SCALE: u64 = 1_000_000_000_000_000_000;
+
+/// Pool of a complete-set AMM market and its liquidity provider accounting.
+///
+/// LP fees accrue to every LP share through `fees_per_share`, so a provider
+/// is owed `shares * fees_per_share` minus what was accounted for when their
+/// stake last changed.
+#[odra::odra_type]
+#[derive(Default)]
+pub struct LiquidityPool {
+    /// Pool balance of every outcome's shares, indexed by outcome ID
+    pub balances: Vec<U256>,
+    /// LP shares outstanding
+    pub total_shares: U256,
+    /// Cumulative LP fees per LP share, scaled by `LP_FEE_SCALE`
+    pub fees_per_share: U256,
+    /// LP fees accrued but not yet paid out, in motes
+    pub unpaid_fees: U256,
+}
+
+impl LiquidityPool {
+    /// Credits `fee` to the current LP shares.
+    pub fn accrue_fees(&mut self, fee: U256) {
+        if self.total_shares.is_zero() {
+            return;
+        }
+        let per_share = fee.saturating_mul(U256::from(LP_FEE_SCALE)) / self.total_shares;
+        self.fees_per_share = self.fees_per_share.saturating_add(per_share);
+        self.unpaid_fees = self.unpaid_fees.saturating_add(fee);
+    }
+
+    /// LP fees accounted for `shares` LP shares so far.
+    pub fn fees_for(&self, shares: U256) -> U256 {
+        shares.saturating_mul(self.fees_per_share) / U256::from(LP_FEE_SCALE)
+    }
+
+    /// LP fees `position` is owed.
+    ///
+    /// A provider holding every LP share is owed all unpaid fees, rounding
+    /// remainder included.
+    pub fn pending_fees(&self, position: &LiquidityPosition) -> U256 {
+        if !position.shares.is_zero() && position.shares >= self.total_shares {
+            return self.unpaid_fees;
+        }
+        self.fees_for(position.shares).saturating_sub(position.fee_debt)
+    }
+
+    /// Pays out the fees `position` is owed and returns them.
+    pub fn settle_fees(&mut self, position: &mut LiquidityPosition) -> U256 {
+        let fees = self.pending_fees(position);
+        self.unpaid_fees = self.unpaid_fees.saturating_sub(fees);
+        position.fee_debt = self.fees_for(position.shares);
+        fees
+    }
+
+    /// Issues `shares` LP shares for `deposit` motes to a position whose fees
+    /// are settled.
+    pub fn mint_shares(&mut self, position: &mut LiquidityPosition, shares: U256, deposit: U256) {
+        position.shares = position.shares.saturating_add(shares);
+        position.deposited = position.deposited.saturating_add(deposit);
+        position.fee_debt = self.fees_for(position.shares);
+        self.total_shares = self.total_shares.saturating_add(shares);
+    }
+
+    /// Burns `shares` LP shares of a position whose fees are settled, and
+    /// returns the part of its deposit they carried.
+    pub fn burn_shares(&mut self, position: &mut LiquidityPosition, shares: U256) -> U256 {
+        let deposit = if position.shares.is_zero() {
+            U256::zero()
+        } else {
+            position.deposited.saturating_mul(shares) / position.shares
+        };
+        position.shares = position.shares.saturating_sub(shares);
+        position.deposited = position.deposited.saturating_sub(deposit);
+        position.fee_debt = self.fees_for(position.shares);
+        self.total_shares = self.total_shares.saturating_sub(shares);
+        deposit
+    }
+}
+
+/// A liquidity provider's stake in a complete-set AMM market.
+#[odra::odra_type]
+#[derive(Default)]
+pub struct LiquidityPosition {
+    /// LP shares held
+    pub shares: U256,
+    /// CSPR deposited for the LP shares still held, refunded on cancellation
+    pub deposited: U256,
+    /// LP fees already accounted for the shares held
+    pub fee_debt: U256,
+}
+
+/// Length of a price checkpoint period, in milliseconds (10 minutes)
+pub const PRICE_CHECKPOINT_PERIOD: u64 = 600_000;
+
+/// Number of checkpoint periods kept per outcome (a day)
+pub const PRICE_CHECKPOINTS: u64 = 144;
+
+/// An outcome's cumulative price as of a point in time.
+///
+/// Two readings give the time-weighted average price between them.
+#[odra::odra_type]
+#[derive(Default, Copy)]
+pub struct PriceAccumulator {
+    /// Block time of the reading, in milliseconds
+    pub timestamp: u64,
+    /// Sum of `price * elapsed milliseconds` from market creation to `timestamp`
+    pub cumulative_price: U256,
+}
+
+impl PriceAccumulator {
+    /// The accumulator at `now`, with `price` in effect since `timestamp`.
+    pub fn advanced(&self, now: u64, price: U256) -> Self {
+        let elapsed = U256::from(now.saturating_sub(self.timestamp));
+        Self {
+            timestamp: now.max(self.timestamp),
+            cumulative_price: self.cumulative_price.saturating_add(price.saturating_mul(elapsed)),
+        }
+    }
+
+    /// Checkpoint period the reading falls in.
+    pub fn period(&self) -> u64 {
+        self.timestamp / PRICE_CHECKPOINT_PERIOD
+    }
+
+    /// Time-weighted average price from `earlier` to this reading, or `None`
+    /// if `earlier` is not earlier.
+    pub fn average_since(&self, earlier: &PriceAccumulator) -> Option<U256> {
+        let elapsed = self.timestamp.checked_sub(earlier.timestamp).filter(|&elapsed| elapsed > 0)?;
+        Some(self.cumulative_price.saturating_sub(earlier.cumulative_price) / U256::from(elapsed))
+    }
+}
+
+/// Side of a trade or limit order.
+#[odra::odra_type]
+#[derive(Default, Copy)]
+pub enum OrderSide {
+    /// Buy shares from the market
+    #[default]
+    Buy,
+    /// Sell shares back to the market
+    Sell,
+}
+
+/// Lifecycle of a limit order.
+#[odra::odra_type]
+#[derive(Default, Copy)]
+pub enum OrderStatus {
+    /// Waiting for the price to reach the limit
+    #[default]
+    Open,
+    /// Executed by a keeper
+    Filled,
+    /// Cancelled by its owner or closed after expiry
+    Cancelled,
+}
+
+/// A resting limit order on one outcome of a market.
+///
+/// The order triggers on the outcome's spot price (`Market::get_current_price`)
+/// and is then filled against the market's pricing, like a `buy_shares` or
+/// `sell_shares` call by its owner, as far as the spot price stays within
+/// the limit. Whatever the fill leaves of the budget or the reserved shares
+/// keeps resting until a later fill, cancellation or expiry.
+#[odra::odra_type]
+pub struct LimitOrder {
+    /// Address that placed the order and receives its proceeds
+    pub owner: Address,
+    /// Outcome traded
+    pub outcome_id: u64,
+    /// Buy or sell
+    pub side: OrderSide,
+    /// Highest spot price to buy at, or lowest to sell at, in motes
+    pub limit_price: U256,
+    /// Escrowed budget in motes for a buy, fee included, or reserved shares
+    /// for a sell; what is left of either after partial fills
+    pub amount: U256,
+    /// Escrowed reward in motes paid to keepers in proportion to what they fill
+    pub keeper_reward: U256,
+    /// Block time in milliseconds after which the order can no longer fill
+    pub expires_at: u64,
+    /// Current status
+    pub status: OrderStatus,
+}
+
+impl LimitOrder {
+    /// Whether an outcome's spot price has reached the limit.
+    pub fn is_triggered(&self, price: U256) -> bool {
+        match self.side {
+            OrderSide::Buy => price <= self.limit_price,
+            OrderSide::Sell => price >= self.limit_price,
+        }
+    }
+
+    /// Whether the order has expired at block time `now`.
+    pub fn is_expired(&self, now: u64) -> bool {
+        now > self.expires_at
+    }
+}
+
+/// One leg of a `Market::batch_trade`.
+#[odra::odra_type]
+pub struct TradeLeg {
+    /// Outcome traded
+    pub outcome_id: u64,
+    /// Buy or sell
+    pub side: OrderSide,
+    /// Number of shares bought or sold
+    pub shares: U256,
+}
+
+/// Sum of `shares` of every outcome, each counted `weights[outcome_id]` times.
+pub fn weighted_shares(shares: &[U256], weights: &[u64]) -> U256 {
+    shares.iter().zip(weights).fold(U256::zero(), |sum, (shares, weight)| {
+        sum.saturating_add(shares.saturating_mul(U256::from(*weight)))
+    })
+}
+
+/// Payout terms frozen when a market resolves.
+///
+/// Every claim pays `distributable_pool * shares / total_winning_shares`,
+/// rounded down. Once all winning shares are claimed, the rounding
+/// remainder is recorded as `dust` and swept to the fee recipient.
+/// Funds owed to neither winners nor fees are left to the creator as
+/// `creator_return`.
+///
+/// When several outcomes pay, as in a market resolved Invalid, a holder's
+/// shares of each outcome count `payout_weights[outcome_id]` times towards
+/// their claim, and `total_winning_shares` is weighted the same way. If the
+/// pool is instead split between outcomes, as in a scalar bonding curve
+/// market, each outcome's part in `outcome_pools` is shared pro rata among
+/// the holders of that outcome.
+#[odra::odra_type]
+#[derive(Default)]
+pub struct Settlement {
+    /// The resolved outcome
+    pub winning_outcome: u64,
+    /// Whether the market was resolved Invalid
+    pub invalid: bool,
+    /// Value a scalar market was resolved to
+    pub scalar_value: Option<i64>,
+    /// Weight of each outcome's shares, by outcome ID, when several outcomes
+    /// pay; empty when only `winning_outcome` does
+    pub payout_weights: Vec<u64>,
+    /// Pool owed to winners: trader funds minus the settlement fee, plus the
+    /// creator's seed when anyone holds the winning outcome
+    pub distributable_pool: U256,
+    /// Settlement fee withheld from the pool
+    pub fee_amount: U256,
+    /// Winning shares outstanding at resolution
+    pub total_winning_shares: U256,
+    /// Winning shares claimed so far
+    pub claimed_shares: U256,
+    /// Cumulative amount paid to winners
+    pub total_paid_out: U256,
+    /// Remainder swept to the fee recipient once nothing is left to claim
+    pub dust: U256,
+    /// Amount the creator can withdraw after resolution, or the liquidity
+    /// providers in a complete-set AMM market
+    pub creator_return: U256,
+    /// Amount winners were owed beyond what the market held at resolution
+    pub shortfall: U256,
+    /// Part of `distributable_pool` owed to each outcome's holders, by
+    /// outcome ID, when the pool is split between outcomes; empty otherwise
+    pub outcome_pools: Vec<U256>,
+    /// Shares of each outcome outstanding at resolution, by outcome ID, when
+    /// the pool is split between outcomes; empty otherwise
+    pub outcome_shares: Vec<U256>,
+}
+
+impl Settlement {
+    /// Freezes the payout terms for a market resolved to `winning_outcome`.
+    ///
+    /// With no winning shares the seed stays with the creator and the whole
+    /// distributable pool is dust.
+    pub fn new(
+        winning_outcome: u64,
+        total_pool: U256,
+        fee_bps: u64,
+        seed: U256,
+        total_winning_shares: U256,
+    ) -> Self {
+        let fee_amount = apply_bps(total_pool, fee_bps);
+        let mut distributable_pool = total_pool.saturating_sub(fee_amount);
+        let mut dust = U256::zero();
+        let mut creator_return = U256::zero();
+        if total_winning_shares.is_zero() {
+            dust = distributable_pool;
+            creator_return = seed;
+        } else {
+            distributable_pool = distributable_pool.saturating_add(seed);
+        }
+        Self {
+            winning_outcome,
+            invalid: false,
+            scalar_value: None,
+            payout_weights: Vec::new(),
+            distributable_pool,
+            fee_amount,
+            total_winning_shares,
+            claimed_shares: U256::zero(),
+            total_paid_out: U256::zero(),
+            dust,
+            creator_return,
+            shortfall: U256::zero(),
+            outcome_pools: Vec::new(),
+            outcome_shares: Vec::new(),
+        }
+    }
+
+    /// Splits the distributable pool between outcomes by `payout_weights`,
+    /// each outcome's part to be shared pro rata among its holders rather
+    /// than weighting their shares. `supplies` are the shares of each
+    /// outcome outstanding, by outcome ID.
+    ///
+    /// The part of an outcome nobody holds, and the rounding remainder of
+    /// the split, is dust.
+    pub fn split_by_outcome(mut self, supplies: &[U256]) -> Self {
+        if self.total_winning_shares.is_zero() {
+            return self;
+        }
+        let weight_total = self.payout_weights.iter().sum::<u64>().max(1);
+        let pool = self.distributable_pool;
+        self.outcome_pools = self
+            .payout_weights
+            .iter()
+            .zip(supplies)
+            .map(|(weight, supply)| {
+                if supply.is_zero() {
+                    return U256::zero();
+                }
+                pool.saturating_mul(U256::from(*weight)) / weight_total
+            })
+            .collect();
+        self.outcome_shares = supplies.to_vec();
+        self.distributable_pool = self.outcome_pools.iter().fold(U256::zero(), |sum, part| sum + *part);
+        self.dust = self.dust.saturating_add(pool - self.distributable_pool);
+        self
+    }
+
+    /// Freezes the payout terms for a market whose winning shares each pay
+    /// `share_value / weight_total`, such as an LMSR market.
+    ///
+    /// `weight_total` is 1 unless several outcomes pay, in which case it is
+    /// the sum of the payout weights a complete set of shares adds up to.
+    /// The settlement fee is withheld from the winners' payout. `available`
+    /// is everything the market holds, subsidy included; whatever winners
+    /// and fees do not take is returned to the creator. If winners are owed
+    /// more than `available`, they share it pro rata and the difference is
+    /// recorded as `shortfall`.
+    pub fn fixed_payout(
+        winning_outcome: u64,
+        available: U256,
+        share_value: U256,
+        weight_total: u64,
+        fee_bps: u64,
+        total_winning_shares: U256,
+    ) -> Self {
+        let owed_in_full = total_winning_shares.saturating_mul(share_value) / weight_total.max(1);
+        let owed = owed_in_full.min(available);
+        let fee_amount = apply_bps(owed, fee_bps);
+        let distributable_pool = owed - fee_amount;
+        Self {
+            winning_outcome,
+            invalid: false,
+            scalar_value: None,
+            payout_weights: Vec::new(),
+            distributable_pool,
+            fee_amount,
+            total_winning_shares,
+            claimed_shares: U256::zero(),
+            total_paid_out: U256::zero(),
+            dust: U256::zero(),
+            creator_return: available - owed,
+            shortfall: owed_in_full - owed,
+            outcome_pools: Vec::new(),
+            outcome_shares: Vec::new(),
+        }
+    }
+
+    /// Shares counting towards the claim of a holder of `shares` of every
+    /// outcome, indexed by outcome ID.
+    pub fn payout_shares(&self, shares: &[U256]) -> U256 {
+        if self.payout_weights.is_empty() {
+            return shares.get(self.winning_outcome as usize).copied().unwrap_or_default();
+        }
+        weighted_shares(shares, &self.payout_weights)
+    }
+
+    /// Payout owed for `shares` winning shares.
+    pub fn payout_for(&self, shares: U256) -> U256 {
+        if self.total_winning_shares.is_zero() {
+            return U256::zero();
+        }
+        self.distributable_pool.saturating_mul(shares) / self.total_winning_shares
+    }
+
+    /// Payout owed to a holder of `shares` of every outcome, indexed by
+    /// outcome ID.
+    pub fn payout_for_holdings(&self, shares: &[U256]) -> U256 {
+        if self.outcome_pools.is_empty() {
+            return self.payout_for(self.payout_shares(shares));
+        }
+        self.outcome_pools
+            .iter()
+            .zip(&self.outcome_shares)
+            .zip(shares)
+            .filter(|((_, supply), _)| !supply.is_zero())
+            .fold(U256::zero(), |sum, ((part, supply), held)| sum + part.saturating_mul(*held) / *supply)
+    }
+
+    /// Records the claim of a holder of `shares` of every outcome, indexed
+    /// by outcome ID, and returns its payout.
+    ///
+    /// The claim that settles the last winning shares also fixes `dust`.
+    pub fn record_claim(&mut self, shares: &[U256]) -> U256 {
+        let payout = self.payout_for_holdings(shares);
+        let shares = self.payout_shares(shares);
+        self.claimed_shares = self.claimed_shares.saturating_add(shares);
+        self.total_paid_out = self.total_paid_out.saturating_add(payout);
+        if self.is_fully_claimed() {
+            self.dust = self.distributable_pool.saturating_sub(self.total_paid_out);
+        }
+        payout
+    }
+
+    /// Whether every winning share has been claimed.
+    pub fn is_fully_claimed(&self) -> bool {
+        !self.total_winning_shares.is_zero() && self.claimed_shares >= self.total_winning_shares
+    }
+}
+
 /// Comprehensive market information returned by view functions.
 #[odra::odra_type]
 pub struct MarketInfo {
@@ -152,6 +628,10 @@ pub struct MarketMetadata {
     pub resolution_source: String,
     /// Category of the market
     pub category: String,
+    /// Sub-questions of a compound market (empty for other types)
+    pub legs: Vec<CompoundLeg>,
+    /// Range of a scalar market (`None` for other types)
+    pub scalar: Option<ScalarRange>,
 }
 
 /// Consolidated configuration for a market.
@@ -161,12 +641,228 @@ pub struct MarketConfig {
     pub admin: Address,
     /// Designated resolver for this market
     pub resolver: Address,
-    /// Platform fee in basis points
-    pub platform_fee_bps: u64,
+    /// Fees charged on trades and at settlement
+    pub fees: FeeSchedule,
     /// Vault contract address
     pub vault_contract: Address,
     /// Factory contract address
     pub factory_contract: Address,
+    /// Bonding curve parameters
+    pub bonding_params: BondingCurveParams,
+    /// How outcomes are priced
+    pub pricing: PricingMode,
+    /// LMSR liquidity parameter `b` in motes (zero for bonding curve markets)
+    pub lmsr_liquidity: U256,
+    /// Oracle the market can be resolved from, if any
+    pub oracle: Option<OracleBinding>,
+    /// Bond and dispute window of optimistic resolution, if the market opted in
+    pub optimistic: Option<OptimisticParams>,
+    /// Committee resolving the market by vote instead of `resolver`, if any
+    pub committee: Option<ResolverCommittee>,
+}
+
+/// Committee vote for resolving a market as Invalid
+pub const INVALID_VOTE: u64 = u64::MAX;
+
+/// An M-of-N committee resolving a market by vote.
+///
+/// The market resolves as soon as `threshold` members vote for the same
+/// outcome, or as Invalid once they vote `INVALID_VOTE`. The threshold must
+/// be a majority of the members, so no two outcomes can both reach it.
+/// While a market has a committee its single `resolver` key has no power;
+/// only the admin keeps the right to resolve directly.
+#[odra::odra_type]
+pub struct ResolverCommittee {
+    /// Members allowed to vote
+    pub members: Vec<Address>,
+    /// Matching votes needed to resolve
+    pub threshold: u64,
+}
+
+impl ResolverCommittee {
+    /// Whether the threshold is a reachable majority and no member is
+    /// listed twice.
+    pub fn is_valid(&self) -> bool {
+        let distinct = self.members.iter().enumerate().all(|(i, member)| !self.members[..i].contains(member));
+        let members = self.members.len() as u64;
+        self.threshold > members / 2 && self.threshold <= members && distinct
+    }
+
+    /// Whether `address` is a member.
+    pub fn is_member(&self, address: &Address) -> bool {
+        self.members.contains(address)
+    }
+}
+
+/// Terms of optimistic resolution.
+///
+/// Once a market has ended, anyone can propose its outcome by posting at
+/// least `bond`. Anyone can dispute the proposal with a matching bond for
+/// `dispute_window` milliseconds; an undisputed proposal then becomes final,
+/// and a disputed one is arbitrated by the resolver or admin, the side that
+/// loses forfeiting its bond to the other.
+///
+/// Markets opt in to it, and only if they are bound to neither an oracle
+/// nor a resolver committee. Until a proposal is final, resolving the
+/// market directly overrides it and settles its bonds the same way.
+#[odra::odra_type]
+#[derive(Copy)]
+pub struct OptimisticParams {
+    /// Smallest bond a proposal must post, in motes
+    pub bond: U256,
+    /// Milliseconds after a proposal during which it can be disputed
+    pub dispute_window: u64,
+}
+
+impl OptimisticParams {
+    /// A 10 CSPR bond and a 24 hour dispute window.
+    pub fn default_params() -> Self {
+        Self {
+            bond: U256::from(10_000_000_000u64),
+            dispute_window: 24 * 60 * 60 * 1000,
+        }
+    }
+}
+
+/// An outcome proposed under optimistic resolution.
+#[odra::odra_type]
+pub struct ResolutionProposal {
+    /// Address that proposed the outcome
+    pub proposer: Address,
+    /// Proposed winning outcome
+    pub outcome_id: u64,
+    /// Bond escrowed by the proposer, and by the disputer if any, in motes
+    pub bond: U256,
+    /// Block time in milliseconds after which the proposal cannot be disputed
+    pub dispute_deadline: u64,
+    /// Address that disputed the proposal, if any
+    pub disputer: Option<Address>,
+}
+
+/// An oracle a market is bound to at creation.
+///
+/// Once the market has ended, anyone can resolve it with the oracle's answer
+/// to `query_id` through `Market::resolve_from_oracle`.
+#[odra::odra_type]
+pub struct OracleBinding {
+    /// Contract implementing `OracleAdapter`
+    pub oracle: Address,
+    /// Question the market asks the oracle
+    pub query_id: u64,
+    /// Oldest answer accepted, as milliseconds between its update and resolution
+    pub max_age: u64,
+}
+
+/// An oracle's answer to a query.
+#[odra::odra_type]
+pub struct OracleAnswer {
+    /// Winning outcome ID; ignored when the answer is invalid
+    pub outcome_id: u64,
+    /// Whether the question has no valid answer, e.g. it was ambiguous
+    pub invalid: bool,
+    /// Block time in milliseconds the answer was last updated
+    pub updated_at: u64,
+}
+
+/// Fees charged by a market, in basis points.
+#[odra::odra_type]
+#[derive(Default, Copy)]
+pub struct FeeSchedule {
+    /// Fee on the bonding curve cost of a buy, paid on top of the cost
+    pub buy_fee_bps: u64,
+    /// Fee deducted from the bonding curve revenue of a sell
+    pub sell_fee_bps: u64,
+    /// Fee withheld from the pool when the market resolves
+    pub settlement_fee_bps: u64,
+    /// Share of every fee owed to the market creator (basis points of the fee)
+    pub creator_share_bps: u64,
+    /// Share of buy and sell fees paid to the liquidity providers of a
+    /// complete-set AMM market (basis points of the fee)
+    pub lp_share_bps: u64,
+}
+
+impl FeeSchedule {
+    /// Schedule charging `fee_bps` on sells and at settlement, with free buys.
+    pub fn flat(fee_bps: u64) -> Self {
+        Self {
+            buy_fee_bps: 0,
+            sell_fee_bps: fee_bps,
+            settlement_fee_bps: fee_bps,
+            creator_share_bps: 0,
+            lp_share_bps: 0,
+        }
+    }
+
+    /// Whether every fee is within `MAX_FEE_BPS` and the creator and LP
+    /// shares within 100%.
+    pub fn is_valid(&self) -> bool {
+        self.buy_fee_bps <= MAX_FEE_BPS
+            && self.sell_fee_bps <= MAX_FEE_BPS
+            && self.settlement_fee_bps <= MAX_FEE_BPS
+            && self.creator_share_bps <= BPS_DENOMINATOR
+            && self.lp_share_bps <= BPS_DENOMINATOR
+    }
+
+    /// Buy fee owed on a bonding curve cost.
+    pub fn buy_fee(&self, cost: U256) -> U256 {
+        apply_bps(cost, self.buy_fee_bps)
+    }
+
+    /// Sell fee owed on a bonding curve revenue.
+    pub fn sell_fee(&self, revenue: U256) -> U256 {
+        apply_bps(revenue, self.sell_fee_bps)
+    }
+
+    /// Creator's share of a fee.
+    pub fn creator_fee(&self, fee: U256) -> U256 {
+        apply_bps(fee, self.creator_share_bps)
+    }
+
+    /// Liquidity providers' share of a trading fee.
+    pub fn lp_fee(&self, fee: U256) -> U256 {
+        apply_bps(fee, self.lp_share_bps)
+    }
+
+    /// Bonding curve cost that can be spent from `budget` while leaving room
+    /// for the buy fee on top.
+    pub fn max_cost_before_buy_fee(&self, budget: U256) -> U256 {
+        budget.saturating_mul(U256::from(BPS_DENOMINATOR))
+            / U256::from(BPS_DENOMINATOR + self.buy_fee_bps)
+    }
+}
+
+/// `amount * bps / 10_000`, rounded down.
+pub fn apply_bps(amount: U256, bps: u64) -> U256 {
+    amount.saturating_mul(U256::from(bps)) / U256::from(BPS_DENOMINATOR)
+}
+
+/// Splits 10_000 basis points across outcomes in proportion to `weights`.
+///
+/// Every outcome first gets `weight * 10_000 / total`, rounded down. The
+/// basis points left over go one each to the outcomes with the largest
+/// rounding remainders, ties going to the lowest outcome ID. Without any
+/// weight every outcome counts equally. The result always sums to exactly
+/// 10_000, so an off-chain client running this on the same weights gets the
+/// same probabilities as `Market::get_implied_probabilities`.
+pub fn implied_probabilities_bps(weights: &[U256]) -> Vec<u64> {
+    let total = weights.iter().fold(U256::zero(), |sum, weight| sum.saturating_add(*weight));
+    let weights = if total.is_zero() { vec![U256::one(); weights.len()] } else { weights.to_vec() };
+    let total = if total.is_zero() { U256::from(weights.len()) } else { total };
+
+    let mut bps = Vec::with_capacity(weights.len());
+    let mut remainders = Vec::with_capacity(weights.len());
+    for (outcome_id, weight) in weights.iter().enumerate() {
+        let scaled = weight.saturating_mul(U256::from(BPS_DENOMINATOR));
+        bps.push((scaled / total).as_u64());
+        remainders.push((scaled % total, outcome_id));
+    }
+
+    let assigned: u64 = bps.iter().sum();
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    for (_, outcome_id) in remainders.iter().take(BPS_DENOMINATOR.saturating_sub(assigned) as usize) {
+        bps[*outcome_id] += 1;
+    }
+    bps
 }
 
 /// Parameters for creating a new binary market.
@@ -182,82 +878,280 @@ pub struct BinaryMarketParams {
     pub category: String,
 }
 
-/// Parameters for creating a new multiple choice market.
+/// A single sub-question (leg) of a compound market.
 #[odra::odra_type]
-pub struct MultipleChoiceMarketParams {
-    /// The prediction question
+pub struct CompoundLeg {
+    /// The sub-question
     pub question: String,
-    /// List of outcome names
+    /// Possible results of this leg
     pub outcomes: Vec<String>,
+}
+
+/// Parameters for creating a new compound market.
+#[odra::odra_type]
+pub struct CompoundMarketParams {
+    /// The overall prediction question
+    pub question: String,
+    /// Related sub-questions; every combination of their results is an outcome
+    pub legs: Vec<CompoundLeg>,
+    /// Unix timestamp when trading ends
+    pub end_time: u64,
+    /// Data source for resolution
+    pub resolution_source: String,
+    /// Market category
+    pub category: String,
+    /// Bonding curve family the outcomes are priced with
+    pub curve: CurveKind,
+    /// Whether outcomes trade on bonding curves or against an LMSR market maker
+    pub pricing: PricingMode,
+}
+
+/// Number of combined outcomes of a compound market (product of leg sizes).
+/// Saturates at `u64::MAX`.
+pub fn compound_outcome_count(legs: &[CompoundLeg]) -> u64 {
+    legs.iter()
+        .fold(1u64, |count, leg| count.saturating_mul(leg.outcomes.len() as u64))
+}
+
+/// Outcome ID of the combination hitting one result per leg.
+///
+/// The first leg is the most significant digit, so IDs follow the order
+/// of `compound_outcome_names`. Returns `None` if a result is out of range
+/// or the number of results does not match the number of legs.
+pub fn compound_outcome_id(legs: &[CompoundLeg], results: &[u64]) -> Option<u64> {
+    if legs.len() != results.len() {
+        return None;
+    }
+    let mut outcome_id = 0u64;
+    for (leg, result) in legs.iter().zip(results) {
+        let size = leg.outcomes.len() as u64;
+        if *result >= size {
+            return None;
+        }
+        outcome_id = outcome_id.checked_mul(size)?.checked_add(*result)?;
+    }
+    Some(outcome_id)
+}
+
+/// Names of all combined outcomes, e.g. "Yes & No", in outcome ID order.
+pub fn compound_outcome_names(legs: &[CompoundLeg]) -> Vec<String> {
+    let mut names = vec![String::new()];
+    for leg in legs {
+        let mut next = Vec::with_capacity(names.len() * leg.outcomes.len());
+        for prefix in &names {
+            for outcome in &leg.outcomes {
+                if prefix.is_empty() {
+                    next.push(outcome.clone());
+                } else {
+                    next.push(format!("{} & {}", prefix, outcome));
+                }
+            }
+        }
+        names = next;
+    }
+    names
+}
+
+/// Outcome ID of the LONG shares of a scalar market
+pub const SCALAR_LONG: u64 = 0;
+
+/// Outcome ID of the SHORT shares of a scalar market
+pub const SCALAR_SHORT: u64 = 1;
+
+/// Range a scalar market's value is resolved within.
+///
+/// Bounds and values share one fixed-point unit chosen by the market, e.g.
+/// a CSPR price in ten-thousandths of a dollar for "between 0.01 and 0.10"
+/// is the range 100 to 1000.
+#[odra::odra_type]
+#[derive(Copy)]
+pub struct ScalarRange {
+    /// Value at or below which SHORT shares take the whole payout
+    pub lower_bound: i64,
+    /// Value at or above which LONG shares take the whole payout
+    pub upper_bound: i64,
+}
+
+impl ScalarRange {
+    /// Whether the range is non-empty.
+    pub fn is_valid(&self) -> bool {
+        self.lower_bound < self.upper_bound
+    }
+
+    /// Share of the payout owed to LONG shares, in basis points, for a
+    /// market resolved to `value`. Values outside the range are clamped.
+    pub fn long_payout_bps(&self, value: i64) -> u64 {
+        let value = value.clamp(self.lower_bound, self.upper_bound);
+        let width = self.upper_bound as i128 - self.lower_bound as i128;
+        let position = value as i128 - self.lower_bound as i128;
+        (position * BPS_DENOMINATOR as i128 / width) as u64
+    }
+
+    /// Payout weights of the LONG and SHORT outcomes for a market resolved
+    /// to `value`, indexed by outcome ID.
+    pub fn payout_weights(&self, value: i64) -> Vec<u64> {
+        let long = self.long_payout_bps(value);
+        vec![long, BPS_DENOMINATOR - long]
+    }
+}
+
+/// Parameters for creating a new scalar market.
+#[odra::odra_type]
+pub struct ScalarMarketParams {
+    /// The prediction question
+    pub question: String,
+    /// Range the resolved value is mapped onto
+    pub range: ScalarRange,
     /// Unix timestamp when trading ends
     pub end_time: u64,
     /// Data source for resolution
     pub resolution_source: String,
     /// Market category
     pub category: String,
+    /// Bonding curve family the outcomes are priced with
+    pub curve: CurveKind,
+    /// Whether outcomes trade on bonding curves or against an LMSR market maker
+    pub pricing: PricingMode,
+}
+
+/// Family of bonding curve a market prices its outcomes with.
+#[odra::odra_type]
+#[derive(Default, Copy)]
+pub enum CurveKind {
+    /// `price = initial_price + k * supply`
+    #[default]
+    Linear,
+    /// `price = initial_price * (1 + k / 1e9)^supply`
+    Exponential,
+    /// `price = initial_price + k * sqrt(supply)`, flattening as supply grows
+    Sqrt,
+    /// Rises from about `initial_price` to at most `max_price` around
+    /// `midpoint`, over a width of `k` shares
+    CappedSigmoid,
+}
+
+/// How a market prices its outcomes.
+#[odra::odra_type]
+#[derive(Default, Copy)]
+pub enum PricingMode {
+    /// Every outcome has its own bonding curve; winners split the pool
+    #[default]
+    BondingCurve,
+    /// One LMSR market maker prices all outcomes as probabilities, funded by
+    /// the creator's seed; every winning share pays `LMSR_SHARE_VALUE`
+    Lmsr,
+    /// Outcomes trade against a constant-product pool of complete sets
+    /// seeded by the creator; every winning share pays one mote
+    CompleteSetAmm,
 }
 
 /// Bonding curve configuration parameters.
 #[odra::odra_type]
 #[derive(Default)]
 pub struct BondingCurveParams {
+    /// Curve family; the meaning of `k_constant` depends on it
+    pub kind: CurveKind,
     /// Base price per share in motes (1 CSPR = 1_000_000_000 motes)
     /// Default: 10_000_000 motes = 0.01 CSPR
     pub initial_price: U256,
-    /// Curve steepness constant (scaled by 1e9 for precision)
+    /// Curve steepness constant
     /// Default: 1_000_000 = 0.001 CSPR per share
     pub k_constant: U256,
+    /// Price cap of a capped sigmoid (unused by other curves)
+    pub max_price: U256,
+    /// Supply at which a capped sigmoid is halfway to its cap (unused by other curves)
+    pub midpoint: U256,
 }
 
 impl BondingCurveParams {
     /// Creates default bonding curve parameters.
     /// Initial price: 0.01 CSPR, K: 0.001 CSPR per share
     pub fn default_params() -> Self {
+        Self::preset(CurveKind::Linear)
+    }
+
+    /// Creates default parameters for a curve family, all starting at 0.01 CSPR.
+    ///
+    /// - Linear: +0.001 CSPR per share
+    /// - Exponential: +0.1% per share
+    /// - Sqrt: +0.0001 CSPR per square root of a share
+    /// - CappedSigmoid: up to 1 CSPR, halfway at 10_000 shares, over 2_500 shares
+    pub fn preset(kind: CurveKind) -> Self {
+        let initial_price = U256::from(10_000_000u64); // 0.01 CSPR in motes
+        let (k_constant, max_price, midpoint) = match kind {
+            CurveKind::Linear => (1_000_000u64, 0u64, 0u64),
+            CurveKind::Exponential => (GROWTH_RATE_SCALE / 1_000, 0, 0),
+            CurveKind::Sqrt => (100_000, 0, 0),
+            CurveKind::CappedSigmoid => (2_500, 1_000_000_000, 10_000),
+        };
         Self {
-            initial_price: U256::from(10_000_000u64), // 0.01 CSPR in motes
-            k_constant: U256::from(1_000_000u64),     // 0.001 CSPR per share
+            kind,
+            initial_price,
+            k_constant: U256::from(k_constant),
+            max_price: U256::from(max_price),
+            midpoint: U256::from(midpoint),
+        }
+    }
+
+    /// Whether the parameters describe a curve that never gives shares away
+    /// and stays within its family's limits.
+    pub fn is_valid(&self) -> bool {
+        if self.initial_price.is_zero() {
+            return false;
+        }
+        match self.kind {
+            CurveKind::Linear | CurveKind::Sqrt => true,
+            CurveKind::Exponential => self.k_constant <= U256::from(GROWTH_RATE_SCALE),
+            CurveKind::CappedSigmoid => {
+                self.max_price > self.initial_price && !self.k_constant.is_zero()
+            }
+        }
+    }
+
+    /// Run `f` against the curve these parameters describe.
+    fn with_curve<R>(&self, f: impl FnOnce(&dyn BondingCurve) -> R) -> R {
+        match self.kind {
+            CurveKind::Linear => f(&LinearCurve {
+                initial_price: self.initial_price,
+                slope: self.k_constant,
+            }),
+            CurveKind::Exponential => f(&ExponentialCurve {
+                initial_price: self.initial_price,
+                growth_rate: self.k_constant,
+            }),
+            CurveKind::Sqrt => f(&SqrtCurve {
+                initial_price: self.initial_price,
+                k: self.k_constant,
+            }),
+            CurveKind::CappedSigmoid => f(&SigmoidCurve {
+                initial_price: self.initial_price,
+                max_price: self.max_price,
+                midpoint: self.midpoint,
+                width: self.k_constant,
+            }),
         }
     }
 
     /// Calculate the price for a specific supply level.
-    /// price(supply) = initial_price + (k * supply)
     pub fn price_at_supply(&self, supply: U256) -> U256 {
-        self.initial_price.saturating_add(self.k_constant.saturating_mul(supply))
+        self.with_curve(|curve| curve.price_at_supply(supply))
     }
 
     /// Calculate the cost to buy n shares starting from current_supply.
-    /// cost = n * initial_price + k * [n*(2*current_supply + n + 1)/2]
     pub fn cost_to_buy(&self, current_supply: U256, shares: U256) -> U256 {
-        if shares.is_zero() {
-            return U256::zero();
-        }
-
-        // Base cost: n * initial_price
-        let base_cost = shares.saturating_mul(self.initial_price);
-
-        // Premium cost from bonding curve:
-        // Sum of (k * i) for i from current_supply+1 to current_supply+n
-        // = k * sum(i) = k * [n * (2*current_supply + n + 1) / 2]
-        let two = U256::from(2u64);
-        let sum_factor = shares.saturating_mul(
-            two.saturating_mul(current_supply)
-                .saturating_add(shares)
-                .saturating_add(U256::one())
-        ) / two;
-        let premium = self.k_constant.saturating_mul(sum_factor);
+        self.with_curve(|curve| curve.cost_to_buy(current_supply, shares))
+    }
 
-        base_cost.saturating_add(premium)
+    /// Calculate the largest number of shares that cost at most max_cost,
+    /// starting from current_supply.
+    pub fn shares_for_cost(&self, current_supply: U256, max_cost: U256) -> U256 {
+        self.with_curve(|curve| curve.shares_for_cost(current_supply, max_cost))
     }
 
     /// Calculate the revenue from selling n shares starting from current_supply.
     /// This is the inverse of cost_to_buy, from current_supply-n to current_supply.
     pub fn revenue_from_sell(&self, current_supply: U256, shares: U256) -> U256 {
-        if shares.is_zero() || current_supply < shares {
-            return U256::zero();
-        }
-
-        let new_supply = current_supply.saturating_sub(shares);
-        self.cost_to_buy(new_supply, shares)
+        self.with_curve(|curve| curve.revenue_from_sell(current_supply, shares))
     }
 }
 
@@ -288,6 +1182,276 @@ mod tests {
         assert_eq!(cost_first, expected);
     }
 
+    /// Largest affordable share count found by walking the curve one share at a time.
+    fn brute_force_shares(params: &BondingCurveParams, supply: U256, max_cost: U256) -> U256 {
+        let mut shares = U256::zero();
+        while params.cost_to_buy(supply, shares + 1) <= max_cost {
+            shares += U256::one();
+        }
+        shares
+    }
+
+    #[test]
+    fn test_shares_for_cost_matches_brute_force() {
+        // Deterministic pseudo-random supplies and budgets (xorshift)
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        let mut next = |bound: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % bound
+        };
+
+        let curves = [
+            BondingCurveParams::default_params(),
+            BondingCurveParams { initial_price: U256::from(7u64), k_constant: U256::from(3u64), ..Default::default() },
+            BondingCurveParams { initial_price: U256::one(), k_constant: U256::from(1_000u64), ..Default::default() },
+            BondingCurveParams { initial_price: U256::from(250u64), k_constant: U256::zero(), ..Default::default() },
+            BondingCurveParams { initial_price: U256::zero(), k_constant: U256::from(11u64), ..Default::default() },
+        ];
+        for params in curves {
+            for _ in 0..200 {
+                let supply = U256::from(next(1_000_000));
+                // Budgets of up to a few hundred shares keep the brute force cheap
+                let budget = params.cost_to_buy(supply, U256::from(next(300)));
+                let max_cost = budget + U256::from(next(budget.low_u64().max(1)));
+
+                let shares = params.shares_for_cost(supply, max_cost);
+                assert_eq!(shares, brute_force_shares(&params, supply, max_cost));
+                assert!(params.cost_to_buy(supply, shares) <= max_cost);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shares_for_cost_edge_cases() {
+        let params = BondingCurveParams::default_params();
+        assert_eq!(params.shares_for_cost(U256::zero(), U256::zero()), U256::zero());
+        assert_eq!(params.shares_for_cost(U256::zero(), params.initial_price), U256::zero());
+        assert_eq!(params.shares_for_cost(U256::zero(), params.price_at_supply(U256::one())), U256::one());
+
+        // Huge budgets and supplies neither overflow nor overshoot
+        let supply = U256::from(u64::MAX);
+        let max_cost = U256::from(u128::MAX);
+        let shares = params.shares_for_cost(supply, max_cost);
+        assert!(!shares.is_zero());
+        assert!(params.cost_to_buy(supply, shares) <= max_cost);
+        assert!(params.cost_to_buy(supply, shares + 1) > max_cost);
+
+        // A zero initial price no longer divides by zero
+        let params = BondingCurveParams { initial_price: U256::zero(), k_constant: U256::from(2u64), ..Default::default() };
+        assert_eq!(params.shares_for_cost(U256::zero(), U256::from(6u64)), U256::from(2u64));
+    }
+
+    fn legs() -> Vec<CompoundLeg> {
+        vec![
+            CompoundLeg {
+                question: "Team A wins?".to_string(),
+                outcomes: vec!["Yes".to_string(), "No".to_string()],
+            },
+            CompoundLeg {
+                question: "Over 2.5 goals?".to_string(),
+                outcomes: vec!["Over".to_string(), "Under".to_string(), "Void".to_string()],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_compound_outcome_encoding() {
+        let legs = legs();
+        let names = compound_outcome_names(&legs);
+
+        assert_eq!(compound_outcome_count(&legs), 6);
+        assert_eq!(names.len(), 6);
+        assert_eq!(names[0], "Yes & Over");
+        assert_eq!(names[5], "No & Void");
+
+        // IDs line up with the generated names
+        assert_eq!(compound_outcome_id(&legs, &[0, 0]), Some(0));
+        assert_eq!(compound_outcome_id(&legs, &[1, 1]), Some(4));
+        assert_eq!(names[4], "No & Under");
+
+        assert_eq!(compound_outcome_id(&legs, &[2, 0]), None);
+        assert_eq!(compound_outcome_id(&legs, &[0]), None);
+    }
+
+    #[test]
+    fn test_scalar_payout_is_linear_and_clamped() {
+        let range = ScalarRange { lower_bound: 100, upper_bound: 1_000 };
+        assert!(range.is_valid());
+        assert!(!ScalarRange { lower_bound: 5, upper_bound: 5 }.is_valid());
+
+        assert_eq!(range.long_payout_bps(100), 0);
+        assert_eq!(range.long_payout_bps(325), 2_500);
+        assert_eq!(range.long_payout_bps(1_000), 10_000);
+        assert_eq!(range.long_payout_bps(-50), 0);
+        assert_eq!(range.long_payout_bps(i64::MAX), 10_000);
+        assert_eq!(range.payout_weights(730), vec![7_000, 3_000]);
+
+        // Extreme bounds do not overflow
+        let full = ScalarRange { lower_bound: i64::MIN, upper_bound: i64::MAX };
+        assert_eq!(full.long_payout_bps(0), 5_000);
+    }
+
+    #[test]
+    fn test_split_settlement_pays_each_outcome_its_part() {
+        let supplies = [U256::from(100u64), U256::from(900u64)];
+        let range = ScalarRange { lower_bound: 0, upper_bound: 100 };
+        let terms = |value: i64| Settlement {
+            payout_weights: range.payout_weights(value),
+            ..Settlement::new(0, U256::from(10_000u64), 0, U256::zero(), U256::from(1u64))
+        };
+
+        // Each side's part is shared by its holders, however many shares it has
+        let mut settlement = terms(70).split_by_outcome(&supplies);
+        assert_eq!(settlement.outcome_pools, vec![U256::from(7_000u64), U256::from(3_000u64)]);
+        assert_eq!(settlement.record_claim(&[U256::from(50u64), U256::zero()]), U256::from(3_500u64));
+        assert_eq!(settlement.record_claim(&[U256::from(50u64), U256::from(300u64)]), U256::from(4_500u64));
+
+        // The part of a side nobody holds is dust
+        let settlement = terms(70).split_by_outcome(&[U256::from(100u64), U256::zero()]);
+        assert_eq!(settlement.distributable_pool, U256::from(7_000u64));
+        assert_eq!(settlement.dust, U256::from(3_000u64));
+    }
+
+    #[test]
+    fn test_settlement_claims_sum_to_snapshot() {
+        // Deterministic pseudo-random cases (xorshift) over pool sizes, fees and
+        // ways of splitting the winning shares between claimants
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = |bound: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % bound
+        };
+
+        for _ in 0..500 {
+            let pool = U256::from(next(1_000_000_000_000));
+            let seed = U256::from(next(10_000_000_000));
+            let fee_bps = next(1_001);
+            let claimants = 1 + next(20) as usize;
+            let holdings: Vec<U256> = (0..claimants).map(|_| U256::from(1 + next(1_000_000))).collect();
+            let total_shares = holdings.iter().fold(U256::zero(), |sum, shares| sum + *shares);
+
+            let mut settlement = Settlement::new(0, pool, fee_bps, seed, total_shares);
+            assert_eq!(settlement.fee_amount + settlement.distributable_pool, pool + seed);
+
+            let mut paid = U256::zero();
+            for shares in &holdings {
+                assert!(!settlement.is_fully_claimed());
+                paid += settlement.record_claim(&[*shares]);
+            }
+
+            assert!(settlement.is_fully_claimed());
+            assert_eq!(paid, settlement.total_paid_out);
+            assert_eq!(paid + settlement.dust, settlement.distributable_pool);
+            // Rounding loses less than one mote per claim
+            assert!(settlement.dust < U256::from(claimants));
+        }
+    }
+
+    #[test]
+    fn test_fee_schedule() {
+        let fees = FeeSchedule { buy_fee_bps: 150, ..FeeSchedule::flat(200) };
+        assert!(fees.is_valid());
+        assert_eq!(fees.buy_fee(U256::from(10_000u64)), U256::from(150u64));
+        assert_eq!(fees.sell_fee(U256::from(10_000u64)), U256::from(200u64));
+
+        // The spendable cost always leaves room for its own fee
+        for budget in [0u64, 1, 999, 10_150, 123_456_789] {
+            let budget = U256::from(budget);
+            let cost = fees.max_cost_before_buy_fee(budget);
+            assert!(cost + fees.buy_fee(cost) <= budget);
+        }
+
+        assert!(!FeeSchedule { buy_fee_bps: MAX_FEE_BPS + 1, ..fees }.is_valid());
+        assert!(!FeeSchedule { creator_share_bps: 10_001, ..fees }.is_valid());
+        assert!(!FeeSchedule { lp_share_bps: 10_001, ..fees }.is_valid());
+    }
+
+    #[test]
+    fn test_implied_probabilities_sum_to_one() {
+        let bps = |weights: &[u64]| {
+            implied_probabilities_bps(&weights.iter().map(|w| U256::from(*w)).collect::<Vec<_>>())
+        };
+        assert_eq!(bps(&[1, 3]), vec![2_500, 7_500]);
+        assert_eq!(bps(&[1, 1, 1]), vec![3_334, 3_333, 3_333]);
+        assert_eq!(bps(&[0, 0, 0, 0]), vec![2_500; 4]);
+        assert_eq!(bps(&[0, 5]), vec![0, 10_000]);
+        // Left-over points go to the largest remainders: 16.66.., 33.33.., 49.99..%
+        assert_eq!(bps(&[100_001, 200_001, 299_999]), vec![1_667, 3_333, 5_000]);
+        assert!(bps(&[]).is_empty());
+
+        for weights in [&[7u64, 11, 13][..], &[1, 999_999_999], &[3; 7], &[123_456, 1, 1, 98_765]] {
+            assert_eq!(bps(weights).iter().sum::<u64>(), 10_000);
+        }
+    }
+
+    #[test]
+    fn test_price_accumulator_averages_between_readings() {
+        let start = PriceAccumulator { timestamp: 1_000, cumulative_price: U256::zero() };
+        let middle = start.advanced(2_000, U256::from(100u64));
+        let end = middle.advanced(4_000, U256::from(400u64));
+        assert_eq!(end.cumulative_price, U256::from(900_000u64));
+
+        assert_eq!(end.average_since(&start), Some(U256::from(300u64)));
+        assert_eq!(end.average_since(&middle), Some(U256::from(400u64)));
+        assert_eq!(end.average_since(&end), None);
+        assert_eq!(start.average_since(&end), None);
+
+        // A reading in the past leaves the accumulator where it is
+        assert_eq!(end.advanced(3_000, U256::from(1u64)), end);
+        assert_eq!(end.period(), 0);
+        assert_eq!(end.advanced(PRICE_CHECKPOINT_PERIOD, U256::zero()).period(), 1);
+    }
+
+    #[test]
+    fn test_lp_fees_accrue_pro_rata() {
+        let mut pool = LiquidityPool::default();
+        let (mut first, mut second) = (LiquidityPosition::default(), LiquidityPosition::default());
+        pool.mint_shares(&mut first, U256::from(100u64), U256::from(100u64));
+        pool.accrue_fees(U256::from(30u64));
+
+        // A late provider earns nothing from earlier fees
+        pool.mint_shares(&mut second, U256::from(200u64), U256::from(250u64));
+        pool.accrue_fees(U256::from(31u64));
+        assert_eq!(pool.pending_fees(&first), U256::from(40u64));
+        assert_eq!(pool.pending_fees(&second), U256::from(20u64));
+
+        assert_eq!(pool.settle_fees(&mut second), U256::from(20u64));
+        assert_eq!(pool.pending_fees(&second), U256::zero());
+        assert_eq!(pool.burn_shares(&mut second, U256::from(100u64)), U256::from(125u64));
+
+        // The last provider takes the rounding remainder
+        assert_eq!(pool.burn_shares(&mut second, U256::from(100u64)), U256::from(125u64));
+        assert_eq!(pool.settle_fees(&mut first), U256::from(41u64));
+        assert!(pool.unpaid_fees.is_zero());
+    }
+
+    #[test]
+    fn test_settlement_without_winners_is_all_dust() {
+        let settlement = Settlement::new(1, U256::from(10_000u64), 200, U256::from(500u64), U256::zero());
+        assert_eq!(settlement.fee_amount, U256::from(200u64));
+        assert_eq!(settlement.distributable_pool, U256::from(9_800u64));
+        assert_eq!(settlement.dust, settlement.distributable_pool);
+        assert!(!settlement.is_fully_claimed());
+        assert_eq!(settlement.payout_for(U256::from(1u64)), U256::zero());
+    }
+
+    #[test]
+    fn test_fixed_payout_records_shortfall() {
+        let solvent = Settlement::fixed_payout(0, U256::from(1_000u64), U256::from(10u64), 1, 0, U256::from(60u64));
+        assert_eq!(solvent.distributable_pool, U256::from(600u64));
+        assert_eq!(solvent.creator_return, U256::from(400u64));
+        assert!(solvent.shortfall.is_zero());
+
+        let insolvent = Settlement::fixed_payout(0, U256::from(1_000u64), U256::from(10u64), 1, 0, U256::from(150u64));
+        assert_eq!(insolvent.distributable_pool, U256::from(1_000u64));
+        assert!(insolvent.creator_return.is_zero());
+        assert_eq!(insolvent.shortfall, U256::from(500u64));
+    }
+
     #[test]
     fn test_user_position_add_remove() {
         let mut pos = UserPosition::new(0);
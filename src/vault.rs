@@ -4,40 +4,27 @@
 //! prediction markets. It provides the following guarantees:
 //!
 //! - **Isolation**: Each market's funds are tracked separately
-//! - **Authorization**: Only authorized market contracts can deposit/withdraw
+//! - **Authorization**: Only authorized market contracts can deposit/withdraw,
+//!   and only under the market ID they were authorized for
 //! - **Security**: Admin controls for emergency situations
-//! - **Fee Collection**: Platform fees are accumulated and claimable
+//! - **Fee Collection**: Platform fees and creator fee shares are accumulated and claimable
 
 use odra::prelude::*;
-use odra::casper_types::{U256, U512};
+use odra::casper_types::U256;
 
+use crate::curves::{u256_to_u512, u512_to_u256};
 use crate::errors::Error;
 use crate::events::{
-    FeesClaimed, FeesCollected, FundsDeposited, FundsWithdrawn,
-    MarketAuthorized, MarketRevoked, VaultPauseStatusChanged, AdminTransferred,
+    CreatorFeesClaimed, CreatorFeesCollected, FeesClaimed, FeesCollected, FundsDeposited,
+    FundsSwept, FundsWithdrawn, MarketAuthorized, MarketRevoked, VaultPauseStatusChanged,
+    AdminTransferred,
 };
 
-/// Convert U512 to U256 (assumes value fits)
-fn u512_to_u256(value: U512) -> U256 {
-    // Extract the lower 256 bits
-    let mut bytes = [0u8; 32];
-    for i in 0..32 {
-        bytes[i] = value.byte(i);
-    }
-    U256::from_little_endian(&bytes)
-}
-
-/// Convert U256 to U512
-fn u256_to_u512(value: U256) -> U512 {
-    let mut bytes = [0u8; 32];
-    value.to_little_endian(&mut bytes);
-    U512::from_little_endian(&bytes)
-}
-
 /// The Vault contract - secure escrow for all prediction market funds.
 #[odra::module(events = [
-    FundsDeposited, FundsWithdrawn, FeesCollected, FeesClaimed,
-    MarketAuthorized, MarketRevoked, VaultPauseStatusChanged, AdminTransferred
+    FundsDeposited, FundsWithdrawn, FeesCollected, FundsSwept, FeesClaimed,
+    CreatorFeesCollected, CreatorFeesClaimed, MarketAuthorized, MarketRevoked,
+    VaultPauseStatusChanged, AdminTransferred
 ])]
 pub struct Vault {
     // =========================================================================
@@ -61,18 +48,28 @@ pub struct Vault {
     // =========================================================================
     // Authorization
     // =========================================================================
-    /// Authorized market contracts (address -> is_authorized)
-    authorized_markets: Mapping<Address, bool>,
+    /// Authorized market contracts (address -> market_id it may move funds of)
+    authorized_markets: Mapping<Address, Option<u64>>,
     /// Factory contract address (can authorize markets)
     factory_contract: Var<Address>,
 
     // =========================================================================
     // Fee Collection
     // =========================================================================
-    /// Total platform fees collected and available for claiming
+    /// Total platform fees and swept funds available for claiming
     platform_fees_collected: Var<U256>,
     /// Address that receives platform fees
     fee_recipient: Var<Address>,
+    /// Lifetime fees collected per market (market_id -> CSPR in motes)
+    market_fees_collected: Mapping<u64, U256>,
+    /// Lifetime fees collected across all markets, including claimed ones
+    total_fees_collected: Var<U256>,
+    /// Unclaimed creator fee shares (creator -> CSPR in motes)
+    creator_fees: Mapping<Address, U256>,
+    /// Lifetime unclaimable funds swept per market (market_id -> CSPR in motes)
+    market_swept_funds: Mapping<u64, U256>,
+    /// Lifetime unclaimable funds swept across all markets
+    total_swept_funds: Var<U256>,
 }
 
 #[odra::module]
@@ -102,7 +99,7 @@ impl Vault {
     #[odra(payable)]
     pub fn deposit(&mut self, market_id: u64) {
         self.require_not_paused();
-        self.require_authorized_caller();
+        self.require_authorized_caller(market_id);
 
         let amount_u512 = self.env().attached_value();
         let amount = u512_to_u256(amount_u512);
@@ -133,7 +130,7 @@ impl Vault {
     /// The CSPR is transferred to the specified recipient.
     pub fn withdraw(&mut self, market_id: u64, recipient: Address, amount: U256) {
         self.require_not_paused();
-        self.require_authorized_caller();
+        self.require_authorized_caller(market_id);
 
         if amount.is_zero() {
             self.env().revert(Error::ZeroAmount);
@@ -170,10 +167,11 @@ impl Vault {
 
     /// Collect platform fees from a market's balance.
     ///
-    /// Called by market contracts when fees are deducted from trades.
+    /// Called by market contracts whenever they charge a fee, on trades and
+    /// at settlement.
     pub fn collect_platform_fees(&mut self, market_id: u64, fee_amount: U256) {
         self.require_not_paused();
-        self.require_authorized_caller();
+        self.require_authorized_caller(market_id);
 
         if fee_amount.is_zero() {
             return;
@@ -192,12 +190,113 @@ impl Vault {
         let fees = self.platform_fees_collected.get_or_default();
         self.platform_fees_collected.set(fees.saturating_add(fee_amount));
 
+        // Keep lifetime accounting per market for audits
+        let market_fees = self.market_fees_collected.get(&market_id).unwrap_or_default();
+        self.market_fees_collected.set(&market_id, market_fees.saturating_add(fee_amount));
+        let total_fees = self.total_fees_collected.get_or_default();
+        self.total_fees_collected.set(total_fees.saturating_add(fee_amount));
+
         self.env().emit_event(FeesCollected {
             market_id,
             amount: fee_amount,
         });
     }
 
+    /// Sweep an unclaimable remainder of a market's balance to the fee recipient.
+    ///
+    /// Called by market contracts for the pool of a market nobody won and the
+    /// rounding dust left after the last claim. Swept funds are claimed with
+    /// the platform fees but kept out of the fee accounting.
+    pub fn sweep_unclaimable(&mut self, market_id: u64, amount: U256) {
+        self.require_not_paused();
+        self.require_authorized_caller(market_id);
+
+        if amount.is_zero() {
+            return;
+        }
+
+        // Deduct from market balance
+        let current_balance = self.market_balances.get(&market_id).unwrap_or_default();
+        if current_balance < amount {
+            self.env().revert(Error::ExceedsMarketBalance);
+        }
+        self.market_balances.set(&market_id, current_balance.saturating_sub(amount));
+
+        // Claimable by the fee recipient
+        let fees = self.platform_fees_collected.get_or_default();
+        self.platform_fees_collected.set(fees.saturating_add(amount));
+
+        let swept = self.market_swept_funds.get(&market_id).unwrap_or_default();
+        self.market_swept_funds.set(&market_id, swept.saturating_add(amount));
+        let total_swept = self.total_swept_funds.get_or_default();
+        self.total_swept_funds.set(total_swept.saturating_add(amount));
+
+        self.env().emit_event(FundsSwept { market_id, amount });
+    }
+
+    /// Collect a market creator's share of fees from a market's balance.
+    ///
+    /// Called by market contracts alongside `collect_platform_fees`. The
+    /// amount accrues to `creator` until claimed with `claim_creator_fees`.
+    pub fn collect_creator_fees(&mut self, market_id: u64, creator: Address, fee_amount: U256) {
+        self.require_not_paused();
+        self.require_authorized_caller(market_id);
+
+        if fee_amount.is_zero() {
+            return;
+        }
+
+        // Deduct from market balance
+        let current_balance = self.market_balances.get(&market_id).unwrap_or_default();
+        if current_balance < fee_amount {
+            self.env().revert(Error::ExceedsMarketBalance);
+        }
+        self.market_balances.set(&market_id, current_balance.saturating_sub(fee_amount));
+
+        // Accrue to the creator
+        let accrued = self.creator_fees.get(&creator).unwrap_or_default();
+        self.creator_fees.set(&creator, accrued.saturating_add(fee_amount));
+
+        // Creator shares count towards the market's fee income
+        let market_fees = self.market_fees_collected.get(&market_id).unwrap_or_default();
+        self.market_fees_collected.set(&market_id, market_fees.saturating_add(fee_amount));
+        let total_fees = self.total_fees_collected.get_or_default();
+        self.total_fees_collected.set(total_fees.saturating_add(fee_amount));
+
+        self.env().emit_event(CreatorFeesCollected {
+            market_id,
+            creator,
+            amount: fee_amount,
+        });
+    }
+
+    /// Claim the caller's accrued creator fees.
+    pub fn claim_creator_fees(&mut self) {
+        self.require_not_paused();
+
+        let creator = self.env().caller();
+        let amount = self.creator_fees.get(&creator).unwrap_or_default();
+        if amount.is_zero() {
+            self.env().revert(Error::NothingToClaim);
+        }
+
+        // Reset accrued fees
+        self.creator_fees.set(&creator, U256::zero());
+
+        // Update total locked
+        let total = self.total_locked.get_or_default();
+        self.total_locked.set(total.saturating_sub(amount));
+
+        // Transfer fees
+        let amount_u512 = u256_to_u512(amount);
+        self.env().transfer_tokens(&creator, &amount_u512);
+
+        self.env().emit_event(CreatorFeesClaimed {
+            creator,
+            amount,
+        });
+    }
+
     /// Claim all collected platform fees.
     ///
     /// Can only be called by the fee recipient.
@@ -237,10 +336,10 @@ impl Vault {
     // Authorization Management
     // =========================================================================
 
-    /// Authorize a market contract to interact with the vault.
+    /// Authorize a market contract to move the funds of `market_id`.
     ///
     /// Can be called by admin or the factory contract.
-    pub fn authorize_market(&mut self, market: Address) {
+    pub fn authorize_market(&mut self, market: Address, market_id: u64) {
         let caller = self.env().caller();
         let admin = self.admin.get().unwrap_or_revert(&self.env());
         let factory = self.factory_contract.get();
@@ -252,11 +351,11 @@ impl Vault {
             self.env().revert(Error::AccessDenied);
         }
 
-        if self.authorized_markets.get(&market).unwrap_or(false) {
+        if self.is_market_authorized(market) {
             self.env().revert(Error::MarketAlreadyAuthorized);
         }
 
-        self.authorized_markets.set(&market, true);
+        self.authorized_markets.set(&market, Some(market_id));
 
         self.env().emit_event(MarketAuthorized { market });
     }
@@ -267,11 +366,11 @@ impl Vault {
     pub fn revoke_market(&mut self, market: Address) {
         self.require_admin();
 
-        if !self.authorized_markets.get(&market).unwrap_or(false) {
+        if !self.is_market_authorized(market) {
             self.env().revert(Error::MarketNotAuthorized);
         }
 
-        self.authorized_markets.set(&market, false);
+        self.authorized_markets.set(&market, None);
 
         self.env().emit_event(MarketRevoked { market });
     }
@@ -333,14 +432,46 @@ impl Vault {
         self.total_locked.get_or_default()
     }
 
-    /// Get the total unclaimed platform fees.
+    /// Get the total unclaimed platform fees, swept funds included.
     pub fn get_platform_fees(&self) -> U256 {
         self.platform_fees_collected.get_or_default()
     }
 
+    /// Get the unclaimed fees accrued to a market creator.
+    pub fn get_creator_fees(&self, creator: Address) -> U256 {
+        self.creator_fees.get(&creator).unwrap_or_default()
+    }
+
+    /// Get the lifetime fees collected from a specific market, creator shares included.
+    pub fn get_market_fees(&self, market_id: u64) -> U256 {
+        self.market_fees_collected.get(&market_id).unwrap_or_default()
+    }
+
+    /// Get the lifetime fees collected across all markets.
+    ///
+    /// Unlike `get_platform_fees`, this is not reset when fees are claimed.
+    pub fn get_total_fees_collected(&self) -> U256 {
+        self.total_fees_collected.get_or_default()
+    }
+
+    /// Get the lifetime unclaimable funds swept from a specific market.
+    pub fn get_market_swept_funds(&self, market_id: u64) -> U256 {
+        self.market_swept_funds.get(&market_id).unwrap_or_default()
+    }
+
+    /// Get the lifetime unclaimable funds swept across all markets.
+    pub fn get_total_swept_funds(&self) -> U256 {
+        self.total_swept_funds.get_or_default()
+    }
+
     /// Check if a market is authorized.
     pub fn is_market_authorized(&self, market: Address) -> bool {
-        self.authorized_markets.get(&market).unwrap_or(false)
+        self.get_authorized_market_id(market).is_some()
+    }
+
+    /// Get the market ID a market contract is authorized for, if any.
+    pub fn get_authorized_market_id(&self, market: Address) -> Option<u64> {
+        self.authorized_markets.get(&market).flatten()
     }
 
     /// Check if the vault is paused.
@@ -383,12 +514,12 @@ impl Vault {
         }
     }
 
-    /// Ensure the caller is an authorized market or the factory.
-    fn require_authorized_caller(&self) {
+    /// Ensure the caller is the market authorized for `market_id`, or the factory.
+    fn require_authorized_caller(&self, market_id: u64) {
         let caller = self.env().caller();
         
-        // Check if caller is an authorized market
-        if self.authorized_markets.get(&caller).unwrap_or(false) {
+        // Check if caller is the market authorized for this market ID
+        if self.get_authorized_market_id(caller) == Some(market_id) {
             return;
         }
 
@@ -413,7 +544,8 @@ impl Vault {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use odra::host::{Deployer, HostRef, NoArgs};
+    use odra::casper_types::U512;
+    use odra::host::{Deployer, HostRef};
 
     fn setup() -> (VaultHostRef, odra::host::HostEnv) {
         let env = odra_test::env();
@@ -454,6 +586,79 @@ mod tests {
         assert!(!vault.is_paused());
     }
 
+    #[test]
+    fn test_fees_are_tracked_per_market() {
+        let (mut vault, env) = setup();
+        let fee_recipient = env.get_account(1);
+        vault.with_tokens(U512::from(1_000u64)).deposit(1);
+        vault.with_tokens(U512::from(1_000u64)).deposit(2);
+
+        vault.collect_platform_fees(1, U256::from(30u64));
+        vault.collect_platform_fees(2, U256::from(50u64));
+        vault.collect_platform_fees(1, U256::from(20u64));
+        assert_eq!(vault.get_market_balance(1), U256::from(950u64));
+        assert_eq!(vault.get_market_fees(1), U256::from(50u64));
+        assert_eq!(vault.get_market_fees(2), U256::from(50u64));
+        assert_eq!(vault.get_platform_fees(), U256::from(100u64));
+
+        // Claiming empties the ledger but not the lifetime accounting
+        let balance_before = env.balance_of(&fee_recipient);
+        env.set_caller(fee_recipient);
+        vault.claim_platform_fees();
+        assert_eq!(env.balance_of(&fee_recipient) - balance_before, U512::from(100u64));
+        assert_eq!(vault.get_platform_fees(), U256::zero());
+        assert_eq!(vault.get_total_locked(), U256::from(1_900u64));
+        assert_eq!(vault.get_total_fees_collected(), U256::from(100u64));
+        assert_eq!(vault.get_market_fees(1), U256::from(50u64));
+    }
+
+    #[test]
+    fn test_swept_funds_are_kept_out_of_fee_accounting() {
+        let (mut vault, env) = setup();
+        let fee_recipient = env.get_account(1);
+        vault.with_tokens(U512::from(1_000u64)).deposit(1);
+
+        vault.collect_platform_fees(1, U256::from(30u64));
+        vault.sweep_unclaimable(1, U256::from(70u64));
+        assert_eq!(vault.get_market_fees(1), U256::from(30u64));
+        assert_eq!(vault.get_total_fees_collected(), U256::from(30u64));
+        assert_eq!(vault.get_market_swept_funds(1), U256::from(70u64));
+        assert_eq!(vault.get_market_balance(1), U256::from(900u64));
+
+        // The fee recipient claims both
+        let balance_before = env.balance_of(&fee_recipient);
+        env.set_caller(fee_recipient);
+        vault.claim_platform_fees();
+        assert_eq!(env.balance_of(&fee_recipient) - balance_before, U512::from(100u64));
+        assert_eq!(vault.get_total_swept_funds(), U256::from(70u64));
+    }
+
+    #[test]
+    fn test_creator_fees_accrue_until_claimed() {
+        let (mut vault, env) = setup();
+        let creator = env.get_account(2);
+        vault.with_tokens(U512::from(1_000u64)).deposit(1);
+
+        vault.collect_creator_fees(1, creator, U256::from(40u64));
+        assert!(env.emitted_event(
+            &vault,
+            CreatorFeesCollected { market_id: 1, creator, amount: U256::from(40u64) }
+        ));
+        vault.collect_platform_fees(1, U256::from(60u64));
+        assert_eq!(vault.get_creator_fees(creator), U256::from(40u64));
+        assert_eq!(vault.get_platform_fees(), U256::from(60u64));
+        assert_eq!(vault.get_market_fees(1), U256::from(100u64));
+        assert_eq!(vault.get_market_balance(1), U256::from(900u64));
+
+        let balance_before = env.balance_of(&creator);
+        env.set_caller(creator);
+        vault.claim_creator_fees();
+        assert_eq!(env.balance_of(&creator) - balance_before, U512::from(40u64));
+        assert_eq!(vault.get_creator_fees(creator), U256::zero());
+        assert_eq!(vault.get_total_locked(), U256::from(960u64));
+        assert_eq!(vault.try_claim_creator_fees(), Err(Error::NothingToClaim.into()));
+    }
+
     #[test]
     fn test_authorize_revoke_market() {
         let (mut vault, env) = setup();
@@ -461,10 +666,36 @@ mod tests {
         
         assert!(!vault.is_market_authorized(market));
         
-        vault.authorize_market(market);
+        vault.authorize_market(market, 1);
         assert!(vault.is_market_authorized(market));
+        assert_eq!(vault.get_authorized_market_id(market), Some(1));
         
         vault.revoke_market(market);
         assert!(!vault.is_market_authorized(market));
     }
+
+    #[test]
+    fn test_markets_only_move_their_own_funds() {
+        let (mut vault, env) = setup();
+        let market = env.get_account(2);
+        vault.with_tokens(U512::from(1_000u64)).deposit(1);
+        vault.with_tokens(U512::from(1_000u64)).deposit(2);
+        vault.authorize_market(market, 1);
+
+        env.set_caller(market);
+        vault.withdraw(1, market, U256::from(100u64));
+        assert_eq!(
+            vault.try_withdraw(2, market, U256::from(100u64)),
+            Err(Error::UnauthorizedMarket.into())
+        );
+        assert_eq!(
+            vault.try_collect_platform_fees(2, U256::from(100u64)),
+            Err(Error::UnauthorizedMarket.into())
+        );
+        assert_eq!(
+            vault.try_collect_creator_fees(2, market, U256::from(100u64)),
+            Err(Error::UnauthorizedMarket.into())
+        );
+        assert_eq!(vault.get_market_balance(2), U256::from(1_000u64));
+    }
 }